@@ -1,13 +1,107 @@
+pub use self::aead::AesGcm;
+pub use self::aead::ChaCha20Poly1305;
+pub use self::aead::NONCE_LEN;
+pub use self::aead::TAG_LEN;
+pub use self::aes::Aes;
+pub use self::aes::Mode;
+pub use self::aes::Padding;
+pub use self::alphabet::Alphabet;
+pub use self::ascii85::Ascii85;
+pub use self::ascii85::Z85;
+pub use self::blake2::Blake2;
+pub use self::blake2::Blake2b;
+pub use self::blake2::Blake2s;
+pub use self::blake2::Blake2Stream;
+pub use self::blake3::Blake3;
+pub use self::blake3::Blake3Stream;
+pub use self::checksum::Adler32;
+pub use self::checksum::Adler32Stream;
+pub use self::checksum::Checksum;
+pub use self::checksum::ChecksumStream;
+pub use self::checksum::Fletcher16;
+pub use self::checksum::Fletcher16Stream;
+pub use self::checksum::Fletcher32;
+pub use self::checksum::Fletcher32Stream;
+pub use self::checksum::Framed;
+pub use self::classical_vigenere::ClassicalVigenere;
+pub use self::classical_vigenere::KeySchedule;
+pub use self::classical_vigenere::VigenereVariant;
+pub use self::crc::Crc;
+pub use self::crc::CrcParams;
+pub use self::crc::CrcStream;
+pub use self::encoding::Base16;
+pub use self::encoding::Base32;
+pub use self::encoding::Base32Alphabet;
+pub use self::encoding::Base64;
+pub use self::encoding::Base64Alphabet;
+pub use self::hamming::BlockStatus;
+pub use self::hamming::HammingDecoder;
 pub use self::hamming::HammingECC;
+pub use self::hamming::HammingEncoder;
+pub use self::hamming::HammingReport;
+pub use self::hash::HashFunction;
+pub use self::hill::Hill;
+pub use self::hmac::Hmac224;
+pub use self::hmac::Hmac256;
+pub use self::hmac::Hmac384;
+pub use self::hmac::Hmac512;
+pub use self::hmac::Hmac;
+pub use self::hmac::HmacStream;
+pub use self::interleaver::BlockInterleaver;
+pub use self::interleaver::ConvolutionalInterleaver;
+pub use self::interleaver::Interleaver;
+pub use self::interleaver::NoInterleaver;
+pub use self::interleaver::PseudoRandomInterleaver;
+pub use self::playfair::Playfair;
+pub use self::reed_solomon::CodewordStatus;
+pub use self::reed_solomon::ReedSolomon;
+pub use self::reed_solomon::ReedSolomonReport;
 pub use self::sha2::Sha224;
 pub use self::sha2::Sha256;
 pub use self::sha2::Sha384;
 pub use self::sha2::Sha512;
 pub use self::sha2::Sha512_224;
 pub use self::sha2::Sha512_256;
+pub use self::sha2::Sha2;
+pub use self::sha2::Sha2Stream;
+pub use self::sha3::Sha3_224;
+pub use self::sha3::Sha3_256;
+pub use self::sha3::Sha3_384;
+pub use self::sha3::Sha3_512;
+pub use self::sha3::Sha3;
+pub use self::sha3::Sha3Stream;
+pub use self::sha3::Shake128;
+pub use self::sha3::Shake256;
+pub use self::sha3::Shake;
+pub use self::sha3::ShakeStream;
+pub use self::substitution::Affine;
+pub use self::substitution::Caesar;
+pub use self::substitution::Substitution;
+pub use self::transposition::ColumnarTransposition;
+pub use self::transposition::RailFence;
 pub use self::vigener::Vigener;
+pub use self::vigener::VigenerStream;
 
+mod aead;
+mod aes;
+mod alphabet;
+mod ascii85;
+mod blake2;
+mod blake3;
+mod checksum;
+mod classical_vigenere;
+mod crc;
+mod encoding;
 mod hamming;
+mod hash;
+mod hill;
+mod hmac;
+mod interleaver;
+mod playfair;
+mod reed_solomon;
 mod sha2;
+mod sha3;
+mod substitution;
+mod transposition;
 mod vigener;
 
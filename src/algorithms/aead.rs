@@ -0,0 +1,452 @@
+use std::iter::ExactSizeIterator;
+use std::iter::FromIterator;
+use std::iter::IntoIterator;
+
+use aes_gcm::aead::consts::U12;
+use aes_gcm::aead::Aead;
+use aes_gcm::aead::KeyInit;
+use aes_gcm::aead::Payload;
+use rand::Rng;
+
+use crate::decrypt::TryDecrypt;
+use crate::encrypt::TryEncrypt;
+use crate::error::Error;
+use crate::Key;
+
+/// Length of the nonce in bytes used by [`AesGcm`] and [`ChaCha20Poly1305`].
+pub const NONCE_LEN: usize = 12;
+
+/// Length of the authentication tag in bytes appended by [`AesGcm`] and
+/// [`ChaCha20Poly1305`].
+pub const TAG_LEN: usize = 16;
+
+/// Maximal length of data in bytes [`AesGcm`] can encrypt, e.g.
+/// 2<sup>36</sup> &ndash; 32 bytes.
+const GCM_MAX_LEN: u64 = (1 << 36) - 32;
+
+/// Maximal length of data in bytes [`ChaCha20Poly1305`] can encrypt, e.g.
+/// 2<sup>38</sup> &ndash; 64 bytes.
+const CHACHA_MAX_LEN: u64 = (1 << 38) - 64;
+
+/// AES in Galois/counter mode, an authenticated encryption with associated data
+/// (AEAD) as specified by NIST SP 800-38D, with 128, 192 or 256-bit key.
+///
+/// # Encryption and decryption
+///
+/// Encryption appends [`TAG_LEN`]-byte authentication tag to the encrypted data.
+/// The tag covers both the encrypted data and the associated data, which are
+/// authenticated but not encrypted. Decryption checks the tag and fails with
+/// [`Error::AuthenticationFailed`] if the data, the associated data, the nonce
+/// or the key do not match.
+///
+/// The same nonce must never be used twice with the same key, otherwise both
+/// confidentiality and authenticity are lost. Therefore, [`TryEncrypt`]
+/// implementation generates a random nonce for each encryption and writes it
+/// before the encrypted data, where [`TryDecrypt`] implementation reads it from.
+/// If the nonce is stored elsewhere, use [`seal()`] and [`open()`] instead.
+///
+/// [`TryEncrypt`]: crate::TryEncrypt
+/// [`TryDecrypt`]: crate::TryDecrypt
+/// [`seal()`]: AesGcm::seal
+/// [`open()`]: AesGcm::open
+pub struct AesGcm {
+    cipher: GcmCipher,
+    associated_data: Vec<u8>,
+}
+
+impl AesGcm {
+
+    /// Creates a new [`AesGcm`] instance.
+    ///
+    /// # Parameters
+    ///
+    /// - `key`: [`Key`] of length 16, 24 or 32 bytes for AES-128, AES-192 or
+    ///   AES-256 respectively
+    /// - `associated_data`: data which are authenticated, but not encrypted, such
+    ///   as a header; can be empty
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with a new instance
+    /// - [`Err`] with [`Error::InvalidKey`] if the key has invalid length
+    pub fn new(key: &Key, associated_data: &[u8]) -> Result<Self, Error> {
+        let cipher = match key.len() {
+            16 => GcmCipher::Aes128(aes_gcm::Aes128Gcm::new_from_slice(&key[..]).unwrap()),
+            24 => GcmCipher::Aes192(aes_gcm::AesGcm::new_from_slice(&key[..]).unwrap()),
+            32 => GcmCipher::Aes256(aes_gcm::Aes256Gcm::new_from_slice(&key[..]).unwrap()),
+            len => return Err(Error::InvalidKey {
+                reason: format!("expected 16, 24 or 32 bytes for AES-GCM but {} were given", len)
+            })
+        };
+        Ok(Self { cipher, associated_data: associated_data.to_vec() })
+    }
+
+    /// Generates a random nonce.
+    ///
+    /// # Parameters
+    ///
+    /// - `rng`: random number generator
+    pub fn random_nonce<R: Rng + ?Sized>(rng: &mut R) -> [u8; NONCE_LEN] {
+        random_nonce(rng)
+    }
+
+    /// Returns the associated data.
+    pub fn associated_data(&self) -> &[u8] {
+        &self.associated_data
+    }
+
+    /// Encrypts data with a nonce chosen by the caller, who stores the nonce
+    /// elsewhere. The instance is consumed, so that it cannot encrypt other data
+    /// with the same nonce.
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with encrypted data followed by the tag, without the nonce
+    /// - [`Err`] with [`Error::InputTooLong`] if the data are too long
+    pub fn seal(self, nonce: [u8; NONCE_LEN], data: &[u8]) -> Result<Vec<u8>, Error> {
+        self.encrypt_with(nonce, data)
+    }
+
+    /// Decrypts data encrypted by [`seal()`](AesGcm::seal) with given nonce.
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with decrypted data
+    /// - [`Err`] with [`Error::AuthenticationFailed`] if the tag does not match
+    pub fn open(&self, nonce: [u8; NONCE_LEN], encrypted_data: &[u8]) -> Result<Vec<u8>, Error> {
+        let payload = Payload { msg: encrypted_data, aad: &self.associated_data };
+        let nonce = (&nonce).into();
+        let decrypted = match &self.cipher {
+            GcmCipher::Aes128(cipher) => cipher.decrypt(nonce, payload),
+            GcmCipher::Aes192(cipher) => cipher.decrypt(nonce, payload),
+            GcmCipher::Aes256(cipher) => cipher.decrypt(nonce, payload),
+        };
+        decrypted.map_err(|_| Error::AuthenticationFailed)
+    }
+
+    fn encrypt_with(&self, nonce: [u8; NONCE_LEN], data: &[u8]) -> Result<Vec<u8>, Error> {
+        let payload = Payload { msg: data, aad: &self.associated_data };
+        let nonce = (&nonce).into();
+        let encrypted = match &self.cipher {
+            GcmCipher::Aes128(cipher) => cipher.encrypt(nonce, payload),
+            GcmCipher::Aes192(cipher) => cipher.encrypt(nonce, payload),
+            GcmCipher::Aes256(cipher) => cipher.encrypt(nonce, payload),
+        };
+        encrypted.map_err(|_| too_long(GCM_MAX_LEN, data.len()))
+    }
+}
+
+impl TryEncrypt for AesGcm {
+
+    /// Error type to be returned when data are too long to be encrypted.
+    type ErrorType = Error;
+
+    /// Encrypts data with a new random nonce, which is written before the
+    /// encrypted data.
+    fn try_encrypt<D, E>(
+        &self,
+        data_to_encrypt: D
+    ) -> Result<E, Self::ErrorType> where
+        D:           IntoIterator<Item = u8>,
+        D::IntoIter: ExactSizeIterator,
+        E:           FromIterator<u8> {
+
+        let data: Vec<u8> = data_to_encrypt.into_iter().collect();
+        let nonce = random_nonce(&mut rand::thread_rng());
+        let encrypted = self.encrypt_with(nonce, &data)?;
+        Ok(nonce.into_iter().chain(encrypted).collect())
+    }
+}
+
+impl TryDecrypt for AesGcm {
+
+    /// Error type to be returned when authentication fails.
+    type ErrorType = Error;
+
+    /// Decrypts data preceded by their nonce.
+    fn try_decrypt<E, D>(
+        &self,
+        encrypted_data: E
+    ) -> Result<D, Self::ErrorType> where
+        E:           IntoIterator<Item = u8>,
+        E::IntoIter: ExactSizeIterator,
+        D:           FromIterator<u8> {
+
+        let data: Vec<u8> = encrypted_data.into_iter().collect();
+        let (nonce, encrypted) = split_nonce(&data)?;
+        Ok(self.open(nonce, encrypted)?.into_iter().collect())
+    }
+}
+
+/// ChaCha20 stream cipher with Poly1305 authenticator, an authenticated
+/// encryption with associated data (AEAD) as specified by RFC 8439, with 256-bit
+/// key.
+///
+/// # Encryption and decryption
+///
+/// Encryption appends [`TAG_LEN`]-byte authentication tag to the encrypted data.
+/// The tag covers both the encrypted data and the associated data, which are
+/// authenticated but not encrypted. Decryption checks the tag and fails with
+/// [`Error::AuthenticationFailed`] if the data, the associated data, the nonce
+/// or the key do not match.
+///
+/// The nonce is handled in the same way as by [`AesGcm`]: [`TryEncrypt`]
+/// implementation writes a random nonce before the encrypted data, [`seal()`]
+/// and [`open()`] take the nonce from the caller.
+///
+/// [`TryEncrypt`]: crate::TryEncrypt
+/// [`seal()`]: ChaCha20Poly1305::seal
+/// [`open()`]: ChaCha20Poly1305::open
+pub struct ChaCha20Poly1305 {
+    cipher: chacha20poly1305::ChaCha20Poly1305,
+    associated_data: Vec<u8>,
+}
+
+impl ChaCha20Poly1305 {
+
+    /// Creates a new [`ChaCha20Poly1305`] instance.
+    ///
+    /// # Parameters
+    ///
+    /// - `key`: [`Key`] of length 32 bytes
+    /// - `associated_data`: data which are authenticated, but not encrypted, such
+    ///   as a header; can be empty
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with a new instance
+    /// - [`Err`] with [`Error::InvalidKey`] if the key has invalid length
+    pub fn new(key: &Key, associated_data: &[u8]) -> Result<Self, Error> {
+        let cipher = chacha20poly1305::ChaCha20Poly1305::new_from_slice(&key[..])
+            .map_err(|_| Error::InvalidKey {
+                reason: format!("expected 32 bytes for ChaCha20 but {} were given", key.len())
+            })?;
+        Ok(Self { cipher, associated_data: associated_data.to_vec() })
+    }
+
+    /// Generates a random nonce.
+    ///
+    /// # Parameters
+    ///
+    /// - `rng`: random number generator
+    pub fn random_nonce<R: Rng + ?Sized>(rng: &mut R) -> [u8; NONCE_LEN] {
+        random_nonce(rng)
+    }
+
+    /// Returns the associated data.
+    pub fn associated_data(&self) -> &[u8] {
+        &self.associated_data
+    }
+
+    /// Encrypts data with a nonce chosen by the caller, who stores the nonce
+    /// elsewhere. The instance is consumed, so that it cannot encrypt other data
+    /// with the same nonce.
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with encrypted data followed by the tag, without the nonce
+    /// - [`Err`] with [`Error::InputTooLong`] if the data are too long
+    pub fn seal(self, nonce: [u8; NONCE_LEN], data: &[u8]) -> Result<Vec<u8>, Error> {
+        self.encrypt_with(nonce, data)
+    }
+
+    /// Decrypts data encrypted by [`seal()`](ChaCha20Poly1305::seal) with given
+    /// nonce.
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with decrypted data
+    /// - [`Err`] with [`Error::AuthenticationFailed`] if the tag does not match
+    pub fn open(&self, nonce: [u8; NONCE_LEN], encrypted_data: &[u8]) -> Result<Vec<u8>, Error> {
+        let payload = Payload { msg: encrypted_data, aad: &self.associated_data };
+        self.cipher.decrypt((&nonce).into(), payload)
+            .map_err(|_| Error::AuthenticationFailed)
+    }
+
+    fn encrypt_with(&self, nonce: [u8; NONCE_LEN], data: &[u8]) -> Result<Vec<u8>, Error> {
+        let payload = Payload { msg: data, aad: &self.associated_data };
+        self.cipher.encrypt((&nonce).into(), payload)
+            .map_err(|_| too_long(CHACHA_MAX_LEN, data.len()))
+    }
+}
+
+impl TryEncrypt for ChaCha20Poly1305 {
+
+    /// Error type to be returned when data are too long to be encrypted.
+    type ErrorType = Error;
+
+    /// Encrypts data with a new random nonce, which is written before the
+    /// encrypted data.
+    fn try_encrypt<D, E>(
+        &self,
+        data_to_encrypt: D
+    ) -> Result<E, Self::ErrorType> where
+        D:           IntoIterator<Item = u8>,
+        D::IntoIter: ExactSizeIterator,
+        E:           FromIterator<u8> {
+
+        let data: Vec<u8> = data_to_encrypt.into_iter().collect();
+        let nonce = random_nonce(&mut rand::thread_rng());
+        let encrypted = self.encrypt_with(nonce, &data)?;
+        Ok(nonce.into_iter().chain(encrypted).collect())
+    }
+}
+
+impl TryDecrypt for ChaCha20Poly1305 {
+
+    /// Error type to be returned when authentication fails.
+    type ErrorType = Error;
+
+    /// Decrypts data preceded by their nonce.
+    fn try_decrypt<E, D>(
+        &self,
+        encrypted_data: E
+    ) -> Result<D, Self::ErrorType> where
+        E:           IntoIterator<Item = u8>,
+        E::IntoIter: ExactSizeIterator,
+        D:           FromIterator<u8> {
+
+        let data: Vec<u8> = encrypted_data.into_iter().collect();
+        let (nonce, encrypted) = split_nonce(&data)?;
+        Ok(self.open(nonce, encrypted)?.into_iter().collect())
+    }
+}
+
+/// AES-GCM with one of supported key sizes.
+enum GcmCipher {
+    Aes128(aes_gcm::Aes128Gcm),
+    Aes192(aes_gcm::AesGcm<aes::Aes192, U12>),
+    Aes256(aes_gcm::Aes256Gcm),
+}
+
+fn random_nonce<R: Rng + ?Sized>(rng: &mut R) -> [u8; NONCE_LEN] {
+    let mut nonce = [0; NONCE_LEN];
+    rng.fill_bytes(&mut nonce);
+    nonce
+}
+
+/// Splits encrypted data into the nonce written before them and the rest.
+fn split_nonce(data: &[u8]) -> Result<([u8; NONCE_LEN], &[u8]), Error> {
+    if data.len() < NONCE_LEN {
+        return Err(Error::AuthenticationFailed)
+    }
+    let (nonce, encrypted) = data.split_at(NONCE_LEN);
+    Ok((nonce.try_into().unwrap(), encrypted))
+}
+
+/// Returns [`Error::InputTooLong`] for data longer than `max` bytes, which may
+/// not fit into `usize`.
+fn too_long(max: u64, actual: usize) -> Error {
+    Error::InputTooLong { max: usize::try_from(max).unwrap_or(usize::MAX), actual }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::hex;
+
+    #[test]
+    fn aes_gcm_known_answers() {
+        // Test cases 1 and 2 from "The Galois/Counter Mode of Operation (GCM)" by
+        // McGrew and Viega:
+        let aes_gcm = || AesGcm::new(&Key::new(vec![0; 16]), &[]).unwrap();
+        let encrypted = aes_gcm().seal([0; NONCE_LEN], &[]).unwrap();
+        assert_eq!(encrypted, hex("58e2fccefa7e3061367f1d57a4e7455a"));
+        let encrypted = aes_gcm().seal([0; NONCE_LEN], &[0; 16]).unwrap();
+        assert_eq!(encrypted, hex(concat!(
+            "0388dace60b6a392f328c2b971b2fe78",
+            "ab6e47d42cec13bdf53a67b21257bddf"
+        )));
+        let decrypted = aes_gcm().open([0; NONCE_LEN], &encrypted).unwrap();
+        assert_eq!(decrypted, vec![0; 16]);
+    }
+
+    #[test]
+    fn chacha20_poly1305_known_answer() {
+        // Test vector from RFC 8439, section 2.8.2:
+        let key = Key::new((0x80..=0x9f).collect());
+        let nonce = hex("070000004041424344454647").try_into().unwrap();
+        let associated_data = hex("50515253c0c1c2c3c4c5c6c7");
+        let plaintext = b"Ladies and Gentlemen of the class of '99: If I could offer you only \
+            one tip for the future, sunscreen would be it.".to_vec();
+        let chacha = || ChaCha20Poly1305::new(&key, &associated_data).unwrap();
+
+        let encrypted = chacha().seal(nonce, &plaintext).unwrap();
+        assert_eq!(encrypted.len(), plaintext.len() + TAG_LEN);
+        assert_eq!(encrypted[..16], hex("d31a8d34648e60db7b86afbc53ef7ec2"));
+        assert_eq!(encrypted[plaintext.len()..], hex("1ae10b594f09e26a7e902ecbd0600691"));
+        let decrypted = chacha().open(nonce, &encrypted).unwrap();
+        assert_eq!(decrypted, plaintext);
+    }
+
+    #[test]
+    fn fresh_nonce() {
+        let data = b"same message".to_vec();
+        let aes_gcm = AesGcm::new(&Key::new(vec![1; 32]), b"header").unwrap();
+        let chacha = ChaCha20Poly1305::new(&Key::new(vec![1; 32]), b"header").unwrap();
+        let encrypted: [Vec<u8>; 4] = [
+            aes_gcm.try_encrypt(data.clone()).unwrap(),
+            aes_gcm.try_encrypt(data.clone()).unwrap(),
+            chacha.try_encrypt(data.clone()).unwrap(),
+            chacha.try_encrypt(data.clone()).unwrap(),
+        ];
+        for pair in encrypted.chunks(2) {
+            assert_eq!(pair[0].len(), NONCE_LEN + data.len() + TAG_LEN);
+            // Each encryption has its own nonce, so the key stream is not reused:
+            assert_ne!(pair[0][..NONCE_LEN], pair[1][..NONCE_LEN]);
+            assert_ne!(pair[0][NONCE_LEN..], pair[1][NONCE_LEN..]);
+        }
+        for encrypted in &encrypted[..2] {
+            let decrypted: Vec<u8> = aes_gcm.try_decrypt(encrypted.clone()).unwrap();
+            assert_eq!(decrypted, data);
+        }
+        for encrypted in &encrypted[2..] {
+            let decrypted: Vec<u8> = chacha.try_decrypt(encrypted.clone()).unwrap();
+            assert_eq!(decrypted, data);
+        }
+        // The nonce is stored in the data:
+        let (nonce, sealed) = encrypted[0].split_at(NONCE_LEN);
+        let decrypted = aes_gcm.open(nonce.try_into().unwrap(), sealed).unwrap();
+        assert_eq!(decrypted, data);
+    }
+
+    #[test]
+    fn tampering_detected() {
+        let data = b"attack at dawn".to_vec();
+        for key_len in [16, 24, 32] {
+            let key = Key::new(vec![7; key_len]);
+            let aes_gcm = AesGcm::new(&key, b"header").unwrap();
+            let mut encrypted: Vec<u8> = aes_gcm.try_encrypt(data.clone()).unwrap();
+
+            // Wrong associated data:
+            let other = AesGcm::new(&key, b"HEADER").unwrap();
+            let result: Result<Vec<u8>, _> = other.try_decrypt(encrypted.clone());
+            assert!(matches!(result.unwrap_err(), Error::AuthenticationFailed));
+            // Modified data:
+            encrypted[NONCE_LEN + 3] ^= 0x20;
+            let result: Result<Vec<u8>, _> = aes_gcm.try_decrypt(encrypted);
+            assert!(matches!(result.unwrap_err(), Error::AuthenticationFailed));
+        }
+
+        let key = Key::new(vec![7; 32]);
+        let chacha = ChaCha20Poly1305::new(&key, b"header").unwrap();
+        let mut encrypted: Vec<u8> = chacha.try_encrypt(data.clone()).unwrap();
+        // Truncated data:
+        for len in [5, NONCE_LEN + 10] {
+            let result: Result<Vec<u8>, _> = chacha.try_decrypt(encrypted[..len].to_vec());
+            assert!(matches!(result.unwrap_err(), Error::AuthenticationFailed));
+        }
+        // Wrong nonce:
+        encrypted[0] ^= 1;
+        let result: Result<Vec<u8>, _> = chacha.try_decrypt(encrypted);
+        assert!(matches!(result.unwrap_err(), Error::AuthenticationFailed));
+    }
+
+    #[test]
+    fn invalid_key() {
+        let result = AesGcm::new(&Key::new(vec![0; 20]), &[]);
+        assert!(matches!(result, Err(Error::InvalidKey { .. })));
+        let result = ChaCha20Poly1305::new(&Key::new(vec![0; 16]), &[]);
+        assert!(matches!(result, Err(Error::InvalidKey { .. })));
+    }
+}
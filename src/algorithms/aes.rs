@@ -0,0 +1,465 @@
+use std::iter::ExactSizeIterator;
+use std::iter::FromIterator;
+use std::iter::IntoIterator;
+
+use aes::cipher::generic_array::GenericArray;
+use aes::cipher::BlockDecrypt;
+use aes::cipher::BlockEncrypt;
+use aes::cipher::KeyInit;
+use rand::Rng;
+
+use crate::decrypt::TryDecrypt;
+use crate::encrypt::TryEncrypt;
+use crate::error::Error;
+use crate::Key;
+
+/// Size of AES block in bytes.
+const BLOCK_LEN: usize = 16;
+
+/// A single AES block.
+type Block = [u8; BLOCK_LEN];
+
+/// Mode of operation of [`Aes`], e.g. the way how the data are split into blocks
+/// and how the blocks are chained. Modes are specified by NIST SP 800-38A.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+
+    /// Electronic codebook: each block is encrypted separately. Equal blocks of
+    /// data give equal blocks of encrypted data, so this mode should be used for
+    /// test vectors only.
+    Ecb,
+
+    /// Cipher block chaining: each block of data is XORed with the previous
+    /// encrypted block (or with the initialization vector) before encryption.
+    /// The initialization vector must be unpredictable, see [`Aes::random_iv`].
+    Cbc {
+        /// Initialization vector.
+        iv: [u8; BLOCK_LEN],
+    },
+
+    /// Counter: a stream cipher XORing the data with encrypted values of a
+    /// counter, which is incremented as a 128-bit big-endian number. The initial
+    /// counter block must never be used twice with the same key.
+    Ctr {
+        /// Initial counter block.
+        nonce: [u8; BLOCK_LEN],
+    },
+
+    /// Cipher feedback (CFB128): a stream cipher XORing the data with encrypted
+    /// previous encrypted block (or the initialization vector). The
+    /// initialization vector must be unpredictable, see [`Aes::random_iv`].
+    Cfb {
+        /// Initialization vector.
+        iv: [u8; BLOCK_LEN],
+    },
+}
+
+/// Padding of the data used by [`Aes`] in [`Mode::Ecb`] and [`Mode::Cbc`], which
+/// can encrypt whole blocks only. Other modes do not use padding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Padding {
+
+    /// PKCS#7 padding: 1 to 16 bytes are appended, each of them having value
+    /// equal to the number of appended bytes.
+    Pkcs7,
+
+    /// No padding: length of the data must be a multiple of 16 bytes.
+    None,
+}
+
+/// AES block cipher as specified by FIPS-197 with 128, 192 or 256-bit key,
+/// together with a [`Mode`] of operation. Key size is given by length of the
+/// [`Key`]. [`Mode::Ecb`] and [`Mode::Cbc`] use [`Padding::Pkcs7`] by default,
+/// use [`with_padding`] to change it.
+///
+/// # Encryption and decryption
+///
+/// This struct implements [`TryEncrypt`] and [`TryDecrypt`] traits. Encryption
+/// fails only if [`Padding::None`] is used and the data are not made of whole
+/// blocks. Decryption fails if the data are not made of whole blocks or the
+/// padding is invalid. Note that AES itself does not detect modified data. Use an
+/// authenticated mode for that.
+///
+/// [`with_padding`]: Aes::with_padding
+/// [`TryEncrypt`]: crate::TryEncrypt
+/// [`TryDecrypt`]: crate::TryDecrypt
+pub struct Aes {
+    cipher: BlockCipher,
+    mode: Mode,
+    padding: Padding,
+}
+
+impl Aes {
+
+    /// Creates a new [`Aes`] instance.
+    ///
+    /// # Parameters
+    ///
+    /// - `key`: [`Key`] of length 16, 24 or 32 bytes for AES-128, AES-192 or
+    ///   AES-256 respectively
+    /// - `mode`: mode of operation
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with a new instance
+    /// - [`Err`] with [`Error::InvalidKey`] if the key has invalid length
+    pub fn new(key: &Key, mode: Mode) -> Result<Self, Error> {
+        let cipher = match key.len() {
+            16 => BlockCipher::Aes128(aes::Aes128::new(GenericArray::from_slice(&key[..]))),
+            24 => BlockCipher::Aes192(aes::Aes192::new(GenericArray::from_slice(&key[..]))),
+            32 => BlockCipher::Aes256(aes::Aes256::new(GenericArray::from_slice(&key[..]))),
+            len => return Err(Error::InvalidKey {
+                reason: format!("expected 16, 24 or 32 bytes for AES but {} were given", len)
+            })
+        };
+        Ok(Self { cipher, mode, padding: Padding::Pkcs7 })
+    }
+
+    /// Returns the instance with given padding. Padding is used in [`Mode::Ecb`]
+    /// and [`Mode::Cbc`] only.
+    pub fn with_padding(self, padding: Padding) -> Self {
+        Self { padding, ..self }
+    }
+
+    /// Returns mode of operation.
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// Returns padding used in [`Mode::Ecb`] and [`Mode::Cbc`].
+    pub fn padding(&self) -> Padding {
+        self.padding
+    }
+
+    /// Generates a random initialization vector or initial counter block.
+    ///
+    /// # Parameters
+    ///
+    /// - `rng`: random number generator
+    pub fn random_iv<R: Rng + ?Sized>(rng: &mut R) -> [u8; BLOCK_LEN] {
+        let mut iv = [0; BLOCK_LEN];
+        rng.fill_bytes(&mut iv);
+        iv
+    }
+
+    /// Appends padding to the data, or checks that the data are made of whole
+    /// blocks if no padding is used.
+    fn pad(&self, data: &mut Vec<u8>) -> Result<(), Error> {
+        match self.padding {
+            Padding::Pkcs7 => {
+                let pad_len = BLOCK_LEN - data.len() % BLOCK_LEN;
+                data.resize(data.len() + pad_len, pad_len as u8);
+                Ok(())
+            },
+            Padding::None => check_whole_blocks(data)
+        }
+    }
+
+    /// Removes padding from decrypted data.
+    fn unpad(&self, data: &mut Vec<u8>) -> Result<(), Error> {
+        if self.padding == Padding::None {
+            return Ok(())
+        }
+        // Value of the last byte is number of padding bytes, all of them must have
+        // the same value:
+        let pad_len = data.last().copied().unwrap_or(0) as usize;
+        let valid = (1..=BLOCK_LEN).contains(&pad_len)
+            && data[data.len() - pad_len..].iter().all(|&byte| byte as usize == pad_len);
+        if !valid {
+            return Err(Error::malformed("invalid PKCS#7 padding"))
+        }
+        data.truncate(data.len() - pad_len);
+        Ok(())
+    }
+}
+
+impl TryEncrypt for Aes {
+
+    /// Error type to be returned when data cannot be encrypted, e.g. when no
+    /// padding is used and the data are not made of whole blocks.
+    type ErrorType = Error;
+
+    fn try_encrypt<D, E>(
+        &self,
+        data_to_encrypt: D
+    ) -> Result<E, Self::ErrorType> where
+        D:           IntoIterator<Item = u8>,
+        D::IntoIter: ExactSizeIterator,
+        E:           FromIterator<u8> {
+
+        let mut data: Vec<u8> = data_to_encrypt.into_iter().collect();
+        match self.mode {
+            Mode::Ecb => {
+                self.pad(&mut data)?;
+                for chunk in data.chunks_exact_mut(BLOCK_LEN) {
+                    self.cipher.encrypt(chunk);
+                }
+            },
+            Mode::Cbc { iv } => {
+                self.pad(&mut data)?;
+                // Each block is XORed with the previous encrypted block:
+                let mut previous = iv;
+                for chunk in data.chunks_exact_mut(BLOCK_LEN) {
+                    xor(chunk, &previous);
+                    self.cipher.encrypt(chunk);
+                    previous.copy_from_slice(chunk);
+                }
+            },
+            Mode::Ctr { nonce } => self.apply_ctr(nonce, &mut data),
+            Mode::Cfb { iv } => {
+                // Encrypt the previous encrypted block and XOR it with the data.
+                // The last block can be partial:
+                let mut previous = iv;
+                for chunk in data.chunks_mut(BLOCK_LEN) {
+                    self.cipher.encrypt(&mut previous);
+                    xor(chunk, &previous);
+                    previous[..chunk.len()].copy_from_slice(chunk);
+                }
+            },
+        }
+        Ok(data.into_iter().collect())
+    }
+}
+
+impl TryDecrypt for Aes {
+
+    /// Error type to be returned when data cannot be decrypted, e.g. when they
+    /// are not made of whole blocks or the padding is invalid.
+    type ErrorType = Error;
+
+    fn try_decrypt<E, D>(
+        &self,
+        encrypted_data: E
+    ) -> Result<D, Self::ErrorType> where
+        E:           IntoIterator<Item = u8>,
+        E::IntoIter: ExactSizeIterator,
+        D:           FromIterator<u8> {
+
+        let mut data: Vec<u8> = encrypted_data.into_iter().collect();
+        match self.mode {
+            Mode::Ecb => {
+                check_whole_blocks(&data)?;
+                for chunk in data.chunks_exact_mut(BLOCK_LEN) {
+                    self.cipher.decrypt(chunk);
+                }
+                self.unpad(&mut data)?;
+            },
+            Mode::Cbc { iv } => {
+                check_whole_blocks(&data)?;
+                let mut previous = iv;
+                for chunk in data.chunks_exact_mut(BLOCK_LEN) {
+                    let encrypted: Block = chunk.try_into().unwrap();
+                    self.cipher.decrypt(chunk);
+                    xor(chunk, &previous);
+                    previous = encrypted;
+                }
+                self.unpad(&mut data)?;
+            },
+            Mode::Ctr { nonce } => self.apply_ctr(nonce, &mut data),
+            Mode::Cfb { iv } => {
+                let mut previous = iv;
+                for chunk in data.chunks_mut(BLOCK_LEN) {
+                    let mut keystream = previous;
+                    self.cipher.encrypt(&mut keystream);
+                    previous[..chunk.len()].copy_from_slice(chunk);
+                    xor(chunk, &keystream);
+                }
+            },
+        }
+        Ok(data.into_iter().collect())
+    }
+}
+
+impl Aes {
+
+    /// XORs the data with the key stream of counter mode. Encryption and
+    /// decryption are the same operation.
+    fn apply_ctr(&self, nonce: Block, data: &mut [u8]) {
+        let mut counter = u128::from_be_bytes(nonce);
+        for chunk in data.chunks_mut(BLOCK_LEN) {
+            let mut keystream = counter.to_be_bytes();
+            self.cipher.encrypt(&mut keystream);
+            xor(chunk, &keystream);
+            counter = counter.wrapping_add(1);
+        }
+    }
+}
+
+/// AES block cipher with one of supported key sizes.
+enum BlockCipher {
+    Aes128(aes::Aes128),
+    Aes192(aes::Aes192),
+    Aes256(aes::Aes256),
+}
+
+impl BlockCipher {
+
+    /// Encrypts a single block in place.
+    fn encrypt(&self, block: &mut [u8]) {
+        let block = GenericArray::from_mut_slice(block);
+        match self {
+            Self::Aes128(cipher) => cipher.encrypt_block(block),
+            Self::Aes192(cipher) => cipher.encrypt_block(block),
+            Self::Aes256(cipher) => cipher.encrypt_block(block),
+        }
+    }
+
+    /// Decrypts a single block in place.
+    fn decrypt(&self, block: &mut [u8]) {
+        let block = GenericArray::from_mut_slice(block);
+        match self {
+            Self::Aes128(cipher) => cipher.decrypt_block(block),
+            Self::Aes192(cipher) => cipher.decrypt_block(block),
+            Self::Aes256(cipher) => cipher.decrypt_block(block),
+        }
+    }
+}
+
+/// Checks that the data are made of whole blocks.
+fn check_whole_blocks(data: &[u8]) -> Result<(), Error> {
+    match data.len() % BLOCK_LEN {
+        0 => Ok(()),
+        _ => Err(Error::malformed(format!(
+            "expected whole number of {}-byte blocks but {} bytes were given",
+            BLOCK_LEN,
+            data.len()
+        )))
+    }
+}
+
+/// XORs `data` with the beginning of `mask` in place.
+fn xor(data: &mut [u8], mask: &[u8]) {
+    for (byte, mask) in data.iter_mut().zip(mask) {
+        *byte ^= mask;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::hex;
+
+    fn block(text: &str) -> Block {
+        hex(text).try_into().unwrap()
+    }
+
+    /// Key from NIST SP 800-38A, appendix F.
+    const SP800_38A_KEY: &str = "2b7e151628aed2a6abf7158809cf4f3c";
+
+    /// Plaintext from NIST SP 800-38A, appendix F.
+    const SP800_38A_PLAINTEXT: &str = concat!(
+        "6bc1bee22e409f96e93d7e117393172a",
+        "ae2d8a571e03ac9c9eb76fac45af8e51",
+        "30c81c46a35ce411e5fbc1191a0a52ef",
+        "f69f2445df4f9b17ad2b417be66c3710"
+    );
+
+    fn check(aes: Aes, plaintext: &str, ciphertext: &str) {
+        let encrypted: Vec<u8> = aes.try_encrypt(hex(plaintext)).unwrap();
+        assert_eq!(encrypted, hex(ciphertext));
+        let decrypted: Vec<u8> = aes.try_decrypt(encrypted).unwrap();
+        assert_eq!(decrypted, hex(plaintext));
+    }
+
+    #[test]
+    fn fips_197() {
+        let test_cases = vec![
+            ("000102030405060708090a0b0c0d0e0f", "69c4e0d86a7b0430d8cdb78070b4c55a"),
+            ("000102030405060708090a0b0c0d0e0f1011121314151617",
+                "dda97ca4864cdfe06eaf70a0ec0d7191"),
+            ("000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
+                "8ea2b7ca516745bfeafc49904b496089"),
+        ];
+
+        for (key, ciphertext) in test_cases {
+            let aes = Aes::new(&Key::new(hex(key)), Mode::Ecb).unwrap()
+                .with_padding(Padding::None);
+            check(aes, "00112233445566778899aabbccddeeff", ciphertext);
+        }
+    }
+
+    #[test]
+    fn sp800_38a() {
+        let key = Key::new(hex(SP800_38A_KEY));
+        let iv = block("000102030405060708090a0b0c0d0e0f");
+        let test_cases = vec![
+            (Mode::Ecb, concat!(
+                "3ad77bb40d7a3660a89ecaf32466ef97",
+                "f5d3d58503b9699de785895a96fdbaaf",
+                "43b1cd7f598ece23881b00e3ed030688",
+                "7b0c785e27e8ad3f8223207104725dd4"
+            )),
+            (Mode::Cbc { iv }, concat!(
+                "7649abac8119b246cee98e9b12e9197d",
+                "5086cb9b507219ee95db113a917678b2",
+                "73bed6b8e3c1743b7116e69e22229516",
+                "3ff1caa1681fac09120eca307586e1a7"
+            )),
+            (Mode::Cfb { iv }, concat!(
+                "3b3fd92eb72dad20333449f8e83cfb4a",
+                "c8a64537a0b3a93fcde3cdad9f1ce58b",
+                "26751f67a3cbb140b1808cf187a4f4df",
+                "c04b05357c5d1c0eeac4c66f9ff7f2e6"
+            )),
+            (Mode::Ctr { nonce: block("f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff") }, concat!(
+                "874d6191b620e3261bef6864990db6ce",
+                "9806f66b7970fdff8617187bb9fffdff",
+                "5ae4df3edbd5d35e5b4f09020db03eab",
+                "1e031dda2fbe03d1792170a0f3009cee"
+            )),
+        ];
+
+        for (mode, ciphertext) in test_cases {
+            let aes = Aes::new(&key, mode).unwrap().with_padding(Padding::None);
+            check(aes, SP800_38A_PLAINTEXT, ciphertext);
+        }
+    }
+
+    #[test]
+    fn pkcs7() {
+        let key = Key::new(hex(SP800_38A_KEY));
+        let aes = Aes::new(&key, Mode::Cbc { iv: [7; BLOCK_LEN] }).unwrap();
+        for len in [0, 1, 15, 16, 17, 100] {
+            let data = vec![0x5a; len];
+            let encrypted: Vec<u8> = aes.try_encrypt(data.clone()).unwrap();
+            assert_eq!(encrypted.len(), (len / BLOCK_LEN + 1) * BLOCK_LEN);
+            let decrypted: Vec<u8> = aes.try_decrypt(encrypted).unwrap();
+            assert_eq!(decrypted, data);
+        }
+
+        // Without padding, the data must be made of whole blocks:
+        let aes = aes.with_padding(Padding::None);
+        let result: Result<Vec<u8>, _> = aes.try_encrypt(vec![0; 17]);
+        assert!(matches!(result.unwrap_err(), Error::Malformed { .. }));
+    }
+
+    #[test]
+    fn stream_modes_partial_block() {
+        let key = Key::new(hex(SP800_38A_KEY));
+        let data = b"stream modes do not need padding".repeat(3)[..77].to_vec();
+        for mode in [Mode::Ctr { nonce: [0xff; BLOCK_LEN] }, Mode::Cfb { iv: [1; BLOCK_LEN] }] {
+            let aes = Aes::new(&key, mode).unwrap();
+            let encrypted: Vec<u8> = aes.try_encrypt(data.clone()).unwrap();
+            assert_eq!(encrypted.len(), data.len());
+            let decrypted: Vec<u8> = aes.try_decrypt(encrypted).unwrap();
+            assert_eq!(decrypted, data);
+        }
+    }
+
+    #[test]
+    fn invalid() {
+        let result = Aes::new(&Key::new(vec![0; 15]), Mode::Ecb);
+        assert!(matches!(result, Err(Error::InvalidKey { .. })));
+
+        let key = Key::new(vec![0; 16]);
+        let aes = Aes::new(&key, Mode::Ecb).unwrap();
+        let result: Result<Vec<u8>, _> = aes.try_decrypt(vec![0; 15]);
+        assert!(matches!(result.unwrap_err(), Error::Malformed { .. }));
+
+        // Block of zeros does not end with valid padding:
+        let unpadded = Aes::new(&key, Mode::Ecb).unwrap().with_padding(Padding::None);
+        let encrypted: Vec<u8> = unpadded.try_encrypt(vec![0; 16]).unwrap();
+        let result: Result<Vec<u8>, _> = aes.try_decrypt(encrypted);
+        assert!(matches!(result.unwrap_err(), Error::Malformed { .. }));
+    }
+}
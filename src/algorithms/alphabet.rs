@@ -0,0 +1,208 @@
+use std::collections::HashMap;
+
+use crate::error::Error;
+use crate::key::Key;
+
+/// Ordered set of symbols a classical cipher works with, such as the letters
+/// A&ndash;Z. Ciphers using an alphabet encrypt only its symbols and pass other
+/// characters through unchanged.
+///
+/// Letters are matched case-insensitively and ciphers keep the case of the
+/// input: with the Latin alphabet `a` is the same symbol as `A` and it is
+/// encrypted to a lowercase letter. If the alphabet contains both the uppercase
+/// and the lowercase form of any letter, case is significant for all symbols,
+/// since swapping case of a symbol could give another symbol of the alphabet.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Alphabet {
+    symbols: Vec<char>,
+    indices: HashMap<char, usize>,
+    fold_case: bool,
+}
+
+impl Alphabet {
+
+    /// Creates an alphabet from its symbols in the order they are given.
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with the alphabet
+    /// - [`Err`] with [`Error::InvalidParameters`] if there are fewer than two
+    ///   symbols or a symbol is repeated
+    pub fn new(symbols: &str) -> Result<Self, Error> {
+        let symbols: Vec<char> = symbols.chars().collect();
+        if symbols.len() < 2 {
+            return Err(Error::invalid_parameter("alphabet", "at least two symbols are needed"))
+        }
+        let mut indices = HashMap::with_capacity(symbols.len());
+        for (index, &symbol) in symbols.iter().enumerate() {
+            if indices.insert(symbol, index).is_some() {
+                return Err(Error::invalid_parameter(
+                    "alphabet",
+                    format!("symbol `{}` is repeated", symbol)
+                ))
+            }
+        }
+        let fold_case = !symbols.iter()
+            .any(|&symbol| other_case(symbol).is_some_and(|other| indices.contains_key(&other)));
+        Ok(Self { symbols, indices, fold_case })
+    }
+
+    /// Returns the Latin alphabet A&ndash;Z.
+    pub fn latin() -> Self {
+        Self::new("ABCDEFGHIJKLMNOPQRSTUVWXYZ").unwrap()
+    }
+
+    /// Returns number of symbols in the alphabet.
+    pub fn len(&self) -> usize {
+        self.symbols.len()
+    }
+
+    /// Returns `true` if the alphabet has no symbols. Since an alphabet cannot be
+    /// created with fewer than two symbols, this is always `false`.
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+
+    /// Returns the symbols in their order.
+    pub fn symbols(&self) -> &[char] {
+        &self.symbols
+    }
+
+    /// Returns index of the symbol, or [`None`] if the character is not in the
+    /// alphabet.
+    pub fn index_of(&self, character: char) -> Option<usize> {
+        self.find(character).map(|(index, _)| index)
+    }
+
+    /// Returns index of the character and whether it has the other case than the
+    /// symbol in the alphabet.
+    pub(crate) fn find(&self, character: char) -> Option<(usize, bool)> {
+        if let Some(&index) = self.indices.get(&character) {
+            return Some((index, false))
+        }
+        if !self.fold_case {
+            return None
+        }
+        other_case(character)
+            .and_then(|other| self.indices.get(&other))
+            .map(|&index| (index, true))
+    }
+
+    /// Returns symbol with given index, reduced modulo the length of the alphabet,
+    /// in the other case if `swap_case` is `true`.
+    pub(crate) fn symbol(&self, index: usize, swap_case: bool) -> char {
+        let symbol = self.symbols[index % self.len()];
+        match swap_case {
+            true  => other_case(symbol).unwrap_or(symbol),
+            false => symbol,
+        }
+    }
+
+    /// Converts a key written by symbols of the alphabet into their indices.
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with the indices
+    /// - [`Err`] with [`Error::InvalidKey`] if the key is empty or contains
+    ///   a character outside the alphabet
+    pub(crate) fn key_indices(&self, key: &str) -> Result<Vec<usize>, Error> {
+        if key.is_empty() {
+            return Err(Error::InvalidKey { reason: "key must not be empty".into() })
+        }
+        key.chars()
+            .map(|character| self.index_of(character).ok_or_else(|| Error::InvalidKey {
+                reason: format!("`{}` is not in the alphabet", character)
+            }))
+            .collect()
+    }
+
+    /// Returns indices of all characters of UTF-8 text in the alphabet.
+    pub(crate) fn indices(&self, text: &[u8]) -> Vec<usize> {
+        text.utf8_chunks()
+            .flat_map(|chunk| chunk.valid().chars())
+            .filter_map(|character| self.index_of(character))
+            .collect()
+    }
+
+    /// Applies `map` to indices of all characters of UTF-8 text in the alphabet,
+    /// keeping their case. Other characters and bytes which are not valid UTF-8
+    /// are passed through unchanged.
+    pub(crate) fn map_text<F>(&self, text: &[u8], mut map: F) -> Vec<u8>
+    where F: FnMut(usize) -> usize {
+        let mut output = Vec::with_capacity(text.len());
+        let mut buffer = [0; 4];
+        for chunk in text.utf8_chunks() {
+            for character in chunk.valid().chars() {
+                let mapped = match self.find(character) {
+                    Some((index, swap_case)) => self.symbol(map(index), swap_case),
+                    None => character,
+                };
+                output.extend(mapped.encode_utf8(&mut buffer).as_bytes());
+            }
+            output.extend(chunk.invalid());
+        }
+        output
+    }
+}
+
+/// Returns a key of a classical cipher stored in a [`Key`] as UTF-8 text.
+pub(crate) fn key_text(key: &Key) -> Result<&str, Error> {
+    std::str::from_utf8(key.reveal())
+        .map_err(|_| Error::InvalidKey { reason: "key is not UTF-8 text".into() })
+}
+
+/// Returns the character in the other case, if it has one written as a single
+/// character.
+fn other_case(character: char) -> Option<char> {
+    let mut other = match (character.is_uppercase(), character.is_lowercase()) {
+        (true, _) => character.to_lowercase().collect::<Vec<char>>(),
+        (_, true) => character.to_uppercase().collect::<Vec<char>>(),
+        _         => return None,
+    };
+    match other.len() {
+        1 => other.pop(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookup() {
+        let latin = Alphabet::latin();
+        assert_eq!(latin.len(), 26);
+        assert_eq!(latin.find('C'), Some((2, false)));
+        assert_eq!(latin.find('c'), Some((2, true)));
+        assert_eq!(latin.find('1'), None);
+        assert_eq!(latin.symbol(28, true), 'c');
+
+        // Both cases in the alphabet are different symbols:
+        let mixed = Alphabet::new("aAbB").unwrap();
+        assert_eq!(mixed.find('A'), Some((1, false)));
+        assert_eq!(mixed.find('a'), Some((0, false)));
+        // Then case is significant even for letters with a single case, since
+        // `B` mapped to `a` would be written as `A`:
+        let partial = Alphabet::new("aAbc").unwrap();
+        assert_eq!(partial.find('b'), Some((2, false)));
+        assert_eq!(partial.find('B'), None);
+        let text = "aAbcBC".as_bytes();
+        let shifted = partial.map_text(text, |index| index + 2);
+        assert_eq!(shifted, "bcaABC".as_bytes());
+        assert_eq!(partial.map_text(&shifted, |index| index + 2), text);
+
+        assert!(Alphabet::new("a").is_err());
+        assert!(Alphabet::new("abca").is_err());
+    }
+
+    #[test]
+    fn map_text() {
+        let latin = Alphabet::latin();
+        let text = "Hello, World! \u{1F600}".as_bytes();
+        let shifted = latin.map_text(text, |index| index + 1);
+        assert_eq!(shifted, "Ifmmp, Xpsme! \u{1F600}".as_bytes());
+        // Invalid UTF-8 is kept:
+        assert_eq!(latin.map_text(&[b'a', 0xff, b'z'], |index| index + 1), vec![b'b', 0xff, b'a']);
+    }
+}
@@ -0,0 +1,299 @@
+use std::iter::FromIterator;
+use std::iter::IntoIterator;
+
+use crate::decrypt::TryDecrypt;
+use crate::encrypt::Encrypt;
+use crate::error::EncodingErrorKind;
+use crate::error::Error;
+
+/// Alphabet of Z85, the ZeroMQ variant of Ascii85.
+const Z85_ALPHABET: &[u8; 85] =
+    b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ.-:+=^!/*?&<>()[]{}@%$#";
+
+/// Symbol of Ascii85 abbreviating a group of four zero bytes.
+const ZERO_GROUP: u8 = b'z';
+
+/// Number of symbols encoding a group of four bytes.
+const GROUP_LEN: usize = 5;
+
+/// Ascii85 encoding as used by btoa and PostScript, which writes every 4 bytes
+/// as 5 symbols from `!` to `u`, and four zero bytes as `z`. The last group of
+/// `n` bytes is written as `n + 1` symbols. The `<~` and `~>` delimiters are
+/// not written and decoding does not accept them, but it ignores whitespace.
+///
+/// ```
+/// use mdcrypt::algorithms::Ascii85;
+/// use mdcrypt::Encrypt;
+///
+/// let encoded: Vec<u8> = Ascii85::new().encrypt(b"Man ".to_vec());
+/// assert_eq!(encoded, b"9jqo^");
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Ascii85;
+
+impl Ascii85 {
+
+    /// Creates the encoding.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Encodes given data and returns them as a string.
+    pub fn encode_to_string(&self, data: &[u8]) -> String {
+        String::from_utf8(self.encrypt(data.iter().copied())).expect("Ascii85 is ASCII")
+    }
+}
+
+impl Encrypt for Ascii85 {
+
+    fn encrypt<D, E>(&self, data_to_encrypt: D) -> E
+    where
+        D:           IntoIterator<Item = u8>,
+        D::IntoIter: ExactSizeIterator,
+        E:           FromIterator<u8>
+    {
+        let data: Vec<u8> = data_to_encrypt.into_iter().collect();
+        encode(&data, |digit| b'!' + digit, true).into_iter().collect()
+    }
+}
+
+impl TryDecrypt for Ascii85 {
+
+    /// Error type returned when the text is not a valid Ascii85.
+    type ErrorType = Error;
+
+    /// Decodes Ascii85 text, ignoring whitespace.
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with the decoded data
+    /// - [`Err`] with [`Error::InvalidEncoding`] if the text contains a symbol
+    ///   other than `!` to `u` and `z`, `z` is inside a group, a group exceeds
+    ///   32 bits or the last group has a single symbol
+    fn try_decrypt<E, D>(
+        &self,
+        encrypted_data: E
+    ) -> Result<D, Self::ErrorType> where
+        E:           IntoIterator<Item = u8>,
+        E::IntoIter: ExactSizeIterator,
+        D:           FromIterator<u8> {
+
+        let symbols = encrypted_data.into_iter()
+            .enumerate()
+            .filter(|(_, symbol)| !symbol.is_ascii_whitespace());
+        let decoded = decode(symbols, "Ascii85", true, |symbol| match symbol {
+            b'!'..=b'u' => Some(symbol - b'!'),
+            _ => None,
+        })?;
+        Ok(decoded.into_iter().collect())
+    }
+}
+
+/// Z85 encoding specified by ZeroMQ RFC 32, which writes every 4 bytes as
+/// 5 symbols of an alphabet safe to be used in source code, e.g. in strings.
+///
+/// The specification requires data whose length is a multiple of 4. Other data
+/// are encoded as by [`Ascii85`]: the last group of `n` bytes is written as
+/// `n + 1` symbols.
+///
+/// ```
+/// use mdcrypt::algorithms::Z85;
+/// use mdcrypt::Encrypt;
+///
+/// let data = vec![0x86, 0x4f, 0xd2, 0x6f, 0xb5, 0x59, 0xf7, 0x5b];
+/// let encoded: Vec<u8> = Z85::new().encrypt(data);
+/// assert_eq!(encoded, b"HelloWorld");
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Z85;
+
+impl Z85 {
+
+    /// Creates the encoding.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Encodes given data and returns them as a string.
+    pub fn encode_to_string(&self, data: &[u8]) -> String {
+        String::from_utf8(self.encrypt(data.iter().copied())).expect("Z85 is ASCII")
+    }
+}
+
+impl Encrypt for Z85 {
+
+    fn encrypt<D, E>(&self, data_to_encrypt: D) -> E
+    where
+        D:           IntoIterator<Item = u8>,
+        D::IntoIter: ExactSizeIterator,
+        E:           FromIterator<u8>
+    {
+        let data: Vec<u8> = data_to_encrypt.into_iter().collect();
+        encode(&data, |digit| Z85_ALPHABET[digit as usize], false).into_iter().collect()
+    }
+}
+
+impl TryDecrypt for Z85 {
+
+    /// Error type returned when the text is not a valid Z85.
+    type ErrorType = Error;
+
+    /// Decodes Z85 text. Whitespace is not allowed.
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with the decoded data
+    /// - [`Err`] with [`Error::InvalidEncoding`] if the text contains a symbol
+    ///   outside of the alphabet, a group exceeds 32 bits or the last group has
+    ///   a single symbol
+    fn try_decrypt<E, D>(
+        &self,
+        encrypted_data: E
+    ) -> Result<D, Self::ErrorType> where
+        E:           IntoIterator<Item = u8>,
+        E::IntoIter: ExactSizeIterator,
+        D:           FromIterator<u8> {
+
+        let symbols = encrypted_data.into_iter().enumerate();
+        let decoded = decode(symbols, "Z85", false, |symbol| {
+            Z85_ALPHABET.iter().position(|&letter| letter == symbol).map(|digit| digit as u8)
+        })?;
+        Ok(decoded.into_iter().collect())
+    }
+}
+
+/// Encodes groups of 4 bytes as 5 base-85 digits, the most significant first.
+///
+/// # Parameters
+///
+/// - `data`: data to encode
+/// - `symbol`: maps a digit to its symbol
+/// - `zero_group`: whether a group of four zero bytes is written as `z`
+fn encode(data: &[u8], symbol: impl Fn(u8) -> u8, zero_group: bool) -> Vec<u8> {
+    let mut encoded = Vec::with_capacity(data.len().div_ceil(4) * GROUP_LEN);
+    for chunk in data.chunks(4) {
+        let mut group = [0; 4];
+        group[..chunk.len()].copy_from_slice(chunk);
+        let value = u32::from_be_bytes(group);
+        if zero_group && value == 0 && chunk.len() == 4 {
+            encoded.push(ZERO_GROUP);
+            continue
+        }
+        let digits: [u8; GROUP_LEN] = std::array::from_fn(|idx| {
+            (value / 85u32.pow((GROUP_LEN - 1 - idx) as u32) % 85) as u8
+        });
+        encoded.extend(digits[..=chunk.len()].iter().map(|&digit| symbol(digit)));
+    }
+    encoded
+}
+
+/// Decodes symbols with their positions in the text.
+///
+/// # Parameters
+///
+/// - `symbols`: symbols with their positions
+/// - `name`: name of the encoding used in errors
+/// - `zero_group`: whether `z` stands for four zero bytes
+/// - `digit`: maps a symbol to its digit, or returns [`None`] if it is invalid
+fn decode<S>(symbols: S, name: &str, zero_group: bool, digit: impl Fn(u8) -> Option<u8>)
+        -> Result<Vec<u8>, Error>
+where S: Iterator<Item = (usize, u8)> {
+    let invalid = |position, kind| Error::invalid_encoding(name, position, kind);
+    let mut decoded = Vec::new();
+    let mut group = Vec::with_capacity(GROUP_LEN);
+    let mut last_position = 0;
+    for (position, symbol) in symbols {
+        last_position = position;
+        if zero_group && symbol == ZERO_GROUP {
+            if !group.is_empty() {
+                return Err(invalid(position, EncodingErrorKind::InvalidSymbol))
+            }
+            decoded.extend([0; 4]);
+            continue
+        }
+        match digit(symbol) {
+            Some(digit) => group.push(digit),
+            None        => return Err(invalid(position, EncodingErrorKind::InvalidSymbol)),
+        }
+        if group.len() == GROUP_LEN {
+            decoded.extend(group_bytes(&group, name, position)?);
+            group.clear();
+        }
+    }
+
+    match group.len() {
+        0 => {},
+        1 => return Err(invalid(last_position, EncodingErrorKind::TruncatedGroup)),
+        len => {
+            // The last group is padded by the highest digit, which is then
+            // truncated away with the extra bytes:
+            group.resize(GROUP_LEN, 84);
+            decoded.extend(&group_bytes(&group, name, last_position)?[..len - 1]);
+        },
+    }
+    Ok(decoded)
+}
+
+/// Returns 4 bytes encoded by a group of 5 digits ending at given position.
+fn group_bytes(group: &[u8], name: &str, position: usize) -> Result<[u8; 4], Error> {
+    let value = group.iter().fold(0u64, |value, &digit| value * 85 + digit as u64);
+    u32::try_from(value)
+        .map(u32::to_be_bytes)
+        .map_err(|_| Error::invalid_encoding(name, position, EncodingErrorKind::GroupOverflow))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::EncodingErrorKind::GroupOverflow;
+    use crate::error::EncodingErrorKind::InvalidSymbol;
+    use crate::error::EncodingErrorKind::TruncatedGroup;
+    use crate::test_util::assert_invalid_encoding;
+
+    #[test]
+    fn ascii85() {
+        let ascii85 = Ascii85::new();
+        for (data, expected) in [
+            (&b"Man is distinguished"[..], "9jqo^BlbD-BleB1DJ+*+F(f,q"),
+            (b"\0\0\0\0abc", "z@:E^"),
+            (b"\xff\xff\xff\xff", "s8W-!"),
+            (b"\0", "!!"),
+            (b"", ""),
+        ] {
+            assert_eq!(ascii85.encode_to_string(data), expected);
+            let decoded: Vec<u8> = ascii85.try_decrypt(expected.bytes()).unwrap();
+            assert_eq!(decoded, data);
+        }
+        let decoded: Vec<u8> = ascii85.try_decrypt(b"9jqo^ Blb\nD-".to_vec()).unwrap();
+        assert_eq!(decoded, b"Man is d");
+        assert_invalid_encoding(&ascii85, &[
+            ("9jqo^B", 5, TruncatedGroup),
+            ("9jzo^", 2, InvalidSymbol),
+            ("s8W-\"", 4, GroupOverflow),
+            ("9jqo~", 4, InvalidSymbol),
+            ("uuuuu", 4, GroupOverflow),
+            // Positions count the ignored whitespace:
+            ("9jqo^ 9jzo^", 8, InvalidSymbol),
+        ]);
+    }
+
+    #[test]
+    fn z85() {
+        let z85 = Z85::new();
+        let data: Vec<u8> = (0..=255).collect();
+        for len in 0..20 {
+            let encoded: Vec<u8> = z85.encrypt(data[..len].to_vec());
+            assert_eq!(encoded.len(), len / 4 * 5 + len % 4 + usize::from(len % 4 > 0));
+            let decoded: Vec<u8> = z85.try_decrypt(encoded).unwrap();
+            assert_eq!(decoded, &data[..len]);
+        }
+        let decoded: Vec<u8> = z85.try_decrypt(b"HelloWorld".to_vec()).unwrap();
+        assert_eq!(decoded, [0x86, 0x4f, 0xd2, 0x6f, 0xb5, 0x59, 0xf7, 0x5b]);
+        assert_invalid_encoding(&z85, &[
+            ("Hello World", 5, InvalidSymbol),
+            ("Hello\"", 5, InvalidSymbol),
+            ("HelloW", 5, TruncatedGroup),
+            ("%%%%%", 4, GroupOverflow),
+        ]);
+    }
+}
@@ -0,0 +1,386 @@
+use std::io;
+use std::io::Read;
+use std::iter::ExactSizeIterator;
+use std::iter::FromIterator;
+use std::iter::IntoIterator;
+
+use blake2::digest::core_api::Buffer;
+use blake2::digest::core_api::UpdateCore;
+use blake2::digest::core_api::VariableOutputCore;
+use blake2::digest::generic_array::GenericArray;
+use blake2::digest::Output;
+use blake2::Blake2bVarCore;
+use blake2::Blake2sVarCore;
+use zeroize::Zeroizing;
+
+use crate::algorithms::hash::hash_of;
+use crate::algorithms::HashFunction;
+use crate::error::Error;
+use crate::stream::Encryptor;
+use crate::stream::StreamEncrypt;
+use crate::Digest;
+use crate::Encrypt;
+use crate::Key;
+
+/// Compression function of a BLAKE2 variant together with its parameters. It is
+/// implemented only for the cores of BLAKE2b and BLAKE2s and cannot be named
+/// outside of this crate.
+pub trait Blake2Core: Clone {
+
+    /// Size of the block in bytes.
+    const BLOCK_LEN: usize;
+
+    /// Maximal length of the digest and of the key in bytes.
+    const MAX_LEN: usize;
+
+    /// Creates the core initialized by the parameter block.
+    fn with_params(key_len: usize, output_len: usize) -> Self;
+
+    /// Compresses blocks which are not the last one.
+    fn compress(&mut self, blocks: &[u8]);
+
+    /// Compresses the last block, which may be shorter, and writes the digest.
+    fn finish(self, last_block: &[u8], digest: &mut [u8]);
+}
+
+impl Blake2Core for Blake2bVarCore {
+    const BLOCK_LEN: usize = 128;
+    const MAX_LEN: usize = 64;
+
+    fn with_params(key_len: usize, output_len: usize) -> Self {
+        Self::new_with_params(&[], &[], key_len, output_len)
+    }
+
+    fn compress(&mut self, blocks: &[u8]) {
+        for block in blocks.chunks(Self::BLOCK_LEN) {
+            self.update_blocks(&[GenericArray::clone_from_slice(block)]);
+        }
+    }
+
+    fn finish(mut self, last_block: &[u8], digest: &mut [u8]) {
+        let mut output = Output::<Self>::default();
+        self.finalize_variable_core(&mut Buffer::<Self>::new(last_block), &mut output);
+        digest.copy_from_slice(&output[..digest.len()]);
+    }
+}
+
+impl Blake2Core for Blake2sVarCore {
+    const BLOCK_LEN: usize = 64;
+    const MAX_LEN: usize = 32;
+
+    fn with_params(key_len: usize, output_len: usize) -> Self {
+        Self::new_with_params(&[], &[], key_len, output_len)
+    }
+
+    fn compress(&mut self, blocks: &[u8]) {
+        for block in blocks.chunks(Self::BLOCK_LEN) {
+            self.update_blocks(&[GenericArray::clone_from_slice(block)]);
+        }
+    }
+
+    fn finish(mut self, last_block: &[u8], digest: &mut [u8]) {
+        let mut output = Output::<Self>::default();
+        self.finalize_variable_core(&mut Buffer::<Self>::new(last_block), &mut output);
+        digest.copy_from_slice(&output[..digest.len()]);
+    }
+}
+
+/// A hash function of the BLAKE2 family as specified by RFC 7693, with the
+/// digest of any length up to 64 bytes for BLAKE2b and 32 bytes for BLAKE2s.
+/// With a key it works as a message authentication code (keyed mode of BLAKE2).
+///
+/// It implements [`Encrypt`] trait by hashing the whole data at once, a stateful
+/// [`Blake2Stream`] for data fed by chunks is returned by [`hasher()`]. Use type
+/// aliases [`Blake2b`] and [`Blake2s`] rather than this struct directly. Their
+/// default instances are not keyed and have the longest digest.
+///
+/// ```
+/// use mdcrypt::algorithms::Blake2b;
+/// use mdcrypt::Key;
+///
+/// let key = Key::new(b"secret key".to_vec());
+/// let mut mac = Blake2b::with_key(&key, 32).unwrap().hasher();
+/// mac.update(b"message");
+/// assert_eq!(mac.finalize().len(), 32);
+/// ```
+///
+/// [`hasher()`]: Blake2::hasher
+/// [`Encrypt`]: crate::Encrypt
+#[derive(Clone)]
+pub struct Blake2<C: Blake2Core> {
+    /// Core initialized by the parameters, with no data yet.
+    initial: C,
+    /// The key padded by zeros to the block size, or nothing if not keyed. It is
+    /// wiped from the memory on drop.
+    key_block: Zeroizing<Box<[u8]>>,
+    output_len: usize,
+}
+
+impl<C: Blake2Core> Blake2<C> {
+
+    /// Creates the hash function.
+    ///
+    /// # Parameters
+    ///
+    /// - `output_len`: length of the digest in bytes
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with the hash function
+    /// - [`Err`] with [`Error::InvalidParameters`] if `output_len` is zero or
+    ///   greater than 64 bytes for BLAKE2b or 32 bytes for BLAKE2s
+    pub fn new(output_len: usize) -> Result<Self, Error> {
+        Self::with_params(&[], output_len)
+    }
+
+    /// Creates the hash function in the keyed mode.
+    ///
+    /// # Parameters
+    ///
+    /// - `key`: secret key
+    /// - `output_len`: length of the digest in bytes
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with the hash function
+    /// - [`Err`] with [`Error::InvalidKey`] if the key is longer than 64 bytes
+    ///   for BLAKE2b or 32 bytes for BLAKE2s
+    /// - [`Err`] with [`Error::InvalidParameters`] if `output_len` is not valid,
+    ///   see [`new()`](Blake2::new)
+    pub fn with_key(key: &Key, output_len: usize) -> Result<Self, Error> {
+        if key.len() > C::MAX_LEN {
+            return Err(Error::InvalidKey {
+                reason: format!("key must not have more than {} bytes", C::MAX_LEN)
+            })
+        }
+        Self::with_params(key.reveal(), output_len)
+    }
+
+    /// Returns `true` if the hash function is in the keyed mode.
+    pub fn is_keyed(&self) -> bool {
+        !self.key_block.is_empty()
+    }
+
+    /// Returns a new stateful hasher with no data hashed yet.
+    pub fn hasher(&self) -> Blake2Stream<C> {
+        // The key padded to a full block is hashed before the data:
+        let (pending, pending_len) = match self.is_keyed() {
+            true  => (self.key_block.clone(), C::BLOCK_LEN),
+            false => (Zeroizing::new(vec![0; C::BLOCK_LEN].into_boxed_slice()), 0),
+        };
+        Blake2Stream {
+            core: self.initial.clone(),
+            pending,
+            pending_len,
+            output_len: self.output_len,
+        }
+    }
+
+    /// Hashes everything that can be read from given reader, so that the data
+    /// do not need to be buffered in the memory.
+    ///
+    /// # Parameters
+    ///
+    /// - `reader`: reader to read data to hash from
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with the digest of all read data
+    /// - [`Err`] if reading has failed
+    pub fn hash_reader<R: Read>(&self, reader: R) -> io::Result<Digest> {
+        self.hasher().hash_reader(reader)
+    }
+
+    fn with_params(key: &[u8], output_len: usize) -> Result<Self, Error> {
+        if !(1..=C::MAX_LEN).contains(&output_len) {
+            return Err(Error::invalid_parameter(
+                "output_len",
+                format!("must be from 1 to {}", C::MAX_LEN)
+            ))
+        }
+        let mut key_block = Zeroizing::new(Box::<[u8]>::default());
+        if !key.is_empty() {
+            key_block = Zeroizing::new(vec![0; C::BLOCK_LEN].into_boxed_slice());
+            key_block[..key.len()].copy_from_slice(key);
+        }
+        Ok(Self { initial: C::with_params(key.len(), output_len), key_block, output_len })
+    }
+}
+
+impl<C: Blake2Core> Encrypt for Blake2<C> {
+
+    /// Hashes given data and returns the digest.
+    fn encrypt<D, E>(&self, data_to_encrypt: D) -> E
+    where
+        D:           IntoIterator<Item = u8>,
+        D::IntoIter: ExactSizeIterator,
+        E:           FromIterator<u8>
+    {
+        hash_of(self.hasher(), data_to_encrypt).into_iter().collect()
+    }
+}
+
+impl<C: Blake2Core> StreamEncrypt for Blake2<C> {
+    type Encryptor<'a> = Blake2Stream<C> where Self: 'a;
+
+    fn encryptor(&self) -> Blake2Stream<C> {
+        self.hasher()
+    }
+}
+
+impl<C: Blake2Core> Default for Blake2<C> {
+    fn default() -> Self {
+        // The maximal length is always valid:
+        Self::new(C::MAX_LEN).unwrap()
+    }
+}
+
+/// State of [`Blake2`] hashing fed by chunks of data, used in the same way as
+/// [`Sha2Stream`]. Data waiting for compression, which include the key block
+/// in the keyed mode, are wiped from the memory on drop.
+///
+/// [`Sha2Stream`]: crate::algorithms::Sha2Stream
+#[derive(Clone)]
+pub struct Blake2Stream<C: Blake2Core> {
+    core: C,
+    /// Data not compressed yet. The last block must be compressed differently,
+    /// so a block is compressed only once next data arrive.
+    pending: Zeroizing<Box<[u8]>>,
+    pending_len: usize,
+    output_len: usize,
+}
+
+impl<C: Blake2Core> Blake2Stream<C> {
+
+    /// Feeds the hasher with next chunk of data.
+    ///
+    /// # Parameters
+    ///
+    /// - `data`: data to hash
+    pub fn update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            if self.pending_len == C::BLOCK_LEN {
+                self.core.compress(&self.pending);
+                self.pending_len = 0;
+                // Keep at least one byte, so the last block stays pending:
+                let compressed = data.len().saturating_sub(1) / C::BLOCK_LEN * C::BLOCK_LEN;
+                self.core.compress(&data[..compressed]);
+                data = &data[compressed..];
+            }
+            let len = data.len().min(C::BLOCK_LEN - self.pending_len);
+            self.pending[self.pending_len..][..len].copy_from_slice(&data[..len]);
+            self.pending_len += len;
+            data = &data[len..];
+        }
+    }
+
+    /// Finishes hashing and returns the digest of all data fed into the hasher.
+    pub fn finalize(self) -> Digest {
+        let mut digest = vec![0; self.output_len];
+        self.core.finish(&self.pending[..self.pending_len], &mut digest);
+        Digest::new(digest)
+    }
+}
+
+impl<C: Blake2Core> HashFunction for Blake2Stream<C> {
+
+    fn output_len(&self) -> usize {
+        self.output_len
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        Blake2Stream::update(self, data);
+    }
+
+    fn finalize(self) -> Digest {
+        Blake2Stream::finalize(self)
+    }
+}
+
+impl<C: Blake2Core> Encryptor for Blake2Stream<C> {
+
+    fn update(&mut self, input: &[u8], _output: &mut Vec<u8>) -> io::Result<()> {
+        Blake2Stream::update(self, input);
+        Ok(())
+    }
+
+    fn finish(self, output: &mut Vec<u8>) -> io::Result<()> {
+        output.extend(self.finalize());
+        Ok(())
+    }
+}
+
+/// BLAKE2b hasher, optimized for 64-bit platforms, implementing [`Encrypt`]
+/// trait from this crate.
+///
+/// [`Encrypt`]: crate::Encrypt
+pub type Blake2b = Blake2<Blake2bVarCore>;
+
+/// BLAKE2s hasher, optimized for 8- to 32-bit platforms, implementing
+/// [`Encrypt`] trait from this crate.
+///
+/// [`Encrypt`]: crate::Encrypt
+pub type Blake2s = Blake2<Blake2sVarCore>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::digest_hex;
+
+    #[test]
+    fn known_answers() {
+        assert_eq!(digest_hex(&Blake2b::default(), b"abc".to_vec()),
+            "ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d1\
+             7d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923");
+        assert_eq!(digest_hex(&Blake2s::default(), b"abc".to_vec()),
+            "508c5e8c327c14e2e1a72ba34eeb452f37458b209ed63a294d999b4c86675982");
+        assert_eq!(digest_hex(&Blake2b::new(32).unwrap(), vec![]),
+            "0e5751c026e543b2e8ab2eb06099daa1d1e5df47778f7787faab45cdf12fe3a8");
+    }
+
+    #[test]
+    fn keyed() {
+        // Test vectors of the reference implementation use the key 00 01 .. 3f
+        // and the message 00 01 .. for given length:
+        let key = Key::new((0..64).collect());
+        let mac = Blake2b::with_key(&key, 64).unwrap();
+        assert!(mac.is_keyed());
+        assert_eq!(digest_hex(&mac, vec![]),
+            "10ebb67700b1868efb4417987acf4690ae9d972fb7a590c2f02871799aaa4786\
+             b5e996e8f0f4eb981fc214b005f42d2ff4233499391653df7aefcbc13fc51568");
+        assert_eq!(digest_hex(&mac, 0..255),
+            "142709d62e28fcccd0af97fad0f8465b971e82201dc51070faa0372aa43e9248\
+             4be1c1e73ba10906d5d1853db6a4106e0a7bf9800d373d6dee2d46d62ef2a461");
+        let mac = Blake2s::with_key(&Key::new((0..32).collect()), 32).unwrap();
+        assert_eq!(digest_hex(&mac, vec![]),
+            "48a8997da407876b3d79c0d92325ad3b89cbb754d86ab71aee047ad345fd2c49");
+        assert_eq!(digest_hex(&mac, 0..64),
+            "8975b0577fd35566d750b362b0897a26c399136df07bababbde6203ff2954ed4");
+    }
+
+    #[test]
+    fn incremental() {
+        let data: Vec<u8> = (0..=255).cycle().take(20000).collect();
+        let key = Key::new(b"key".to_vec());
+        for hasher in [Blake2b::new(20).unwrap(), Blake2b::with_key(&key, 64).unwrap()] {
+            let expected: Vec<u8> = hasher.encrypt(data.clone());
+            assert_eq!(hasher.hash_reader(&data[..]).unwrap().as_bytes(), &expected[..]);
+            let mut hasher = hasher.hasher();
+            // Chunks ending exactly at the block boundary as well:
+            let (blocks, rest) = data.split_at(1280);
+            for chunk in blocks.chunks(128).chain(rest.chunks(777)) {
+                hasher.update(chunk);
+            }
+            assert_eq!(hasher.finalize().as_bytes(), &expected[..]);
+        }
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(matches!(Blake2b::new(0), Err(Error::InvalidParameters { .. })));
+        assert!(Blake2b::new(65).is_err());
+        assert!(Blake2s::new(33).is_err());
+        assert!(matches!(Blake2s::with_key(&Key::new(vec![0; 33]), 32),
+            Err(Error::InvalidKey { .. })));
+    }
+}
@@ -0,0 +1,262 @@
+use std::io;
+use std::io::Read;
+use std::iter::ExactSizeIterator;
+use std::iter::FromIterator;
+use std::iter::IntoIterator;
+
+use zeroize::Zeroize;
+
+use crate::algorithms::hash::hash_of;
+use crate::algorithms::hash::MAX_OUTPUT_LEN;
+use crate::algorithms::HashFunction;
+use crate::error::Error;
+use crate::stream::Encryptor;
+use crate::stream::StreamEncrypt;
+use crate::Digest;
+use crate::Encrypt;
+use crate::Key;
+
+/// Length of the key and of the default digest in bytes.
+const LEN: usize = 32;
+
+/// The BLAKE3 hash function, whose digest can have any length up to 1 MiB.
+/// With a key it works as a message authentication code (keyed mode of BLAKE3).
+///
+/// It implements [`Encrypt`] trait by hashing the whole data at once, a stateful
+/// [`Blake3Stream`] for data fed by chunks is returned by [`hasher()`]. The
+/// default instance is not keyed and has a digest of 32 bytes.
+///
+/// ```
+/// use mdcrypt::algorithms::Blake3;
+/// use mdcrypt::Encrypt;
+///
+/// let digest: Vec<u8> = Blake3::default().encrypt(vec![]);
+/// assert_eq!(digest[..4], [0xaf, 0x13, 0x49, 0xb9]);
+/// ```
+///
+/// The hasher holding the key is wiped when the instance is dropped.
+///
+/// [`hasher()`]: Blake3::hasher
+/// [`Encrypt`]: crate::Encrypt
+#[derive(Clone, Debug)]
+pub struct Blake3 {
+    /// Hasher initialized by the key, with no data yet.
+    initial: blake3::Hasher,
+    keyed: bool,
+    output_len: usize,
+}
+
+impl Blake3 {
+
+    /// Creates the hash function.
+    ///
+    /// # Parameters
+    ///
+    /// - `output_len`: length of the digest in bytes
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with the hash function
+    /// - [`Err`] with [`Error::InvalidParameters`] if `output_len` is zero or
+    ///   greater than 1 MiB
+    pub fn new(output_len: usize) -> Result<Self, Error> {
+        Self::with_hasher(blake3::Hasher::new(), false, output_len)
+    }
+
+    /// Creates the hash function in the keyed mode.
+    ///
+    /// # Parameters
+    ///
+    /// - `key`: secret key of 32 bytes
+    /// - `output_len`: length of the digest in bytes
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with the hash function
+    /// - [`Err`] with [`Error::InvalidKey`] if the key does not have 32 bytes
+    /// - [`Err`] with [`Error::InvalidParameters`] if `output_len` is not valid,
+    ///   see [`new()`](Blake3::new)
+    pub fn with_key(key: &Key, output_len: usize) -> Result<Self, Error> {
+        let key: &[u8; LEN] = key.reveal().try_into().map_err(|_| Error::InvalidKey {
+            reason: format!("key must have {} bytes", LEN)
+        })?;
+        Self::with_hasher(blake3::Hasher::new_keyed(key), true, output_len)
+    }
+
+    /// Returns `true` if the hash function is in the keyed mode.
+    pub fn is_keyed(&self) -> bool {
+        self.keyed
+    }
+
+    /// Returns a new stateful hasher with no data hashed yet.
+    pub fn hasher(&self) -> Blake3Stream {
+        Blake3Stream { hasher: self.initial.clone(), output_len: self.output_len }
+    }
+
+    /// Hashes everything that can be read from given reader, so that the data
+    /// do not need to be buffered in the memory.
+    ///
+    /// # Parameters
+    ///
+    /// - `reader`: reader to read data to hash from
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with the digest of all read data
+    /// - [`Err`] if reading has failed
+    pub fn hash_reader<R: Read>(&self, reader: R) -> io::Result<Digest> {
+        self.hasher().hash_reader(reader)
+    }
+
+    fn with_hasher(initial: blake3::Hasher, keyed: bool, output_len: usize)
+            -> Result<Self, Error> {
+        if !(1..=MAX_OUTPUT_LEN).contains(&output_len) {
+            return Err(Error::invalid_parameter(
+                "output_len",
+                format!("must be from 1 to {}", MAX_OUTPUT_LEN)
+            ))
+        }
+        Ok(Self { initial, keyed, output_len })
+    }
+}
+
+impl Default for Blake3 {
+    fn default() -> Self {
+        Self { initial: blake3::Hasher::new(), keyed: false, output_len: LEN }
+    }
+}
+
+impl Drop for Blake3 {
+    fn drop(&mut self) {
+        self.initial.zeroize();
+    }
+}
+
+impl Encrypt for Blake3 {
+
+    /// Hashes given data and returns the digest.
+    fn encrypt<D, E>(&self, data_to_encrypt: D) -> E
+    where
+        D:           IntoIterator<Item = u8>,
+        D::IntoIter: ExactSizeIterator,
+        E:           FromIterator<u8>
+    {
+        hash_of(self.hasher(), data_to_encrypt).into_iter().collect()
+    }
+}
+
+impl StreamEncrypt for Blake3 {
+    type Encryptor<'a> = Blake3Stream;
+
+    fn encryptor(&self) -> Blake3Stream {
+        self.hasher()
+    }
+}
+
+/// State of [`Blake3`] hashing fed by chunks of data, used in the same way as
+/// [`Sha2Stream`]. The state is wiped when the instance is dropped.
+///
+/// [`Sha2Stream`]: crate::algorithms::Sha2Stream
+#[derive(Clone, Debug)]
+pub struct Blake3Stream {
+    hasher: blake3::Hasher,
+    output_len: usize,
+}
+
+impl Blake3Stream {
+
+    /// Feeds the hasher with next chunk of data.
+    ///
+    /// # Parameters
+    ///
+    /// - `data`: data to hash
+    pub fn update(&mut self, data: &[u8]) {
+        self.hasher.update(data);
+    }
+
+    /// Finishes hashing and returns the digest of all data fed into the hasher.
+    pub fn finalize(self) -> Digest {
+        let mut digest = vec![0; self.output_len];
+        let mut output = self.hasher.finalize_xof();
+        output.fill(&mut digest);
+        output.zeroize();
+        Digest::new(digest)
+    }
+}
+
+impl Drop for Blake3Stream {
+    fn drop(&mut self) {
+        self.hasher.zeroize();
+    }
+}
+
+impl HashFunction for Blake3Stream {
+
+    fn output_len(&self) -> usize {
+        self.output_len
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        Blake3Stream::update(self, data);
+    }
+
+    fn finalize(self) -> Digest {
+        Blake3Stream::finalize(self)
+    }
+}
+
+impl Encryptor for Blake3Stream {
+
+    fn update(&mut self, input: &[u8], _output: &mut Vec<u8>) -> io::Result<()> {
+        Blake3Stream::update(self, input);
+        Ok(())
+    }
+
+    fn finish(self, output: &mut Vec<u8>) -> io::Result<()> {
+        output.extend(self.finalize());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::digest_hex;
+
+    #[test]
+    fn known_answers() {
+        assert_eq!(digest_hex(&Blake3::default(), vec![]),
+            "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262");
+        // The longer digest extends the default one:
+        assert_eq!(digest_hex(&Blake3::new(40).unwrap(), vec![])[..64],
+            *"af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262");
+        // Key of the official test vectors:
+        let key = Key::new(b"whats the Elvish word for friend".to_vec());
+        let mac = Blake3::with_key(&key, 32).unwrap();
+        assert!(mac.is_keyed());
+        assert_eq!(digest_hex(&mac, vec![]),
+            "92b2b75604ed3c761f9d6f62392c8a9227ad0ea3f09573e783f1498a4ed60d26");
+    }
+
+    #[test]
+    fn incremental() {
+        let data: Vec<u8> = (0..=255).cycle().take(20000).collect();
+        let blake3 = Blake3::new(100).unwrap();
+        let expected: Vec<u8> = blake3.encrypt(data.clone());
+        let mut hasher = blake3.hasher();
+        for chunk in data.chunks(777) {
+            hasher.update(chunk);
+        }
+        assert_eq!(hasher.finalize().as_bytes(), &expected[..]);
+        assert_eq!(blake3.hash_reader(&data[..]).unwrap().as_bytes(), &expected[..]);
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(matches!(Blake3::new(0), Err(Error::InvalidParameters { .. })));
+        assert!(Blake3::new(MAX_OUTPUT_LEN).is_ok());
+        assert!(Blake3::new(usize::MAX).is_err());
+        assert!(matches!(Blake3::with_key(&Key::new(vec![0; 31]), 32),
+            Err(Error::InvalidKey { .. })));
+    }
+}
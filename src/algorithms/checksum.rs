@@ -0,0 +1,456 @@
+use std::iter::FromIterator;
+use std::iter::IntoIterator;
+
+use crate::algorithms::hash::for_each_chunk;
+use crate::decrypt::TryDecrypt;
+use crate::encrypt::Encrypt;
+use crate::error::Error;
+use crate::Digest;
+
+/// Largest prime below 2<sup>16</sup>, the modulus of Adler-32.
+const ADLER_MODULUS: u32 = 65521;
+
+/// A checksum such as [`Crc`] or [`Adler32`]. Unlike a cryptographic hash,
+/// a checksum is cheap to compute and detects accidental errors only; anybody
+/// can modify the data and fix the checksum.
+///
+/// All checksums of this crate implement [`Encrypt`], which computes checksum
+/// of the whole data at once and returns it as big-endian bytes, and they can be
+/// wrapped in [`Framed`] to append the checksum to the data. For data fed by
+/// chunks, get a stateful [`ChecksumStream`] by calling [`stream()`].
+///
+/// [`Crc`]: crate::algorithms::Crc
+/// [`stream()`]: Checksum::stream
+pub trait Checksum: Clone {
+
+    /// The state of the checksum fed by chunks of data.
+    type Stream: ChecksumStream;
+
+    /// Returns number of bits of the checksum.
+    fn width(&self) -> u8;
+
+    /// Returns a new stateful checksum with no data yet.
+    fn stream(&self) -> Self::Stream;
+}
+
+/// State of a [`Checksum`] fed by chunks of data. Feed it with data by calling
+/// [`update()`] any number of times and get the value by calling [`value()`] or
+/// [`finalize()`].
+///
+/// [`update()`]: ChecksumStream::update
+/// [`value()`]: ChecksumStream::value
+/// [`finalize()`]: ChecksumStream::finalize
+pub trait ChecksumStream: Clone {
+
+    /// Returns number of bits of the checksum.
+    fn width(&self) -> u8;
+
+    /// Feeds the checksum with next chunk of data.
+    ///
+    /// # Parameters
+    ///
+    /// - `data`: data to checksum
+    fn update(&mut self, data: &[u8]);
+
+    /// Returns the checksum of all data fed so far. More data can be fed after
+    /// that.
+    fn value(&self) -> u64;
+
+    /// Returns the checksum of all data fed so far as big-endian bytes, as many
+    /// as needed for [`width()`](ChecksumStream::width) bits.
+    fn finalize(self) -> Digest {
+        let len = (self.width() as usize).div_ceil(8);
+        Digest::new(self.value().to_be_bytes()[8 - len..].to_vec())
+    }
+}
+
+/// Computes checksum of given data by a new stream of `checksum`.
+pub(crate) fn checksum_of<C, D>(checksum: &C, data: D) -> Digest
+where C: Checksum, D: IntoIterator<Item = u8> {
+    let mut stream = checksum.stream();
+    for_each_chunk(data, |chunk| stream.update(chunk));
+    stream.finalize()
+}
+
+/// The Adler-32 checksum used by zlib.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Adler32;
+
+impl Adler32 {
+
+    /// Creates the checksum.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Checksum for Adler32 {
+    type Stream = Adler32Stream;
+
+    fn width(&self) -> u8 {
+        32
+    }
+
+    fn stream(&self) -> Adler32Stream {
+        Adler32Stream { a: 1, b: 0 }
+    }
+}
+
+impl Encrypt for Adler32 {
+
+    /// Returns the checksum of given data as 4 big-endian bytes.
+    fn encrypt<D, E>(&self, data_to_encrypt: D) -> E
+    where
+        D:           IntoIterator<Item = u8>,
+        D::IntoIter: ExactSizeIterator,
+        E:           FromIterator<u8>
+    {
+        checksum_of(self, data_to_encrypt).into_iter().collect()
+    }
+}
+
+/// State of [`Adler32`] fed by chunks of data.
+#[derive(Clone, Debug)]
+pub struct Adler32Stream {
+    a: u32,
+    b: u32,
+}
+
+impl ChecksumStream for Adler32Stream {
+
+    fn width(&self) -> u8 {
+        32
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        // The sums cannot overflow in 5552 bytes, so they are reduced only once
+        // per chunk:
+        for chunk in data.chunks(5552) {
+            for &byte in chunk {
+                self.a += byte as u32;
+                self.b += self.a;
+            }
+            self.a %= ADLER_MODULUS;
+            self.b %= ADLER_MODULUS;
+        }
+    }
+
+    fn value(&self) -> u64 {
+        ((self.b as u64) << 16) | self.a as u64
+    }
+}
+
+/// The Fletcher-16 checksum, two sums of bytes modulo 255.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Fletcher16;
+
+impl Fletcher16 {
+
+    /// Creates the checksum.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Checksum for Fletcher16 {
+    type Stream = Fletcher16Stream;
+
+    fn width(&self) -> u8 {
+        16
+    }
+
+    fn stream(&self) -> Fletcher16Stream {
+        Fletcher16Stream::default()
+    }
+}
+
+impl Encrypt for Fletcher16 {
+
+    /// Returns the checksum of given data as 2 big-endian bytes.
+    fn encrypt<D, E>(&self, data_to_encrypt: D) -> E
+    where
+        D:           IntoIterator<Item = u8>,
+        D::IntoIter: ExactSizeIterator,
+        E:           FromIterator<u8>
+    {
+        checksum_of(self, data_to_encrypt).into_iter().collect()
+    }
+}
+
+/// State of [`Fletcher16`] fed by chunks of data.
+#[derive(Clone, Debug, Default)]
+pub struct Fletcher16Stream {
+    sum1: u32,
+    sum2: u32,
+}
+
+impl ChecksumStream for Fletcher16Stream {
+
+    fn width(&self) -> u8 {
+        16
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        for &byte in data {
+            self.sum1 = (self.sum1 + byte as u32) % 255;
+            self.sum2 = (self.sum2 + self.sum1) % 255;
+        }
+    }
+
+    fn value(&self) -> u64 {
+        ((self.sum2 as u64) << 8) | self.sum1 as u64
+    }
+}
+
+/// The Fletcher-32 checksum, two sums of 16-bit little-endian words modulo
+/// 65&#8239;535. Data of odd length are padded by a zero byte.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Fletcher32;
+
+impl Fletcher32 {
+
+    /// Creates the checksum.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Checksum for Fletcher32 {
+    type Stream = Fletcher32Stream;
+
+    fn width(&self) -> u8 {
+        32
+    }
+
+    fn stream(&self) -> Fletcher32Stream {
+        Fletcher32Stream::default()
+    }
+}
+
+impl Encrypt for Fletcher32 {
+
+    /// Returns the checksum of given data as 4 big-endian bytes.
+    fn encrypt<D, E>(&self, data_to_encrypt: D) -> E
+    where
+        D:           IntoIterator<Item = u8>,
+        D::IntoIter: ExactSizeIterator,
+        E:           FromIterator<u8>
+    {
+        checksum_of(self, data_to_encrypt).into_iter().collect()
+    }
+}
+
+/// State of [`Fletcher32`] fed by chunks of data.
+#[derive(Clone, Debug, Default)]
+pub struct Fletcher32Stream {
+    sum1: u32,
+    sum2: u32,
+    /// The first byte of a word whose second byte has not been fed yet.
+    pending: Option<u8>,
+}
+
+impl Fletcher32Stream {
+
+    fn add_word(sum1: &mut u32, sum2: &mut u32, word: u16) {
+        *sum1 = (*sum1 + word as u32) % 65535;
+        *sum2 = (*sum2 + *sum1) % 65535;
+    }
+}
+
+impl ChecksumStream for Fletcher32Stream {
+
+    fn width(&self) -> u8 {
+        32
+    }
+
+    fn update(&mut self, mut data: &[u8]) {
+        if let (Some(low), Some((&high, rest))) = (self.pending, data.split_first()) {
+            Self::add_word(&mut self.sum1, &mut self.sum2, u16::from_le_bytes([low, high]));
+            self.pending = None;
+            data = rest;
+        }
+        let mut words = data.chunks_exact(2);
+        for word in words.by_ref() {
+            Self::add_word(&mut self.sum1, &mut self.sum2, u16::from_le_bytes([word[0], word[1]]));
+        }
+        if let [byte] = words.remainder() {
+            self.pending = Some(*byte);
+        }
+    }
+
+    fn value(&self) -> u64 {
+        let (mut sum1, mut sum2) = (self.sum1, self.sum2);
+        if let Some(byte) = self.pending {
+            Self::add_word(&mut sum1, &mut sum2, byte as u16);
+        }
+        ((sum2 as u64) << 16) | sum1 as u64
+    }
+}
+
+/// Frame of data followed by their [`Checksum`]. Encryption appends the
+/// checksum of the data as big-endian bytes, decryption verifies and strips it.
+///
+/// ```
+/// use mdcrypt::algorithms::Crc;
+/// use mdcrypt::algorithms::CrcParams;
+/// use mdcrypt::algorithms::Framed;
+/// use mdcrypt::Encrypt;
+/// use mdcrypt::TryDecrypt;
+///
+/// let framed = Framed::new(Crc::new(CrcParams::CRC_32).unwrap());
+/// let frame: Vec<u8> = framed.encrypt(b"123456789".to_vec());
+/// assert_eq!(&frame[9..], [0xcb, 0xf4, 0x39, 0x26]);
+/// let data: Vec<u8> = framed.try_decrypt(frame).unwrap();
+/// assert_eq!(data, b"123456789");
+/// ```
+#[derive(Clone, Debug)]
+pub struct Framed<C: Checksum> {
+    checksum: C,
+}
+
+impl<C: Checksum> Framed<C> {
+
+    /// Creates frames protected by given checksum.
+    pub fn new(checksum: C) -> Self {
+        Self { checksum }
+    }
+
+    /// Returns the checksum.
+    pub fn checksum(&self) -> &C {
+        &self.checksum
+    }
+
+    /// Returns number of bytes of the checksum appended to the data.
+    pub fn checksum_len(&self) -> usize {
+        (self.checksum.width() as usize).div_ceil(8)
+    }
+}
+
+impl<C: Checksum> Encrypt for Framed<C> {
+
+    /// Returns the data followed by their checksum.
+    fn encrypt<D, E>(&self, data_to_encrypt: D) -> E
+    where
+        D:           IntoIterator<Item = u8>,
+        D::IntoIter: ExactSizeIterator,
+        E:           FromIterator<u8>
+    {
+        let data: Vec<u8> = data_to_encrypt.into_iter().collect();
+        let checksum = checksum_of(&self.checksum, data.iter().copied());
+        data.into_iter().chain(checksum).collect()
+    }
+}
+
+impl<C: Checksum> TryDecrypt for Framed<C> {
+
+    /// Error type returned when the frame is damaged.
+    type ErrorType = Error;
+
+    /// Verifies the checksum at the end of the frame and returns the data
+    /// without it.
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with the data
+    /// - [`Err`] with [`Error::Malformed`] if the checksum does not match the
+    ///   data or the frame is shorter than the checksum
+    fn try_decrypt<E, D>(
+        &self,
+        encrypted_data: E
+    ) -> Result<D, Self::ErrorType> where
+        E:           IntoIterator<Item = u8>,
+        E::IntoIter: ExactSizeIterator,
+        D:           FromIterator<u8> {
+
+        let mut frame: Vec<u8> = encrypted_data.into_iter().collect();
+        let data_len = frame.len().checked_sub(self.checksum_len())
+            .ok_or_else(|| Error::malformed(format!(
+                "frame of {} bytes is shorter than the {}-byte checksum",
+                frame.len(),
+                self.checksum_len()
+            )))?;
+        let expected = frame.split_off(data_len);
+        // A checksum detects accidental errors only, so a mismatch does not mean
+        // that the data were forged:
+        if checksum_of(&self.checksum, frame.iter().copied()).as_bytes() != expected {
+            return Err(Error::malformed("checksum of the frame does not match the data"))
+        }
+        Ok(frame.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::Crc;
+    use crate::algorithms::CrcParams;
+
+    #[test]
+    fn known_answers() {
+        let test_cases: Vec<(Vec<u8>, Vec<u8>)> = vec![
+            (Adler32::new().encrypt(b"Wikipedia".to_vec()), vec![0x11, 0xe6, 0x03, 0x98]),
+            (Adler32::new().encrypt(b"123456789".to_vec()), vec![0x09, 0x1e, 0x01, 0xde]),
+            (Adler32::new().encrypt(vec![]), vec![0, 0, 0, 1]),
+            (Fletcher16::new().encrypt(b"abcde".to_vec()), vec![0xc8, 0xf0]),
+            (Fletcher16::new().encrypt(b"abcdef".to_vec()), vec![0x20, 0x57]),
+            (Fletcher32::new().encrypt(b"abcde".to_vec()), vec![0xf0, 0x4f, 0xc7, 0x29]),
+            (Fletcher32::new().encrypt(b"abcdef".to_vec()), vec![0x56, 0x50, 0x2d, 0x2a]),
+            (Fletcher32::new().encrypt(b"abcdefgh".to_vec()), vec![0xeb, 0xe1, 0x95, 0x91]),
+        ];
+        for (actual, expected) in test_cases {
+            assert_eq!(actual, expected);
+        }
+    }
+
+    #[test]
+    fn incremental() {
+        let data: Vec<u8> = (0..=255).cycle().take(20001).collect();
+        let expected: [Vec<u8>; 3] = [
+            Adler32::new().encrypt(data.clone()),
+            Fletcher16::new().encrypt(data.clone()),
+            Fletcher32::new().encrypt(data.clone()),
+        ];
+
+        let mut adler = Adler32::new().stream();
+        let mut fletcher16 = Fletcher16::new().stream();
+        let mut fletcher32 = Fletcher32::new().stream();
+        // Chunks of odd lengths split words of Fletcher-32:
+        for chunk in data.chunks(777) {
+            let (first, second) = chunk.split_at(chunk.len() / 3);
+            for part in [first, second] {
+                adler.update(part);
+                fletcher16.update(part);
+                fletcher32.update(part);
+            }
+        }
+        let actual: [Vec<u8>; 3] = [
+            adler.finalize().into(),
+            fletcher16.finalize().into(),
+            fletcher32.finalize().into(),
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn framed() {
+        let framed = Framed::new(Adler32::new());
+        let frame: Vec<u8> = framed.encrypt(b"Wikipedia".to_vec());
+        assert_eq!(frame, b"Wikipedia\x11\xe6\x03\x98");
+        let data: Vec<u8> = framed.try_decrypt(frame.clone()).unwrap();
+        assert_eq!(data, b"Wikipedia");
+
+        let mut damaged = frame;
+        damaged[3] ^= 1;
+        let result: Result<Vec<u8>, _> = framed.try_decrypt(damaged);
+        assert!(matches!(result, Err(Error::Malformed { .. })));
+        let result: Result<Vec<u8>, _> = framed.try_decrypt(vec![0; 3]);
+        assert!(matches!(result, Err(Error::Malformed { .. })));
+
+        // Checksum of empty data is still appended:
+        let framed = Framed::new(Crc::new(CrcParams::CRC_16_ARC).unwrap());
+        let frame: Vec<u8> = framed.encrypt(vec![]);
+        assert_eq!(frame.len(), framed.checksum_len());
+        let data: Vec<u8> = framed.try_decrypt(frame).unwrap();
+        assert!(data.is_empty());
+    }
+}
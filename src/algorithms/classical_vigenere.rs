@@ -0,0 +1,287 @@
+use std::collections::VecDeque;
+use std::iter::FromIterator;
+use std::iter::IntoIterator;
+
+use crate::algorithms::Alphabet;
+use crate::algorithms::alphabet::key_text;
+use crate::decrypt::Decrypt;
+use crate::encrypt::Encrypt;
+use crate::error::Error;
+use crate::key::Key;
+
+/// How [`ClassicalVigenere`] combines a symbol of the text with a symbol of the
+/// key. Symbols are numbered by their index in the alphabet and all arithmetic
+/// is modulo the length of the alphabet.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum VigenereVariant {
+
+    /// The textbook Vigenère cipher, the key is added to the plaintext:
+    /// `C = P + K`.
+    #[default]
+    Vigenere,
+
+    /// The Beaufort cipher, the plaintext is subtracted from the key:
+    /// `C = K - P`. Encryption and decryption are the same operation.
+    Beaufort,
+
+    /// The variant Beaufort cipher, the key is subtracted from the plaintext:
+    /// `C = P - K`. It is the Vigenère cipher with encryption and decryption
+    /// swapped.
+    VariantBeaufort,
+}
+
+impl VigenereVariant {
+
+    fn encrypt(self, plain: usize, key: usize, len: usize) -> usize {
+        match self {
+            Self::Vigenere        => plain + key,
+            Self::Beaufort        => key + len - plain,
+            Self::VariantBeaufort => plain + len - key,
+        }
+    }
+
+    fn decrypt(self, encrypted: usize, key: usize, len: usize) -> usize {
+        match self {
+            Self::Vigenere        => encrypted + len - key,
+            Self::Beaufort        => key + len - encrypted,
+            Self::VariantBeaufort => encrypted + key,
+        }
+    }
+}
+
+/// Which key symbols [`ClassicalVigenere`] uses after the key is exhausted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum KeySchedule {
+
+    /// The key is repeated over the whole text.
+    #[default]
+    Repeating,
+
+    /// The key is followed by the plaintext itself, so that the key is never
+    /// repeated.
+    Autokey,
+}
+
+/// The textbook Vigenère cipher and its relatives working over an [`Alphabet`],
+/// by default the Latin alphabet A&ndash;Z. Unlike [`Vigener`], which adds bytes
+/// modulo 256, it encrypts text to readable text.
+///
+/// Data are treated as UTF-8 text. Only characters of the alphabet are encrypted
+/// and they keep their case; other characters, such as spaces and punctuation,
+/// are passed through unchanged and do not move the position in the key. Bytes
+/// which are not valid UTF-8 are passed through as well.
+///
+/// The cipher can be switched to the Beaufort or the variant Beaufort cipher by
+/// [`with_variant()`] and to the autokey cipher by [`with_schedule()`]. All
+/// combinations share the same key scheduling.
+///
+/// ```
+/// use mdcrypt::algorithms::ClassicalVigenere;
+/// use mdcrypt::Encrypt;
+///
+/// let cipher = ClassicalVigenere::new("LEMON").unwrap();
+/// let encrypted: Vec<u8> = cipher.encrypt(b"Attack at dawn!".to_vec());
+/// assert_eq!(encrypted, b"Lxfopv ef rnhr!");
+/// ```
+///
+/// [`Vigener`]: crate::algorithms::Vigener
+/// [`with_variant()`]: ClassicalVigenere::with_variant
+/// [`with_schedule()`]: ClassicalVigenere::with_schedule
+#[derive(Clone, Debug)]
+pub struct ClassicalVigenere {
+    alphabet: Alphabet,
+    key: Vec<usize>,
+    variant: VigenereVariant,
+    schedule: KeySchedule,
+}
+
+impl ClassicalVigenere {
+
+    /// Creates the Vigenère cipher over the Latin alphabet A&ndash;Z.
+    ///
+    /// # Parameters
+    ///
+    /// - `key`: letters of the key; the case does not matter
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with the cipher
+    /// - [`Err`] with [`Error::InvalidKey`] if the key is empty or contains
+    ///   a character which is not a Latin letter
+    pub fn new(key: &str) -> Result<Self, Error> {
+        Self::with_alphabet(Alphabet::latin(), key)
+    }
+
+    /// Creates the Vigenère cipher over given alphabet.
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with the cipher
+    /// - [`Err`] with [`Error::InvalidKey`] if the key is empty or contains
+    ///   a character outside the alphabet
+    pub fn with_alphabet(alphabet: Alphabet, key: &str) -> Result<Self, Error> {
+        let key = alphabet.key_indices(key)?;
+        Ok(Self {
+            alphabet,
+            key,
+            variant: VigenereVariant::default(),
+            schedule: KeySchedule::default(),
+        })
+    }
+
+    /// Creates the Vigenère cipher over given alphabet with key stored in a [`Key`]
+    /// as UTF-8 text.
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with the cipher
+    /// - [`Err`] with [`Error::InvalidKey`] if the key is not UTF-8 text, or it
+    ///   is empty, or it contains a character outside the alphabet
+    pub fn from_key(alphabet: Alphabet, key: &Key) -> Result<Self, Error> {
+        Self::with_alphabet(alphabet, key_text(key)?)
+    }
+
+    /// Sets how symbols of the text are combined with symbols of the key.
+    pub fn with_variant(mut self, variant: VigenereVariant) -> Self {
+        self.variant = variant;
+        self
+    }
+
+    /// Sets which key symbols are used after the key is exhausted.
+    pub fn with_schedule(mut self, schedule: KeySchedule) -> Self {
+        self.schedule = schedule;
+        self
+    }
+
+    /// Returns the alphabet of the cipher.
+    pub fn alphabet(&self) -> &Alphabet {
+        &self.alphabet
+    }
+
+    /// Returns the variant of the cipher.
+    pub fn variant(&self) -> VigenereVariant {
+        self.variant
+    }
+
+    /// Returns the key schedule of the cipher.
+    pub fn schedule(&self) -> KeySchedule {
+        self.schedule
+    }
+
+    /// Maps symbols of the text with `map`, which gets index of the symbol and of
+    /// the key symbol and returns index of the resulting symbol and of the
+    /// plaintext symbol.
+    fn apply<F>(&self, text: &[u8], map: F) -> Vec<u8>
+    where F: Fn(usize, usize) -> (usize, usize) {
+        // Key symbols waiting to be used; every used symbol is replaced at the
+        // end by either itself or the plaintext symbol:
+        let mut key_stream: VecDeque<usize> = self.key.iter().copied().collect();
+        self.alphabet.map_text(text, |index| {
+            let key = key_stream.pop_front().unwrap();
+            let (output, plain) = map(index, key);
+            key_stream.push_back(match self.schedule {
+                KeySchedule::Repeating => key,
+                KeySchedule::Autokey   => plain % self.alphabet.len(),
+            });
+            output
+        })
+    }
+}
+
+impl Encrypt for ClassicalVigenere {
+
+    fn encrypt<D, E>(&self, data_to_encrypt: D) -> E
+    where
+        D:           IntoIterator<Item = u8>,
+        D::IntoIter: ExactSizeIterator,
+        E:           FromIterator<u8>
+    {
+        let data: Vec<u8> = data_to_encrypt.into_iter().collect();
+        let len = self.alphabet.len();
+        self.apply(&data, |plain, key| (self.variant.encrypt(plain, key, len), plain))
+            .into_iter()
+            .collect()
+    }
+}
+
+impl Decrypt for ClassicalVigenere {
+
+    fn decrypt<E, D>(&self, encrypted_data: E) -> D
+    where
+        E:           IntoIterator<Item = u8>,
+        E::IntoIter: ExactSizeIterator,
+        D:           FromIterator<u8>
+    {
+        let data: Vec<u8> = encrypted_data.into_iter().collect();
+        let len = self.alphabet.len();
+        self.apply(&data, |encrypted, key| {
+            let plain = self.variant.decrypt(encrypted, key, len);
+            (plain, plain)
+        })
+            .into_iter()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::round_trip_text;
+
+    #[test]
+    fn vigenere() {
+        let cipher = ClassicalVigenere::new("LEMON").unwrap();
+        assert_eq!(round_trip_text(&cipher, "ATTACKATDAWN"), "LXFOPVEFRNHR");
+        assert_eq!(round_trip_text(&cipher, "attack at dawn"), "lxfopv ef rnhr");
+        assert_eq!(round_trip_text(&cipher, "Attack, at dawn! 42"), "Lxfopv, ef rnhr! 42");
+        assert_eq!(round_trip_text(&ClassicalVigenere::new("lemon").unwrap(), "ATTACK"), "LXFOPV");
+    }
+
+    #[test]
+    fn variants() {
+        let beaufort = ClassicalVigenere::new("FORTIFICATION").unwrap()
+            .with_variant(VigenereVariant::Beaufort);
+        assert_eq!(
+            round_trip_text(&beaufort, "DEFENDTHEEASTWALLOFTHECASTLE"),
+            "CKMPVCPVWPIWUJOGIUAPVWRIWUUK"
+        );
+        let encrypted: Vec<u8> = beaufort.encrypt(b"Defend the east".to_vec());
+        let reencrypted: Vec<u8> = beaufort.encrypt(encrypted);
+        assert_eq!(reencrypted, b"Defend the east");
+
+        let variant = ClassicalVigenere::new("LEMON").unwrap()
+            .with_variant(VigenereVariant::VariantBeaufort);
+        assert_eq!(round_trip_text(&variant, "LXFOPVEFRNHR"), "ATTACKATDAWN");
+
+        let autokey = ClassicalVigenere::new("QUEENLY").unwrap()
+            .with_schedule(KeySchedule::Autokey);
+        assert_eq!(round_trip_text(&autokey, "ATTACKATDAWN"), "QNXEPVYTWTWP");
+        assert_eq!(round_trip_text(&autokey, "Attack at dawn."), "Qnxepv yt wtwp.");
+
+        let autokey_beaufort = ClassicalVigenere::new("KEY").unwrap()
+            .with_variant(VigenereVariant::Beaufort)
+            .with_schedule(KeySchedule::Autokey);
+        round_trip_text(&autokey_beaufort, "The quick brown fox jumps over the lazy dog");
+    }
+
+    #[test]
+    fn custom_alphabet() {
+        let czech = Alphabet::new("AÁBCČDĎEÉĚFGHIÍJKLMNŇOÓPQRŘSŠTŤUÚŮVWXYÝZŽ").unwrap();
+        let cipher = ClassicalVigenere::with_alphabet(czech, "Žluťoučký").unwrap();
+        let encrypted = round_trip_text(&cipher, "Příliš žluťoučký kůň úpěl ďábelské ódy.");
+        assert_ne!(encrypted, "Příliš žluťoučký kůň úpěl ďábelské ódy.");
+        assert!(encrypted.starts_with(char::is_uppercase));
+
+        let digits = Alphabet::new("0123456789").unwrap();
+        let cipher = ClassicalVigenere::from_key(digits, &Key::new(b"31415".to_vec())).unwrap();
+        assert_eq!(round_trip_text(&cipher, "PIN: 0000-1234"), "PIN: 3141-6548");
+    }
+
+    #[test]
+    fn invalid_key() {
+        assert!(matches!(ClassicalVigenere::new(""), Err(Error::InvalidKey { .. })));
+        assert!(matches!(ClassicalVigenere::new("LEMON TREE"), Err(Error::InvalidKey { .. })));
+        let invalid = Key::new(vec![0xff]);
+        assert!(ClassicalVigenere::from_key(Alphabet::latin(), &invalid).is_err());
+    }
+}
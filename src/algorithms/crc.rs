@@ -0,0 +1,302 @@
+use std::iter::FromIterator;
+use std::iter::IntoIterator;
+use std::sync::Arc;
+
+use crate::algorithms::checksum::checksum_of;
+use crate::algorithms::Checksum;
+use crate::algorithms::ChecksumStream;
+use crate::encrypt::Encrypt;
+use crate::error::Error;
+
+/// Parameters of a cyclic redundancy check in the Rocksoft model, which is used
+/// by the catalogue of CRC algorithms maintained by Greg Cook. Associated
+/// constants such as [`CrcParams::CRC_32`] contain the commonly used parameter
+/// sets, named as in the catalogue.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CrcParams {
+    /// Number of bits of the CRC, from 1 to 64.
+    pub width: u8,
+    /// Generator polynomial without the highest term, not reflected.
+    pub poly: u64,
+    /// Initial value of the register, not reflected.
+    pub init: u64,
+    /// Whether bits of every input byte are processed from the least significant.
+    pub reflect_in: bool,
+    /// Whether the final register is reflected before `xor_out` is applied.
+    pub reflect_out: bool,
+    /// Value XORed with the final register.
+    pub xor_out: u64,
+    /// CRC of the ASCII string `123456789`.
+    pub check: u64,
+}
+
+impl CrcParams {
+
+    /// CRC-8/SMBUS.
+    pub const CRC_8: Self = Self {
+        width: 8, poly: 0x07, init: 0x00, reflect_in: false, reflect_out: false,
+        xor_out: 0x00, check: 0xf4,
+    };
+
+    /// CRC-16/ARC, also known as CRC-16 or CRC-16/IBM.
+    pub const CRC_16_ARC: Self = Self {
+        width: 16, poly: 0x8005, init: 0x0000, reflect_in: true, reflect_out: true,
+        xor_out: 0x0000, check: 0xbb3d,
+    };
+
+    /// CRC-16/IBM-3740, also known as CRC-16/CCITT-FALSE.
+    pub const CRC_16_IBM_3740: Self = Self {
+        width: 16, poly: 0x1021, init: 0xffff, reflect_in: false, reflect_out: false,
+        xor_out: 0x0000, check: 0x29b1,
+    };
+
+    /// CRC-16/KERMIT, also known as CRC-16/CCITT.
+    pub const CRC_16_KERMIT: Self = Self {
+        width: 16, poly: 0x1021, init: 0x0000, reflect_in: true, reflect_out: true,
+        xor_out: 0x0000, check: 0x2189,
+    };
+
+    /// CRC-16/XMODEM.
+    pub const CRC_16_XMODEM: Self = Self {
+        width: 16, poly: 0x1021, init: 0x0000, reflect_in: false, reflect_out: false,
+        xor_out: 0x0000, check: 0x31c3,
+    };
+
+    /// CRC-32/ISO-HDLC used by Ethernet, zip and PNG.
+    pub const CRC_32: Self = Self {
+        width: 32, poly: 0x04c11db7, init: 0xffffffff, reflect_in: true, reflect_out: true,
+        xor_out: 0xffffffff, check: 0xcbf43926,
+    };
+
+    /// CRC-32/BZIP2.
+    pub const CRC_32_BZIP2: Self = Self {
+        width: 32, poly: 0x04c11db7, init: 0xffffffff, reflect_in: false, reflect_out: false,
+        xor_out: 0xffffffff, check: 0xfc891918,
+    };
+
+    /// CRC-32/ISCSI, also known as CRC-32C (Castagnoli).
+    pub const CRC_32C: Self = Self {
+        width: 32, poly: 0x1edc6f41, init: 0xffffffff, reflect_in: true, reflect_out: true,
+        xor_out: 0xffffffff, check: 0xe3069283,
+    };
+
+    /// CRC-64/XZ, also known as CRC-64/GO-ECMA.
+    pub const CRC_64_XZ: Self = Self {
+        width: 64, poly: 0x42f0e1eba9ea3693, init: u64::MAX, reflect_in: true,
+        reflect_out: true, xor_out: u64::MAX, check: 0x995dc9bbdf1939fa,
+    };
+
+    /// CRC-64/ECMA-182.
+    pub const CRC_64_ECMA_182: Self = Self {
+        width: 64, poly: 0x42f0e1eba9ea3693, init: 0, reflect_in: false, reflect_out: false,
+        xor_out: 0, check: 0x6c40df5f0b497347,
+    };
+
+    /// Returns mask of the lowest `width` bits.
+    fn mask(&self) -> u64 {
+        u64::MAX >> (64 - self.width)
+    }
+}
+
+/// Cyclic redundancy check with given [`CrcParams`], computed by a table of 256
+/// entries. It implements [`Encrypt`], which returns the CRC as big-endian
+/// bytes, and [`Checksum`], whose [`stream()`] computes the CRC incrementally.
+///
+/// ```
+/// use mdcrypt::algorithms::Checksum;
+/// use mdcrypt::algorithms::ChecksumStream;
+/// use mdcrypt::algorithms::Crc;
+/// use mdcrypt::algorithms::CrcParams;
+///
+/// let mut crc = Crc::new(CrcParams::CRC_32C).unwrap().stream();
+/// crc.update(b"1234");
+/// crc.update(b"56789");
+/// assert_eq!(crc.value(), 0xe3069283);
+/// ```
+///
+/// [`stream()`]: Checksum::stream
+#[derive(Clone, Debug)]
+pub struct Crc {
+    params: CrcParams,
+    /// The table, shared with streams of the CRC.
+    table: Arc<[u64; 256]>,
+}
+
+impl Crc {
+
+    /// Creates the CRC and computes its table.
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with the CRC
+    /// - [`Err`] with [`Error::InvalidParameters`] if the width is not between
+    ///   1 and 64, or the polynomial, the initial value or `xor_out` do not fit
+    ///   into the width
+    pub fn new(params: CrcParams) -> Result<Self, Error> {
+        if !(1..=64).contains(&params.width) {
+            return Err(Error::invalid_parameter("width", "must be between 1 and 64"))
+        }
+        for (name, value) in [("poly", params.poly), ("init", params.init),
+                ("xor_out", params.xor_out)] {
+            if value & !params.mask() != 0 {
+                return Err(Error::invalid_parameter(
+                    name,
+                    format!("does not fit into {} bits", params.width)
+                ))
+            }
+        }
+
+        let shift = 64 - params.width as u32;
+        let mut table = Box::new([0; 256]);
+        for (byte, entry) in table.iter_mut().enumerate() {
+            *entry = match params.reflect_in {
+                true  => (0..8).fold(byte as u64, |value, _| match value & 1 {
+                    1 => (value >> 1) ^ (params.poly.reverse_bits() >> shift),
+                    _ => value >> 1,
+                }),
+                false => (0..8).fold((byte as u64) << 56, |value, _| match value >> 63 {
+                    1 => (value << 1) ^ (params.poly << shift),
+                    _ => value << 1,
+                }),
+            };
+        }
+        Ok(Self { params, table: table.into() })
+    }
+
+    /// Returns parameters of the CRC.
+    pub fn params(&self) -> &CrcParams {
+        &self.params
+    }
+}
+
+impl Checksum for Crc {
+    type Stream = CrcStream;
+
+    fn width(&self) -> u8 {
+        self.params.width
+    }
+
+    fn stream(&self) -> CrcStream {
+        let shift = 64 - self.params.width as u32;
+        let register = match self.params.reflect_in {
+            true  => self.params.init.reverse_bits() >> shift,
+            false => self.params.init << shift,
+        };
+        CrcStream { params: self.params, table: Arc::clone(&self.table), register }
+    }
+}
+
+impl Encrypt for Crc {
+
+    /// Returns the CRC of given data as big-endian bytes.
+    fn encrypt<D, E>(&self, data_to_encrypt: D) -> E
+    where
+        D:           IntoIterator<Item = u8>,
+        D::IntoIter: ExactSizeIterator,
+        E:           FromIterator<u8>
+    {
+        checksum_of(self, data_to_encrypt).into_iter().collect()
+    }
+}
+
+/// State of [`Crc`] fed by chunks of data.
+#[derive(Clone, Debug)]
+pub struct CrcStream {
+    params: CrcParams,
+    table: Arc<[u64; 256]>,
+    /// The register, aligned to the most significant bit if the input is not
+    /// reflected and to the least significant bit otherwise.
+    register: u64,
+}
+
+impl ChecksumStream for CrcStream {
+
+    fn width(&self) -> u8 {
+        self.params.width
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        let table = &self.table;
+        self.register = match self.params.reflect_in {
+            true  => data.iter().fold(self.register, |register, &byte| {
+                table[((register ^ byte as u64) & 0xff) as usize] ^ (register >> 8)
+            }),
+            false => data.iter().fold(self.register, |register, &byte| {
+                table[((register >> 56) ^ byte as u64) as usize] ^ (register << 8)
+            }),
+        };
+    }
+
+    fn value(&self) -> u64 {
+        let shift = 64 - self.params.width as u32;
+        // Value of the register in the bit order of the output:
+        let register = match (self.params.reflect_in, self.params.reflect_out) {
+            (true, true)   => self.register,
+            (true, false)  => self.register.reverse_bits() >> shift,
+            (false, true)  => self.register.reverse_bits(),
+            (false, false) => self.register >> shift,
+        };
+        register ^ self.params.xor_out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parameter sets from the catalogue with unusual widths or reflection.
+    const CRC_5_USB: CrcParams = CrcParams {
+        width: 5, poly: 0x05, init: 0x1f, reflect_in: true, reflect_out: true,
+        xor_out: 0x1f, check: 0x19,
+    };
+    const CRC_12_UMTS: CrcParams = CrcParams {
+        width: 12, poly: 0x80f, init: 0x000, reflect_in: false, reflect_out: true,
+        xor_out: 0x000, check: 0xdaf,
+    };
+    const CRC_3_GSM: CrcParams = CrcParams {
+        width: 3, poly: 0x3, init: 0x0, reflect_in: false, reflect_out: false,
+        xor_out: 0x7, check: 0x4,
+    };
+
+    #[test]
+    fn check_values() {
+        for params in [CrcParams::CRC_8, CrcParams::CRC_16_ARC, CrcParams::CRC_16_IBM_3740,
+                CrcParams::CRC_16_KERMIT, CrcParams::CRC_16_XMODEM, CrcParams::CRC_32,
+                CrcParams::CRC_32_BZIP2, CrcParams::CRC_32C, CrcParams::CRC_64_XZ,
+                CrcParams::CRC_64_ECMA_182, CRC_5_USB, CRC_12_UMTS, CRC_3_GSM] {
+            let crc = Crc::new(params).unwrap();
+            let bytes: Vec<u8> = crc.encrypt(b"123456789".to_vec());
+            let len = (params.width as usize).div_ceil(8);
+            assert_eq!(bytes, params.check.to_be_bytes()[8 - len..], "{:?}", params);
+        }
+    }
+
+    #[test]
+    fn incremental() {
+        let data: Vec<u8> = (0..=255).cycle().take(20000).collect();
+        for params in [CrcParams::CRC_32, CrcParams::CRC_16_IBM_3740, CRC_12_UMTS] {
+            let expected: Vec<u8> = Crc::new(params).unwrap().encrypt(data.clone());
+            let crc = Crc::new(params).unwrap();
+            let mut stream = crc.stream();
+            for chunk in data.chunks(777) {
+                stream.update(chunk);
+            }
+            assert_eq!(stream.finalize().as_bytes(), &expected[..]);
+            let mut stream = crc.stream();
+            stream.update(b"123456789");
+            assert_eq!(stream.value(), params.check);
+        }
+    }
+
+    #[test]
+    fn invalid() {
+        let params = CrcParams { width: 0, ..CrcParams::CRC_8 };
+        assert!(matches!(Crc::new(params), Err(Error::InvalidParameters { .. })));
+        let params = CrcParams { width: 65, ..CrcParams::CRC_8 };
+        assert!(Crc::new(params).is_err());
+        let params = CrcParams { poly: 0x107, ..CrcParams::CRC_8 };
+        assert!(Crc::new(params).is_err());
+        let params = CrcParams { xor_out: 0x1ffff, ..CrcParams::CRC_16_ARC };
+        assert!(Crc::new(params).is_err());
+    }
+}
@@ -0,0 +1,563 @@
+use std::iter::FromIterator;
+use std::iter::IntoIterator;
+
+use crate::decrypt::TryDecrypt;
+use crate::encrypt::Encrypt;
+use crate::error::EncodingErrorKind;
+use crate::error::Error;
+
+/// Symbol appended to the encoded text so that its length is a multiple of the
+/// group length.
+const PADDING: u8 = b'=';
+
+const BASE16: Radix = Radix {
+    name: "Base16",
+    alphabet: b"0123456789abcdef",
+    bits: 4,
+    group_len: 2,
+};
+
+const BASE32: Radix = Radix {
+    name: "Base32",
+    alphabet: b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567",
+    bits: 5,
+    group_len: 8,
+};
+
+const CROCKFORD: Radix = Radix {
+    name: "Crockford's Base32",
+    alphabet: b"0123456789ABCDEFGHJKMNPQRSTVWXYZ",
+    bits: 5,
+    group_len: 8,
+};
+
+const BASE64: Radix = Radix {
+    name: "Base64",
+    alphabet: b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/",
+    bits: 6,
+    group_len: 4,
+};
+
+const BASE64_URL: Radix = Radix {
+    name: "URL-safe Base64",
+    alphabet: b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_",
+    bits: 6,
+    group_len: 4,
+};
+
+/// Encoding of data by symbols of an alphabet whose size is a power of two, so
+/// that every symbol carries `bits` bits.
+struct Radix {
+    name: &'static str,
+    alphabet: &'static [u8],
+    bits: u32,
+    /// Number of symbols encoding a whole number of bytes, the unit of padding.
+    group_len: usize,
+}
+
+impl Radix {
+
+    /// Encodes data, padding the output to a multiple of the group length if
+    /// `padding` is `true`.
+    fn encode<D: IntoIterator<Item = u8>>(&self, data: D, padding: bool) -> Vec<u8> {
+        let mask = (1 << self.bits) - 1;
+        let (mut buffer, mut buffered) = (0u32, 0);
+        let mut encoded = Vec::new();
+        for byte in data {
+            buffer = (buffer << 8 | byte as u32) & 0xffff;
+            buffered += 8;
+            while buffered >= self.bits {
+                buffered -= self.bits;
+                encoded.push(self.alphabet[(buffer >> buffered & mask) as usize]);
+            }
+        }
+        if buffered > 0 {
+            encoded.push(self.alphabet[(buffer << (self.bits - buffered) & mask) as usize]);
+        }
+        if padding {
+            let padding_len = (self.group_len - encoded.len() % self.group_len) % self.group_len;
+            encoded.resize(encoded.len() + padding_len, PADDING);
+        }
+        encoded
+    }
+
+    /// Decodes text, which must be padded if and only if `padding` is `true`.
+    ///
+    /// # Parameters
+    ///
+    /// - `symbols`: symbols of the text with their positions in the text
+    /// - `padding`: whether the text must be padded
+    /// - `normalize`: maps a symbol to its form in the alphabet, e.g. to
+    ///   uppercase if the encoding is case-insensitive
+    fn decode(&self, symbols: Vec<(usize, u8)>, padding: bool, normalize: fn(u8) -> u8)
+            -> Result<Vec<u8>, Error> {
+        let invalid = |position, kind| Error::invalid_encoding(self.name, position, kind);
+        let padding_len = symbols.iter()
+            .rev()
+            .take_while(|&&(_, symbol)| symbol == PADDING)
+            .count();
+        let data_len = symbols.len() - padding_len;
+        // Padding inside the text is reported where it is, not as a wrong length:
+        let misplaced = symbols[..data_len].iter().find(|&&(_, symbol)| symbol == PADDING);
+        if let Some(&(position, _)) = misplaced {
+            return Err(invalid(position, EncodingErrorKind::BadPadding))
+        }
+        if padding && symbols.len() % self.group_len != 0 {
+            // The padding is too short, so it is missing after the last symbol:
+            let end = symbols.last().map_or(0, |&(position, _)| position + 1);
+            return Err(invalid(end, EncodingErrorKind::BadPadding))
+        }
+        if (!padding && padding_len > 0) || padding_len >= self.group_len {
+            return Err(invalid(symbols[data_len].0, EncodingErrorKind::BadPadding))
+        }
+        // Number of symbols of the last group must encode a whole number of bytes:
+        let remainder = data_len % self.group_len;
+        let byte_count = remainder * self.bits as usize / 8;
+        if (byte_count * 8).div_ceil(self.bits as usize) != remainder {
+            return Err(invalid(symbols[data_len - 1].0, EncodingErrorKind::TruncatedGroup))
+        }
+
+        let (mut buffer, mut buffered) = (0u32, 0);
+        let mut decoded = Vec::with_capacity(data_len * self.bits as usize / 8);
+        for &(position, symbol) in &symbols[..data_len] {
+            let value = self.alphabet.iter()
+                .position(|&letter| letter == normalize(symbol))
+                .ok_or_else(|| invalid(position, EncodingErrorKind::InvalidSymbol))?;
+            buffer = (buffer << self.bits | value as u32) & 0xffff;
+            buffered += self.bits;
+            if buffered >= 8 {
+                buffered -= 8;
+                decoded.push((buffer >> buffered) as u8);
+            }
+        }
+        // Bits not covered by the decoded bytes must be zero:
+        if buffer & ((1 << buffered) - 1) != 0 {
+            return Err(invalid(symbols[data_len - 1].0, EncodingErrorKind::TrailingBits))
+        }
+        Ok(decoded)
+    }
+}
+
+/// Base16 encoding (RFC 4648, section 8), which writes every byte as two
+/// hexadecimal digits. Decoding accepts both lowercase and uppercase digits.
+///
+/// ```
+/// use mdcrypt::algorithms::Base16;
+/// use mdcrypt::Encrypt;
+///
+/// let encoded: Vec<u8> = Base16::new().encrypt(vec![0xca, 0xfe]);
+/// assert_eq!(encoded, b"cafe");
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Base16 {
+    uppercase: bool,
+}
+
+impl Base16 {
+
+    /// Creates the encoding writing lowercase digits.
+    pub fn new() -> Self {
+        Self { uppercase: false }
+    }
+
+    /// Creates the encoding writing uppercase digits.
+    pub fn uppercase() -> Self {
+        Self { uppercase: true }
+    }
+
+    /// Encodes given data and returns them as a string.
+    pub fn encode_to_string(&self, data: &[u8]) -> String {
+        String::from_utf8(self.encrypt(data.iter().copied())).expect("Base16 is ASCII")
+    }
+}
+
+impl Encrypt for Base16 {
+
+    fn encrypt<D, E>(&self, data_to_encrypt: D) -> E
+    where
+        D:           IntoIterator<Item = u8>,
+        D::IntoIter: ExactSizeIterator,
+        E:           FromIterator<u8>
+    {
+        let encoded = BASE16.encode(data_to_encrypt, false);
+        match self.uppercase {
+            true  => encoded.into_iter().map(|symbol| symbol.to_ascii_uppercase()).collect(),
+            false => encoded.into_iter().collect(),
+        }
+    }
+}
+
+impl TryDecrypt for Base16 {
+
+    /// Error type returned when the text is not a valid Base16.
+    type ErrorType = Error;
+
+    /// Decodes Base16 text.
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with the decoded data
+    /// - [`Err`] with [`Error::InvalidEncoding`] if the text contains a symbol
+    ///   other than a hexadecimal digit or has an odd length
+    fn try_decrypt<E, D>(
+        &self,
+        encrypted_data: E
+    ) -> Result<D, Self::ErrorType> where
+        E:           IntoIterator<Item = u8>,
+        E::IntoIter: ExactSizeIterator,
+        D:           FromIterator<u8> {
+
+        let symbols = encrypted_data.into_iter().enumerate().collect();
+        Ok(BASE16.decode(symbols, false, |symbol| symbol.to_ascii_lowercase())?
+            .into_iter()
+            .collect())
+    }
+}
+
+/// Alphabet of [`Base32`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Base32Alphabet {
+
+    /// The standard alphabet `A`&ndash;`Z`, `2`&ndash;`7` (RFC 4648, section 6).
+    #[default]
+    Standard,
+
+    /// Douglas Crockford's alphabet of digits and uppercase letters without
+    /// I, L, O and U. Decoding ignores case and hyphens and reads I and L as 1
+    /// and O as 0.
+    Crockford,
+}
+
+/// Base32 encoding, which writes every 5 bytes as 8 symbols.
+///
+/// ```
+/// use mdcrypt::algorithms::Base32;
+/// use mdcrypt::algorithms::Base32Alphabet;
+/// use mdcrypt::Encrypt;
+///
+/// let encoded: Vec<u8> = Base32::default().encrypt(b"foo".to_vec());
+/// assert_eq!(encoded, b"MZXW6===");
+/// let encoded: Vec<u8> = Base32::new(Base32Alphabet::Crockford).encrypt(b"foo".to_vec());
+/// assert_eq!(encoded, b"CSQPY");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Base32 {
+    alphabet: Base32Alphabet,
+    padding: bool,
+}
+
+impl Base32 {
+
+    /// Creates the encoding with given alphabet. The standard alphabet is padded
+    /// by `=` and Crockford's one is not, as their specifications say. Use
+    /// [`with_padding()`](Base32::with_padding) to change it.
+    pub fn new(alphabet: Base32Alphabet) -> Self {
+        Self { alphabet, padding: alphabet == Base32Alphabet::Standard }
+    }
+
+    /// Sets whether the encoded text is padded by `=` to a multiple of 8
+    /// symbols. Decoding then requires the padding, or rejects it.
+    pub fn with_padding(mut self, padding: bool) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Returns the alphabet of the encoding.
+    pub fn alphabet(&self) -> Base32Alphabet {
+        self.alphabet
+    }
+
+    /// Returns `true` if the encoded text is padded.
+    pub fn is_padded(&self) -> bool {
+        self.padding
+    }
+
+    /// Encodes given data and returns them as a string.
+    pub fn encode_to_string(&self, data: &[u8]) -> String {
+        String::from_utf8(self.encrypt(data.iter().copied())).expect("Base32 is ASCII")
+    }
+
+    fn radix(&self) -> &'static Radix {
+        match self.alphabet {
+            Base32Alphabet::Standard  => &BASE32,
+            Base32Alphabet::Crockford => &CROCKFORD,
+        }
+    }
+}
+
+impl Default for Base32 {
+    fn default() -> Self {
+        Self::new(Base32Alphabet::Standard)
+    }
+}
+
+impl Encrypt for Base32 {
+
+    fn encrypt<D, E>(&self, data_to_encrypt: D) -> E
+    where
+        D:           IntoIterator<Item = u8>,
+        D::IntoIter: ExactSizeIterator,
+        E:           FromIterator<u8>
+    {
+        self.radix().encode(data_to_encrypt, self.padding).into_iter().collect()
+    }
+}
+
+impl TryDecrypt for Base32 {
+
+    /// Error type returned when the text is not a valid Base32.
+    type ErrorType = Error;
+
+    /// Decodes Base32 text.
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with the decoded data
+    /// - [`Err`] with [`Error::InvalidEncoding`] if the text contains a symbol
+    ///   outside of the alphabet, its padding is missing, misplaced or not
+    ///   expected, or its length or trailing bits cannot be produced by encoding
+    fn try_decrypt<E, D>(
+        &self,
+        encrypted_data: E
+    ) -> Result<D, Self::ErrorType> where
+        E:           IntoIterator<Item = u8>,
+        E::IntoIter: ExactSizeIterator,
+        D:           FromIterator<u8> {
+
+        let symbols = encrypted_data.into_iter().enumerate();
+        let decoded = match self.alphabet {
+            Base32Alphabet::Standard  => BASE32.decode(symbols.collect(), self.padding, |symbol| {
+                symbol
+            }),
+            Base32Alphabet::Crockford => CROCKFORD.decode(
+                symbols.filter(|&(_, symbol)| symbol != b'-').collect(),
+                self.padding,
+                |symbol| match symbol.to_ascii_uppercase() {
+                    b'I' | b'L' => b'1',
+                    b'O'        => b'0',
+                    symbol      => symbol,
+                }
+            ),
+        }?;
+        Ok(decoded.into_iter().collect())
+    }
+}
+
+/// Alphabet of [`Base64`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Base64Alphabet {
+
+    /// The standard alphabet ending with `+` and `/` (RFC 4648, section 4).
+    #[default]
+    Standard,
+
+    /// The URL and filename safe alphabet ending with `-` and `_` (RFC 4648,
+    /// section 5).
+    UrlSafe,
+}
+
+/// Base64 encoding, which writes every 3 bytes as 4 symbols.
+///
+/// ```
+/// use mdcrypt::algorithms::Base64;
+/// use mdcrypt::algorithms::Base64Alphabet;
+/// use mdcrypt::Encrypt;
+///
+/// let encoded: Vec<u8> = Base64::default().encrypt(vec![0xfb, 0xff]);
+/// assert_eq!(encoded, b"+/8=");
+/// let url_safe = Base64::new(Base64Alphabet::UrlSafe).with_padding(false);
+/// let encoded: Vec<u8> = url_safe.encrypt(vec![0xfb, 0xff]);
+/// assert_eq!(encoded, b"-_8");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Base64 {
+    alphabet: Base64Alphabet,
+    padding: bool,
+}
+
+impl Base64 {
+
+    /// Creates the encoding with given alphabet, padded by `=`. Use
+    /// [`with_padding()`](Base64::with_padding) to omit the padding.
+    pub fn new(alphabet: Base64Alphabet) -> Self {
+        Self { alphabet, padding: true }
+    }
+
+    /// Sets whether the encoded text is padded by `=` to a multiple of 4
+    /// symbols. Decoding then requires the padding, or rejects it.
+    pub fn with_padding(mut self, padding: bool) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Returns the alphabet of the encoding.
+    pub fn alphabet(&self) -> Base64Alphabet {
+        self.alphabet
+    }
+
+    /// Returns `true` if the encoded text is padded.
+    pub fn is_padded(&self) -> bool {
+        self.padding
+    }
+
+    /// Encodes given data and returns them as a string.
+    pub fn encode_to_string(&self, data: &[u8]) -> String {
+        String::from_utf8(self.encrypt(data.iter().copied())).expect("Base64 is ASCII")
+    }
+
+    fn radix(&self) -> &'static Radix {
+        match self.alphabet {
+            Base64Alphabet::Standard => &BASE64,
+            Base64Alphabet::UrlSafe  => &BASE64_URL,
+        }
+    }
+}
+
+impl Default for Base64 {
+    fn default() -> Self {
+        Self::new(Base64Alphabet::Standard)
+    }
+}
+
+impl Encrypt for Base64 {
+
+    fn encrypt<D, E>(&self, data_to_encrypt: D) -> E
+    where
+        D:           IntoIterator<Item = u8>,
+        D::IntoIter: ExactSizeIterator,
+        E:           FromIterator<u8>
+    {
+        self.radix().encode(data_to_encrypt, self.padding).into_iter().collect()
+    }
+}
+
+impl TryDecrypt for Base64 {
+
+    /// Error type returned when the text is not a valid Base64.
+    type ErrorType = Error;
+
+    /// Decodes Base64 text. Whitespace is not allowed.
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with the decoded data
+    /// - [`Err`] with [`Error::InvalidEncoding`] if the text contains a symbol
+    ///   outside of the alphabet, its padding is missing, misplaced or not
+    ///   expected, or its length or trailing bits cannot be produced by encoding
+    fn try_decrypt<E, D>(
+        &self,
+        encrypted_data: E
+    ) -> Result<D, Self::ErrorType> where
+        E:           IntoIterator<Item = u8>,
+        E::IntoIter: ExactSizeIterator,
+        D:           FromIterator<u8> {
+
+        let symbols = encrypted_data.into_iter().enumerate().collect();
+        Ok(self.radix().decode(symbols, self.padding, |symbol| symbol)?.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::EncodingErrorKind::BadPadding;
+    use crate::error::EncodingErrorKind::InvalidSymbol;
+    use crate::error::EncodingErrorKind::TrailingBits;
+    use crate::error::EncodingErrorKind::TruncatedGroup;
+    use crate::test_util::assert_invalid_encoding;
+
+    /// Test vectors of RFC 4648, section 10.
+    const DATA: [&str; 7] = ["", "f", "fo", "foo", "foob", "fooba", "foobar"];
+
+    fn round_trip<C>(codec: C, expected: &[&str])
+    where C: Encrypt + TryDecrypt<ErrorType = Error> {
+        for (data, expected) in DATA.iter().zip(expected) {
+            let encoded: Vec<u8> = codec.encrypt(data.bytes());
+            assert_eq!(String::from_utf8(encoded.clone()).unwrap(), *expected);
+            let decoded: Vec<u8> = codec.try_decrypt(encoded).unwrap();
+            assert_eq!(decoded, data.as_bytes());
+        }
+    }
+
+    #[test]
+    fn base16() {
+        round_trip(Base16::new(), &["", "66", "666f", "666f6f", "666f6f62", "666f6f6261",
+            "666f6f626172"]);
+        assert_eq!(Base16::uppercase().encode_to_string(&[0xab, 0x01]), "AB01");
+        let decoded: Vec<u8> = Base16::new().try_decrypt(b"aB01".to_vec()).unwrap();
+        assert_eq!(decoded, [0xab, 0x01]);
+        assert_invalid_encoding(&Base16::new(), &[
+            ("abc", 2, TruncatedGroup),
+            ("0g", 1, InvalidSymbol),
+            ("00 1", 2, InvalidSymbol),
+        ]);
+    }
+
+    #[test]
+    fn base32() {
+        round_trip(Base32::default(), &["", "MY======", "MZXQ====", "MZXW6===", "MZXW6YQ=",
+            "MZXW6YTB", "MZXW6YTBOI======"]);
+        round_trip(Base32::default().with_padding(false), &["", "MY", "MZXQ", "MZXW6",
+            "MZXW6YQ", "MZXW6YTB", "MZXW6YTBOI"]);
+        round_trip(Base32::new(Base32Alphabet::Crockford), &["", "CR", "CSQG", "CSQPY",
+            "CSQPYRG", "CSQPYRK1", "CSQPYRK1E8"]);
+        let decoded: Vec<u8> = Base32::new(Base32Alphabet::Crockford)
+            .try_decrypt(b"csqp-yrki-e8".to_vec())
+            .unwrap();
+        assert_eq!(decoded, b"foobar");
+        assert_invalid_encoding(&Base32::default(), &[
+            ("MY=====", 7, BadPadding),
+            ("MZXW6YQ", 7, BadPadding),
+            ("MZ======", 1, TrailingBits),
+            ("MY======MY", 2, BadPadding),
+            ("mzxw6===", 0, InvalidSymbol),
+            ("M1======", 1, InvalidSymbol),
+            ("========", 0, BadPadding),
+            ("MZ=W6===", 2, BadPadding),
+            ("MYA=====", 2, TruncatedGroup),
+        ]);
+        assert_invalid_encoding(&Base32::new(Base32Alphabet::Crockford), &[
+            ("CU", 1, InvalidSymbol),
+            ("CSQPY=", 5, BadPadding),
+            ("C", 0, TruncatedGroup),
+            ("CS", 1, TrailingBits),
+            // Positions count the ignored hyphens:
+            ("C-S-Q-P-Y-R-G-U", 14, InvalidSymbol),
+        ]);
+    }
+
+    #[test]
+    fn base64() {
+        round_trip(Base64::default(), &["", "Zg==", "Zm8=", "Zm9v", "Zm9vYg==", "Zm9vYmE=",
+            "Zm9vYmFy"]);
+        round_trip(Base64::default().with_padding(false), &["", "Zg", "Zm8", "Zm9v", "Zm9vYg",
+            "Zm9vYmE", "Zm9vYmFy"]);
+        let url_safe = Base64::new(Base64Alphabet::UrlSafe);
+        assert_eq!(url_safe.encode_to_string(&[0xfb, 0xff, 0xbf]), "-_-_");
+        assert_invalid_encoding(&Base64::default(), &[
+            ("Zg=", 3, BadPadding),
+            ("Z===", 0, TruncatedGroup),
+            ("Zg==Zm8=", 2, BadPadding),
+            ("Zg==Zm", 2, BadPadding),
+            ("Zm9-", 3, InvalidSymbol),
+            ("Zh==", 1, TrailingBits),
+            ("Zm8 ", 3, InvalidSymbol),
+            ("====", 0, BadPadding),
+        ]);
+        assert_invalid_encoding(&Base64::default().with_padding(false), &[
+            ("Zg==", 2, BadPadding),
+            ("Z", 0, TruncatedGroup),
+            ("Zm9vY", 4, TruncatedGroup),
+            ("Zm=v", 2, BadPadding),
+        ]);
+        assert_invalid_encoding(&url_safe, &[("+/8=", 0, InvalidSymbol)]);
+    }
+
+    #[test]
+    fn error_message() {
+        let result: Result<Vec<u8>, Error> = Base64::default().try_decrypt(b"Zm9v\xffm9v".to_vec());
+        assert_eq!(result.unwrap_err().to_string(), "Invalid Base64: invalid symbol at position 4");
+        let result: Result<Vec<u8>, Error> = Base32::default().try_decrypt(b"MY=====A".to_vec());
+        assert_eq!(result.unwrap_err().to_string(), "Invalid Base32: bad padding at position 2");
+    }
+}
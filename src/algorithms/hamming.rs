@@ -2,11 +2,28 @@ use std::io;
 use std::iter::ExactSizeIterator;
 use std::iter::FromIterator;
 use std::iter::IntoIterator;
-use std::iter::repeat;
+use std::iter::repeat_n;
 
 use bit_vec::BitVec;
 
+use crate::algorithms::BlockInterleaver;
+use crate::algorithms::Interleaver;
+use crate::decrypt::TryDecrypt;
 use crate::encrypt::TryEncrypt;
+use crate::error::Error;
+use crate::stream::Decryptor;
+use crate::stream::Encryptor;
+use crate::stream::StreamDecrypt;
+use crate::stream::StreamEncrypt;
+
+/// Maximal number of bytes of data in a single frame when encoding a stream. See
+/// [`StreamEncrypt`] implementation of [`HammingECC`] for more information.
+const STREAM_FRAME_LEN: usize = 0xFFFF;
+
+/// Maximal base-2 logarithm of the block size. Blocks of 2<sup>24</sup> bits
+/// are more than enough, and on targets with small `usize` the block must stay
+/// far below the size of the address space.
+const MAX_BLK_LOG_SIZE: u8 = if usize::BITS / 2 < 24 { (usize::BITS / 2) as u8 } else { 24 };
 
 /// A struct that implements Hamming's error correction code (ECC), which puts data
 /// into blocks. This code can detect up to 2 errors and correct 1 error in a single
@@ -28,13 +45,27 @@ use crate::encrypt::TryEncrypt;
 /// [`TryDecrypt`] trait. Decomposing data can also fail, because there may be two
 /// errors in a single block. Then Hamming's ECC knows that there is an error, but it
 /// cannot determine where those errors are.
+///
+/// # Interleaving
+///
+/// Bits of all blocks are interleaved by an [`Interleaver`], so that a burst of
+/// errors in consecutive bits is spread over several blocks. Instances created
+/// by [`new()`] use a [`BlockInterleaver`] with rows as long as the blocks, so
+/// consecutive bits belong to consecutive blocks. Another interleaver can be
+/// given to [`with_interleaver()`], for example [`NoInterleaver`] to disable
+/// interleaving.
 /// 
-/// [`TryEncrypt`]: crate::crypt::TryEncrypt
-/// [`TryDecrypt`]: crate::crypt::TryEncrypt
-/// [`size_field_bits()`]: Hamming::size_field_bits
-pub struct HammingECC {
+/// [`TryEncrypt`]: crate::TryEncrypt
+/// [`TryDecrypt`]: crate::TryDecrypt
+/// [`size_field_bits()`]: HammingECC::size_field_bits
+/// [`new()`]: HammingECC::new
+/// [`with_interleaver()`]: HammingECC::with_interleaver
+/// [`NoInterleaver`]: crate::algorithms::NoInterleaver
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HammingECC<I = BlockInterleaver> {
     blk_log_size: u8,
     size_field_bits: u8,
+    interleaver: I,
 }
 
 impl HammingECC {
@@ -48,30 +79,127 @@ impl HammingECC {
     ///  -  `size_field_bits`: how many bits should be reserved for information about data length
     ///     in bytes
     ///
-    /// # Return value
+    /// # Returns
     ///
-    ///  -  [`Option::Some`] if both parateters have valid value, e.g. when
-    ///     `blk_log_size >= 3 && size_field_bits >= 2`,
-    ///  -  [`Option::None`] otherwise
+    ///  -  [`Ok`] if both parateters have valid value, e.g. when
+    ///     `3 <= blk_log_size <= 24` and `2 <= size_field_bits <= 64`,
+    ///  -  [`Err`] with [`Error::InvalidParameters`] naming the invalid parameter
+    ///     otherwise
     pub fn new(
         blk_log_size: u8,
         size_field_bits: u8
-    ) -> Option<Self> {
-        // If all parateters are in specified range, we can create a new instance:
-        match blk_log_size >= 3 && size_field_bits >= 2 {
-            true  => Option::Some(Self { blk_log_size, size_field_bits }),
-            false => Option::None
-        }
+    ) -> Result<Self, Error> {
+        check_parameters(blk_log_size, size_field_bits)?;
+        // Rows of the interleaver are the blocks:
+        let interleaver = BlockInterleaver::new(1 << blk_log_size)
+            .expect("block is not empty");
+        Ok(Self { blk_log_size, size_field_bits, interleaver })
     }
 }
 
-impl TryEncrypt for HammingECC {
+impl<I: Interleaver> HammingECC<I> {
 
-    /// Error type to be returned when data size exceeds value
-    /// `u16::MAX * 8`.
-    type ErrorType = io::Error;
+    /// Creates a new [`HammingECC`](HammingECC) instance, which interleaves
+    /// bits of the blocks by given interleaver.
+    ///
+    /// # Parameters
+    ///
+    ///  -  `blk_log_size`, `size_field_bits`: the same as for
+    ///     [`new()`](HammingECC::new)
+    ///  -  `interleaver`: interleaver of bits of all blocks
+    ///
+    /// # Returns
+    ///
+    /// The same as for [`new()`](HammingECC::new).
+    pub fn with_interleaver(
+        blk_log_size: u8,
+        size_field_bits: u8,
+        interleaver: I
+    ) -> Result<Self, Error> {
+        check_parameters(blk_log_size, size_field_bits)?;
+        Ok(Self { blk_log_size, size_field_bits, interleaver })
+    }
 
-    /// 
+    /// Returns size of a block expressed by its base-2 logarithm, e.g. number 3
+    /// for 8-bit blocks, number 4 for 16-bit blocks and so on.
+    pub fn blk_log_size(&self) -> u8 {
+        self.blk_log_size
+    }
+
+    /// Returns how many bits at the beginning of encoded data are reserved for
+    /// information about data length in bytes. Therefore, at most
+    /// 2<sup>*n*</sup> &ndash; 1 bytes can be encoded, where *n* is the returned
+    /// value.
+    pub fn size_field_bits(&self) -> u8 {
+        self.size_field_bits
+    }
+
+    /// Returns the interleaver of bits of all blocks.
+    pub fn interleaver(&self) -> &I {
+        &self.interleaver
+    }
+
+    /// Returns maximal number of bytes which can be encoded at once, e.g.
+    /// 2<sup>*n*</sup> &ndash; 1, where *n* is [`size_field_bits()`].
+    ///
+    /// [`size_field_bits()`]: HammingECC::size_field_bits
+    pub fn max_data_len(&self) -> usize {
+        1usize.checked_shl(self.size_field_bits as u32)
+            .map_or(usize::MAX, |limit| limit - 1)
+    }
+
+    /// Returns number of bytes of data in each frame of a stream but the last
+    /// one.
+    fn stream_frame_len(&self) -> usize {
+        self.max_data_len().min(STREAM_FRAME_LEN)
+    }
+
+    /// Returns number of bytes which given number of data bytes is encoded into.
+    fn encoded_len(&self, data_byte_len: usize) -> usize {
+        let blk_bits_total = 1usize << self.blk_log_size;
+        let blk_bits_data = blk_bits_total - 1 - self.blk_log_size as usize;
+        let blk_count = (data_byte_len * 8 + self.size_field_bits as usize)
+            .div_ceil(blk_bits_data);
+        (blk_count * blk_bits_total).div_ceil(8)
+    }
+}
+
+/// Checks that parameters of [`HammingECC`] are in specified range.
+fn check_parameters(blk_log_size: u8, size_field_bits: u8) -> Result<(), Error> {
+    if !(3..=MAX_BLK_LOG_SIZE).contains(&blk_log_size) {
+        return Err(Error::invalid_parameter(
+            "blk_log_size",
+            format!(
+                "expected value from 3 to {} but {} was given",
+                MAX_BLK_LOG_SIZE,
+                blk_log_size
+            )
+        ))
+    }
+    if !(2..=64).contains(&size_field_bits) {
+        return Err(Error::invalid_parameter(
+            "size_field_bits",
+            format!("expected value from 2 to 64 but {} was given", size_field_bits)
+        ))
+    }
+    Ok(())
+}
+
+impl<I: Interleaver> TryEncrypt for HammingECC<I> {
+
+    /// Error type to be returned when data size exceeds
+    /// [`max_data_len()`](HammingECC::max_data_len).
+    type ErrorType = Error;
+
+    /// Composes given data into Hamming code blocks. The first
+    /// *n*=`size_field_bits` bits carry length of the data in bytes, then the
+    /// data follows. Bits of the blocks are interleaved by the
+    /// [`interleaver()`](HammingECC::interleaver).
+    ///
+    /// # Returns
+    ///
+    ///  -  [`Ok`] with encoded data
+    ///  -  [`Err`] if the data is longer than 2<sup>*n*</sup> &ndash; 1 bytes
     fn try_encrypt<D, E>(
         &self,
         data_to_encrypt: D
@@ -84,18 +212,13 @@ impl TryEncrypt for HammingECC {
         let data_byte_iter = data_to_encrypt.into_iter();
         // Size of data in bytes:
         let data_byte_len = data_byte_iter.len();
-        // If size of message in bytes exceeds maximum value of unsigned 16-bit
-        // integer, we return an error:
-        if data_byte_len >= (1 << self.size_field_bits) {
-            // Return error
-            return Err(io::Error::new(
-                io::ErrorKind::InvalidData,
-                format!(
-                    "Expected at most {} bytes to encrypt but {} bytes were given",
-                    (1 << self.size_field_bits) - 1,
-                    data_byte_len
-                )
-            ))
+        // If size of message in bytes cannot be stored in the size field, we
+        // return an error:
+        if data_byte_len > self.max_data_len() {
+            return Err(Error::InputTooLong {
+                max: self.max_data_len(),
+                actual: data_byte_len
+            })
         }
         // Size of data in bits:
         let data_bit_len = data_byte_len * 8;
@@ -107,14 +230,14 @@ impl TryEncrypt for HammingECC {
         let blk_bits_data = blk_bits_total - blk_bits_ecc;
         // Number of blocks needed calculated without need of floats and rounding up:
         // let blk_count = ceil((data_size_bits as f64) / (message_bits_per_block as f64))
-        let blk_count = (data_bit_len + self.size_field_bits as usize + blk_bits_data - 1) / blk_bits_data;
+        let blk_count = (data_bit_len + self.size_field_bits as usize).div_ceil(blk_bits_data);
         // Vector of blocks
-        let mut blocks = repeat(BitVec::with_capacity(blk_bits_total))
-            .take(blk_count)
+        let mut blocks = repeat_n(BitVec::with_capacity(blk_bits_total), blk_count)
             .collect::<Vec<BitVec>>();
-        // Iterator over bits of size field:
-        let sz_field_bit_iter = (0..self.size_field_bits).into_iter().rev()
-            .map(|i| (data_byte_len & (1 << i)) != 0);
+        // Iterator over bits of size field. The field can be wider than `usize`
+        // on 32-bit targets, so the bits are taken from `u64`:
+        let sz_field_bit_iter = (0..self.size_field_bits).rev()
+            .map(|i| (data_byte_len as u64 & (1 << i)) != 0);
         // Iterator over bits from the data. Map each byte into 8 boolean values:
         let data_bit_iter = data_byte_iter.flat_map(|byte| {
             // Create an vector of booleans representing bits. Compute each bit. The
@@ -155,36 +278,409 @@ impl TryEncrypt for HammingECC {
             // the end:
             for i in 0..self.blk_log_size {
                 let mask = 1 << i;
-                let ecc_bit = (0..blk_bits_total).into_iter()
+                let ecc_bit = (0..blk_bits_total)
                     .filter(|b| (b & mask) == mask)
                     .map(|b| block.get(b).unwrap())
                     .reduce(|parity, bit| parity ^ bit)
                     .unwrap();
                 block.set(mask, ecc_bit);
             }
-            // 
+            // Bit at position 0 is the parity of the whole block:
             block.set(0, block.iter().reduce(|parity, bit| parity ^ bit).unwrap())
         }
 
-        // Create a bit vector that can hold all our data:
-        let mut result = BitVec::with_capacity(16 + blk_bits_total * blk_count);
-        // Now we zip blocks so the blocks take turns after each bit, e.g. previous
-        // bit is from previous block and next bit is from next block. This prevents
-        // the data to be corrupted by the hacker just by changing a number of
-        // consecutive bits.
-        for bit_idx in 0..blk_bits_total {
-            for block in &blocks {
-                result.push(block.get(bit_idx).unwrap());
-            }
-        }
+        // Now we interleave bits of all blocks. By default, the blocks take turns
+        // after each bit, e.g. previous bit is from previous block and next bit is
+        // from next block. This prevents the data to be corrupted by the hacker
+        // just by changing a number of consecutive bits. Positions are read from
+        // the blocks one by one, so no other copy of the bits is made:
+        let result: BitVec = self.interleaver.order(blk_count * blk_bits_total)
+            .map(|position| blocks[position / blk_bits_total][position % blk_bits_total])
+            .collect();
         // Convert bitvector into iterator over u8 and `collect()` it:
         Ok(result.to_bytes().into_iter().collect())
     }
 }
 
+/// Outcome of decoding a single Hamming code block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockStatus {
+
+    /// The block contained no error.
+    Clean,
+
+    /// The block contained a single error, which was corrected.
+    Corrected {
+        /// Position of the flipped bit within the block. Position 0 is the parity
+        /// bit of the whole block.
+        bit: usize
+    },
+
+    /// The block contained two errors, which can be detected but not corrected.
+    Uncorrectable,
+}
+
+/// Diagnostic report of decoding data composed by [`HammingECC`]. It contains
+/// [`BlockStatus`] of each block in order of the blocks and totals over all
+/// blocks, which can be used for estimating the bit error rate of the channel.
+/// The report is returned by [`try_decrypt_with_report`] method.
+///
+/// [`try_decrypt_with_report`]: HammingECC::try_decrypt_with_report
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HammingReport {
+    blocks: Vec<BlockStatus>,
+    blk_bits_total: usize,
+}
+
+impl HammingReport {
+
+    /// Returns status of each block in the order of the blocks.
+    pub fn blocks(&self) -> &[BlockStatus] {
+        &self.blocks
+    }
+
+    /// Returns number of blocks which contained no error.
+    pub fn clean_count(&self) -> usize {
+        self.count(|status| status == BlockStatus::Clean)
+    }
+
+    /// Returns number of blocks which contained a single, corrected error.
+    pub fn corrected_count(&self) -> usize {
+        self.count(|status| matches!(status, BlockStatus::Corrected { .. }))
+    }
+
+    /// Returns number of blocks which contained two errors that could not be
+    /// corrected.
+    pub fn uncorrectable_count(&self) -> usize {
+        self.count(|status| status == BlockStatus::Uncorrectable)
+    }
+
+    /// Returns number of bits in all blocks, e.g. number of bits which could be
+    /// affected by an error.
+    pub fn total_bits(&self) -> usize {
+        self.blocks.len() * self.blk_bits_total
+    }
+
+    /// Returns the minimal number of wrong bits, e.g. one for each corrected block
+    /// and two for each uncorrectable block. Blocks with three or more errors can
+    /// be misdetected, so the actual number of wrong bits may be higher.
+    pub fn detected_bit_errors(&self) -> usize {
+        self.corrected_count() + 2 * self.uncorrectable_count()
+    }
+
+    /// Returns estimation of bit error rate, e.g. ratio of
+    /// [`detected_bit_errors()`] to [`total_bits()`]. If there were no blocks,
+    /// zero is returned.
+    ///
+    /// [`detected_bit_errors()`]: HammingReport::detected_bit_errors
+    /// [`total_bits()`]: HammingReport::total_bits
+    pub fn bit_error_rate(&self) -> f64 {
+        match self.total_bits() {
+            0     => 0.0,
+            total => self.detected_bit_errors() as f64 / total as f64
+        }
+    }
+
+    fn count<P>(&self, predicate: P) -> usize
+    where P: Fn(BlockStatus) -> bool {
+        self.blocks.iter().filter(|&&status| predicate(status)).count()
+    }
+}
+
+impl<I: Interleaver> HammingECC<I> {
+
+    /// Decomposes Hamming code blocks back into the original data in the same way
+    /// as [`try_decrypt`] does, but also returns [`HammingReport`] with status of
+    /// each block. All blocks are examined even if some of them are
+    /// uncorrectable, so the report is complete. If the data does not consist of
+    /// whole blocks, the report contains no blocks.
+    ///
+    /// # Returns
+    ///
+    /// A tuple of:
+    ///
+    ///  -  result of decoding, the same as [`try_decrypt`] would return
+    ///  -  report of all blocks
+    ///
+    /// [`try_decrypt`]: HammingECC::try_decrypt
+    pub fn try_decrypt_with_report<E, D>(
+        &self,
+        encrypted_data: E
+    ) -> (Result<D, Error>, HammingReport) where
+        E:           IntoIterator<Item = u8>,
+        E::IntoIter: ExactSizeIterator,
+        D:           FromIterator<u8> {
+
+        // Convert encrypted data into a bit vector:
+        let bytes: Vec<u8> = encrypted_data.into_iter().collect();
+        let bits = BitVec::from_bytes(&bytes);
+        // Total number of bits in the block:
+        let blk_bits_total = 1usize << self.blk_log_size;
+        // Number of error-correction bits in the block:
+        let blk_bits_ecc = 1usize + self.blk_log_size as usize;
+        // Number of bits in the block that carry data:
+        let blk_bits_data = blk_bits_total - blk_bits_ecc;
+        // Report with no blocks for now:
+        let mut report = HammingReport { blocks: Vec::new(), blk_bits_total };
+        // Number of whole blocks. The rest are zero bits which were added when
+        // converting bits into bytes, so there is always less than 8 of them:
+        let blk_count = bits.len() / blk_bits_total;
+        if blk_count == 0 || bits.len() - blk_count * blk_bits_total >= 8 {
+            let error = Error::malformed(format!(
+                "expected whole number of {}-bit blocks but {} bits were given",
+                blk_bits_total,
+                bits.len()
+            ));
+            return (Err(error), report)
+        }
+        // De-interleave bits of whole blocks, e.g. do the reverse of what
+        // `try_encrypt` does. The trailing zero bits are left out by `zip()`:
+        let mut blocks = vec![BitVec::from_elem(blk_bits_total, false); blk_count];
+        for (bit, position) in bits.iter().zip(self.interleaver.order(blk_count * blk_bits_total)) {
+            blocks[position / blk_bits_total].set(position % blk_bits_total, bit);
+        }
+        // Now we correct errors in each block:
+        for block in blocks.iter_mut() {
+            // Syndrome is XOR of indices of all set bits. For a correct block it is
+            // zero, for a block with a single error it is index of the wrong bit:
+            let syndrome = block.iter().enumerate()
+                .filter(|&(_, bit)| bit)
+                .fold(0usize, |syndrome, (bit_idx, _)| syndrome ^ bit_idx);
+            // Parity of the whole block. For a correct block it is even:
+            let parity = block.iter().fold(false, |parity, bit| parity ^ bit);
+            let status = match (syndrome, parity) {
+                // No error:
+                (0, false) => BlockStatus::Clean,
+                // Odd number of errors, we assume there is only one. Flip the bit
+                // given by the syndrome. If syndrome is zero, the wrong bit is the
+                // parity bit at position 0:
+                (wrong_bit, true) => {
+                    block.set(wrong_bit, !block[wrong_bit]);
+                    BlockStatus::Corrected { bit: wrong_bit }
+                },
+                // Even number of errors, we know there are (at least) two, but we
+                // do not know where:
+                (_, false) => BlockStatus::Uncorrectable
+            };
+            report.blocks.push(status);
+        }
+        // If any block is uncorrectable, we cannot decode the data:
+        if let Some(blk_idx) = report.blocks.iter()
+            .position(|&status| status == BlockStatus::Uncorrectable) {
+            let error = Error::Uncorrectable { block: blk_idx };
+            return (Err(error), report)
+        }
+        // Iterator over bits carrying data, e.g. bits that are not at position 0
+        // or at a position which is a power of two:
+        let mut decode_bit_iter = blocks.iter()
+            .flat_map(|block| block.iter().enumerate())
+            .filter(|(bit_idx, _)| bit_idx.count_ones() > 1)
+            .map(|(_, bit)| bit);
+        // Read size field. The most significant bit is the first one:
+        let data_byte_len = decode_bit_iter.by_ref()
+            .take(self.size_field_bits as usize)
+            .fold(0u64, |len, bit| (len << 1) | bit as u64);
+        // The size field must correspond to the number of blocks we have got,
+        // otherwise the data were not encoded by us or the size field is corrupted.
+        // It is not trusted, so the computation must not overflow:
+        let expected_blk_count = usize::try_from(data_byte_len).ok()
+            .filter(|&len| len <= self.max_data_len())
+            .and_then(|len| len.checked_mul(8))
+            .and_then(|bits| bits.checked_add(self.size_field_bits as usize))
+            .map(|bits| bits.div_ceil(blk_bits_data));
+        if expected_blk_count != Some(blk_count) {
+            let error = Error::malformed(format!(
+                "size field indicates {} bytes, which does not fit into {} blocks",
+                data_byte_len,
+                blk_count
+            ));
+            return (Err(error), report)
+        }
+        // Compose bytes from bits, the most significant bit is the first one. The
+        // padding of the last block is trimmed:
+        let data_byte_len = data_byte_len as usize;
+        let mut data_bytes = Vec::with_capacity(data_byte_len);
+        for _ in 0..data_byte_len {
+            data_bytes.push(decode_bit_iter.by_ref()
+                .take(8)
+                .fold(0u8, |byte, bit| (byte << 1) | bit as u8));
+        }
+        (Ok(data_bytes.into_iter().collect()), report)
+    }
+}
+
+impl<I: Interleaver> TryDecrypt for HammingECC<I> {
+
+    /// Error type to be returned when data cannot be decoded, e.g. when a block
+    /// contains two errors or the data is not made of whole blocks.
+    type ErrorType = Error;
+
+    /// Decomposes Hamming code blocks back into the original data. Bits of the
+    /// blocks are de-interleaved first, then a single error in each block is
+    /// corrected. Finally, the size field is read and the padding of the last
+    /// block is trimmed. Use [`try_decrypt_with_report`] to get to know which
+    /// blocks were corrected.
+    ///
+    /// # Returns
+    ///
+    ///  -  [`Ok`] with decoded data
+    ///  -  [`Err`] if any block contains two errors, or if the data does not
+    ///     have the shape produced by [`try_encrypt`]
+    ///
+    /// [`try_encrypt`]: HammingECC::try_encrypt
+    /// [`try_decrypt_with_report`]: HammingECC::try_decrypt_with_report
+    fn try_decrypt<E, D>(
+        &self,
+        encrypted_data: E
+    ) -> Result<D, Self::ErrorType> where
+        E:           IntoIterator<Item = u8>,
+        E::IntoIter: ExactSizeIterator,
+        D:           FromIterator<u8> {
+
+        // Decode and throw the report away:
+        self.try_decrypt_with_report(encrypted_data).0
+    }
+}
+
+/// Streamed data are split into frames, each of them encoded separately in the
+/// same way as [`try_encrypt`] does. Each frame but the last one contains the
+/// same number of data bytes, which is the lesser of [`max_data_len()`] and
+/// 65&#8239;535. The last frame contains fewer bytes, possibly none, and marks
+/// the end of the stream, so a stream cut at a frame boundary is detected.
+/// Therefore, data shorter than a frame are encoded exactly as by
+/// [`try_encrypt`] and data of any length can be encoded.
+///
+/// [`try_encrypt`]: HammingECC::try_encrypt
+/// [`max_data_len()`]: HammingECC::max_data_len
+impl<I: Interleaver> StreamEncrypt for HammingECC<I> {
+    type Encryptor<'a> = HammingEncoder<'a, I> where Self: 'a;
+
+    fn encryptor(&self) -> HammingEncoder<'_, I> {
+        HammingEncoder { hamming: self, frame: Vec::new(), frame_count: 0 }
+    }
+}
+
+/// Streamed data are decoded frame by frame, see [`StreamEncrypt`]
+/// implementation of [`HammingECC`] for more information.
+impl<I: Interleaver> StreamDecrypt for HammingECC<I> {
+    type Decryptor<'a> = HammingDecoder<'a, I> where Self: 'a;
+
+    fn decryptor(&self) -> HammingDecoder<'_, I> {
+        HammingDecoder { hamming: self, frame: Vec::new(), frame_count: 0 }
+    }
+}
+
+/// State of [`HammingECC`] encoding fed by chunks of data. It collects data
+/// until a frame is full, then the whole frame is encoded.
+pub struct HammingEncoder<'a, I = BlockInterleaver> {
+    hamming: &'a HammingECC<I>,
+    frame: Vec<u8>,
+    frame_count: usize,
+}
+
+impl<I: Interleaver> HammingEncoder<'_, I> {
+
+    /// Encodes the collected frame and starts a new one.
+    fn flush_frame(&mut self, output: &mut Vec<u8>) -> io::Result<()> {
+        let encoded: Vec<u8> = self.hamming.try_encrypt(self.frame.drain(..))?;
+        output.extend_from_slice(&encoded);
+        self.frame_count += 1;
+        Ok(())
+    }
+}
+
+impl<I: Interleaver> Encryptor for HammingEncoder<'_, I> {
+    fn update(&mut self, mut input: &[u8], output: &mut Vec<u8>) -> io::Result<()> {
+        let frame_len = self.hamming.stream_frame_len();
+        while !input.is_empty() {
+            // Fill the frame as much as possible:
+            let take_len = input.len().min(frame_len - self.frame.len());
+            self.frame.extend_from_slice(&input[..take_len]);
+            input = &input[take_len..];
+            // Encode the frame if it is full:
+            if self.frame.len() == frame_len {
+                self.flush_frame(output)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(mut self, output: &mut Vec<u8>) -> io::Result<()> {
+        // Encode the last frame, which is never full. If there are no data left,
+        // the frame is empty:
+        self.flush_frame(output)
+    }
+}
+
+/// State of [`HammingECC`] decoding fed by chunks of encoded data. It collects
+/// encoded data until a whole frame is available, then the frame is decoded.
+pub struct HammingDecoder<'a, I = BlockInterleaver> {
+    hamming: &'a HammingECC<I>,
+    frame: Vec<u8>,
+    frame_count: usize,
+}
+
+impl<I: Interleaver> HammingDecoder<'_, I> {
+
+    /// Decodes first `encoded_len` bytes of the collected data as a frame.
+    fn decode_frame(&mut self, encoded_len: usize) -> io::Result<Vec<u8>> {
+        let decoded: Vec<u8> = self.hamming.try_decrypt(self.frame.drain(..encoded_len))?;
+        self.frame_count += 1;
+        Ok(decoded)
+    }
+}
+
+impl<I: Interleaver> Decryptor for HammingDecoder<'_, I> {
+    fn update(&mut self, input: &[u8], output: &mut Vec<u8>) -> io::Result<()> {
+        let frame_len = self.hamming.stream_frame_len();
+        let encoded_frame_len = self.hamming.encoded_len(frame_len);
+        self.frame.extend_from_slice(input);
+        // Decode all full frames we have got. The last frame can be encoded into
+        // as many bytes as a full frame, so a frame is decoded only when more
+        // data follow it:
+        while self.frame.len() > encoded_frame_len {
+            let decoded = self.decode_frame(encoded_frame_len)?;
+            if decoded.len() != frame_len {
+                return Err(Error::malformed(format!(
+                    "frame {} contains {} bytes, but {} bytes were expected",
+                    self.frame_count - 1,
+                    decoded.len(),
+                    frame_len
+                )).into())
+            }
+            output.extend_from_slice(&decoded);
+        }
+        Ok(())
+    }
+
+    fn finish(mut self, output: &mut Vec<u8>) -> io::Result<()> {
+        // The stream must end with a frame shorter than the others. If the last
+        // frame is full, the stream was cut at a frame boundary:
+        let decoded = match self.frame.len() {
+            0 => None,
+            len => Some(self.decode_frame(len)?)
+                .filter(|decoded| decoded.len() < self.hamming.stream_frame_len()),
+        };
+        match decoded {
+            Some(decoded) => {
+                output.extend_from_slice(&decoded);
+                Ok(())
+            },
+            None => Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "Expected the last frame, which is shorter than the others"
+            )),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::algorithms::ConvolutionalInterleaver;
+    use crate::algorithms::NoInterleaver;
+    use crate::algorithms::PseudoRandomInterleaver;
+    use crate::Key;
 
     #[test]
     fn into_ok() {
@@ -218,4 +714,180 @@ mod tests {
             assert_eq!(actual_output, expected_output)
         }
     }
+
+    #[test]
+    fn from_ok() {
+        let test_cases = vec![
+            (HammingECC::new(4, 3), vec![0b10010110, 0b00110110]),
+            (HammingECC::new(4, 4), vec![0b01011010, 0b10000001]),
+            (HammingECC::new(3, 4), vec![0b01110010, 0b01101000]),
+            (HammingECC::new(4, 3), vec![]),
+            (HammingECC::new(3, 5), vec![]),
+            (HammingECC::new(5, 16), b"Hamming code corrects single errors".to_vec()),
+        ];
+
+        for (instance, input) in test_cases {
+            let hamming = instance.unwrap();
+            let encoded: Vec<u8> = hamming.try_encrypt(input.clone()).unwrap();
+            let decoded: Vec<u8> = hamming.try_decrypt(encoded).unwrap();
+            assert_eq!(decoded, input)
+        }
+    }
+
+    #[test]
+    fn from_single_error() {
+        let hamming = HammingECC::new(4, 8).unwrap();
+        let input = b"single error".to_vec();
+        let encoded: Vec<u8> = hamming.try_encrypt(input.clone()).unwrap();
+
+        // Flipping any single bit must be corrected:
+        for bit_idx in 0..encoded.len() * 8 {
+            let mut corrupted = encoded.clone();
+            corrupted[bit_idx / 8] ^= 0x80 >> (bit_idx % 8);
+            let decoded: Vec<u8> = hamming.try_decrypt(corrupted).unwrap();
+            assert_eq!(decoded, input)
+        }
+    }
+
+    #[test]
+    fn from_double_error() {
+        let hamming = HammingECC::new(4, 8).unwrap();
+        let encoded: Vec<u8> = hamming.try_encrypt(b"double error".to_vec()).unwrap();
+        // Number of blocks; consecutive bits belong to consecutive blocks, so bits
+        // `0` and `blk_count` both belong to the first block:
+        let blk_count = encoded.len() * 8 / 16;
+
+        let mut corrupted = encoded.clone();
+        corrupted[0] ^= 0x80;
+        corrupted[blk_count / 8] ^= 0x80 >> (blk_count % 8);
+        let result: Result<Vec<u8>, _> = hamming.try_decrypt(corrupted);
+        assert!(matches!(result.unwrap_err(), Error::Uncorrectable { block: 0 }))
+    }
+
+    #[test]
+    fn from_with_report() {
+        let hamming = HammingECC::new(4, 8).unwrap();
+        let input = b"report".to_vec();
+        let mut encoded: Vec<u8> = hamming.try_encrypt(input.clone()).unwrap();
+        let blk_count = encoded.len() * 8 / 16;
+
+        // Flip bit 5 of the second block and bit 0 of the third block:
+        let flips = [5 * blk_count + 1, 2];
+        for bit_idx in flips {
+            encoded[bit_idx / 8] ^= 0x80 >> (bit_idx % 8);
+        }
+        let (result, report) = hamming.try_decrypt_with_report(encoded.clone());
+        let result: Result<Vec<u8>, _> = result;
+        assert_eq!(result.unwrap(), input);
+        assert_eq!(report.blocks().len(), blk_count);
+        assert_eq!(report.blocks()[0], BlockStatus::Clean);
+        assert_eq!(report.blocks()[1], BlockStatus::Corrected { bit: 5 });
+        assert_eq!(report.blocks()[2], BlockStatus::Corrected { bit: 0 });
+        assert_eq!(report.corrected_count(), 2);
+        assert_eq!(report.clean_count(), blk_count - 2);
+        assert_eq!(report.uncorrectable_count(), 0);
+        assert_eq!(report.total_bits(), blk_count * 16);
+
+        // Add another error into the second block:
+        let bit_idx = 6 * blk_count + 1;
+        encoded[bit_idx / 8] ^= 0x80 >> (bit_idx % 8);
+        let (result, report) = hamming.try_decrypt_with_report(encoded);
+        let result: Result<Vec<u8>, _> = result;
+        assert!(result.is_err());
+        assert_eq!(report.blocks()[1], BlockStatus::Uncorrectable);
+        assert_eq!(report.blocks()[2], BlockStatus::Corrected { bit: 0 });
+        assert_eq!(report.detected_bit_errors(), 3);
+    }
+
+    #[test]
+    fn interleavers() {
+        let input = b"interleaved".to_vec();
+        let default = HammingECC::new(4, 8).unwrap();
+        let block = HammingECC::with_interleaver(4, 8, BlockInterleaver::new(16).unwrap())
+            .unwrap();
+        assert_eq!(block, default);
+        let encoded: Vec<u8> = default.try_encrypt(input.clone()).unwrap();
+        let blk_count = encoded.len() * 8 / 16;
+
+        // A burst as long as the number of blocks hits each block once:
+        let mut corrupted = encoded.clone();
+        for bit_idx in 3..3 + blk_count {
+            corrupted[bit_idx / 8] ^= 0x80 >> (bit_idx % 8);
+        }
+        let decoded: Vec<u8> = default.try_decrypt(corrupted.clone()).unwrap();
+        assert_eq!(decoded, input);
+
+        // Without interleaving, the same burst hits a single block:
+        let plain = HammingECC::with_interleaver(4, 8, NoInterleaver).unwrap();
+        let plain_encoded: Vec<u8> = plain.try_encrypt(input.clone()).unwrap();
+        let mut corrupted = plain_encoded.clone();
+        corrupted[0] ^= 0b00110000;
+        let result: Result<Vec<u8>, _> = plain.try_decrypt(corrupted);
+        assert!(matches!(result.unwrap_err(), Error::Uncorrectable { block: 0 }));
+
+        // Other interleavers only change the order of the same bits:
+        let key = Key::new(b"channel".to_vec());
+        let ones = |bytes: &[u8]| bytes.iter().map(|byte| byte.count_ones()).sum::<u32>();
+        let random = HammingECC::with_interleaver(4, 8, PseudoRandomInterleaver::new(&key))
+            .unwrap();
+        let encoded: Vec<u8> = random.try_encrypt(input.clone()).unwrap();
+        assert_eq!(ones(&encoded), ones(&plain_encoded));
+        let decoded: Vec<u8> = random.try_decrypt(encoded).unwrap();
+        assert_eq!(decoded, input);
+        let convolutional = ConvolutionalInterleaver::new(8, 2).unwrap();
+        let convolutional = HammingECC::with_interleaver(4, 8, convolutional).unwrap();
+        let encoded: Vec<u8> = convolutional.try_encrypt(input.clone()).unwrap();
+        assert_eq!(ones(&encoded), ones(&plain_encoded));
+        let decoded: Vec<u8> = convolutional.try_decrypt(encoded).unwrap();
+        assert_eq!(decoded, input);
+    }
+
+    #[test]
+    fn from_huge_size_field() {
+        // A single 128-bit block whose size field is all ones. The data bits of
+        // the block are set and the error correction bits are computed:
+        let hamming = HammingECC::new(7, 64).unwrap();
+        let mut block = BitVec::from_elem(128, false);
+        for bit_idx in (0..128usize).filter(|idx| idx.count_ones() > 1) {
+            block.set(bit_idx, true);
+        }
+        for i in 0..7 {
+            let mask = 1 << i;
+            let ecc_bit = (0..128).filter(|b| b & mask == mask && block[*b])
+                .count() % 2 == 1;
+            block.set(mask, ecc_bit);
+        }
+        block.set(0, block.iter().filter(|&bit| bit).count() % 2 == 1);
+        let result: Result<Vec<u8>, _> = hamming.try_decrypt(block.to_bytes());
+        assert!(matches!(result.unwrap_err(), Error::Malformed { .. }))
+    }
+
+    #[test]
+    fn new_err() {
+        let test_cases = vec![(2, 8, "blk_log_size"), (25, 8, "blk_log_size"),
+            (4, 1, "size_field_bits"), (4, 65, "size_field_bits")];
+
+        for (blk_log_size, size_field_bits, expected) in test_cases {
+            match HammingECC::new(blk_log_size, size_field_bits) {
+                Err(Error::InvalidParameters { parameter, .. }) => assert_eq!(parameter, expected),
+                _ => panic!("Expected invalid `{}`", expected)
+            }
+        }
+    }
+
+    #[test]
+    fn into_too_long() {
+        let hamming = HammingECC::new(4, 3).unwrap();
+        let result: Result<Vec<u8>, _> = hamming.try_encrypt(vec![0; 8]);
+        assert!(matches!(result.unwrap_err(), Error::InputTooLong { max: 7, actual: 8 }))
+    }
+
+    #[test]
+    fn from_malformed() {
+        let hamming = HammingECC::new(4, 8).unwrap();
+        for input in [vec![], vec![0b00000000], vec![0; 5]] {
+            let result: Result<Vec<u8>, _> = hamming.try_decrypt(input);
+            assert!(result.is_err())
+        }
+    }
 }
\ No newline at end of file
@@ -0,0 +1,93 @@
+use std::io;
+use std::io::Read;
+
+use crate::Digest;
+
+/// Size of chunks in which data are passed to hash functions and checksums.
+const CHUNK_LEN: usize = 8192;
+
+/// Maximal length of the digest of hash functions with variable output length,
+/// such as [`Shake128`] or [`Blake3`], which is 1 MiB.
+///
+/// [`Shake128`]: crate::algorithms::Shake128
+/// [`Blake3`]: crate::algorithms::Blake3
+pub(crate) const MAX_OUTPUT_LEN: usize = 1 << 20;
+
+/// State of hashing fed by chunks of data, shared by all hash families of this
+/// crate, e.g. [`Sha2Stream`], [`Sha3Stream`], [`Blake2Stream`] or
+/// [`Blake3Stream`]. Each of them is created by a hash function implementing
+/// [`Encrypt`], which hashes the whole data at once, so hash families can be
+/// switched without changing the code that uses them.
+///
+/// [`Sha2Stream`]: crate::algorithms::Sha2Stream
+/// [`Sha3Stream`]: crate::algorithms::Sha3Stream
+/// [`Blake2Stream`]: crate::algorithms::Blake2Stream
+/// [`Blake3Stream`]: crate::algorithms::Blake3Stream
+/// [`Encrypt`]: crate::Encrypt
+pub trait HashFunction: Clone {
+
+    /// Returns length of the digest in bytes.
+    fn output_len(&self) -> usize;
+
+    /// Feeds the hasher with next chunk of data.
+    ///
+    /// # Parameters
+    ///
+    /// - `data`: data to hash
+    fn update(&mut self, data: &[u8]);
+
+    /// Finishes hashing and returns the digest of all data fed into the hasher.
+    fn finalize(self) -> Digest;
+
+    /// Feeds the hasher with everything that can be read from given reader, so
+    /// that the data do not need to be buffered in the memory, and finishes
+    /// hashing.
+    ///
+    /// # Parameters
+    ///
+    /// - `reader`: reader to read data to hash from
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with the digest of all data fed into the hasher
+    /// - [`Err`] if reading has failed
+    fn hash_reader<R: Read>(mut self, mut reader: R) -> io::Result<Digest> {
+        let mut chunk = vec![0; CHUNK_LEN];
+        loop {
+            match reader.read(&mut chunk) {
+                Ok(0)    => return Ok(self.finalize()),
+                Ok(len)  => self.update(&chunk[..len]),
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {},
+                Err(err) => return Err(err)
+            }
+        }
+    }
+}
+
+/// Passes given data to `update` by chunks, so that the data do not need to be
+/// collected.
+pub(crate) fn for_each_chunk<D, F>(data: D, mut update: F)
+where
+    D: IntoIterator<Item = u8>,
+    F: FnMut(&[u8]),
+{
+    let mut chunk = Vec::with_capacity(CHUNK_LEN);
+    for byte in data {
+        chunk.push(byte);
+        if chunk.len() == CHUNK_LEN {
+            update(&chunk);
+            chunk.clear();
+        }
+    }
+    update(&chunk);
+}
+
+/// Feeds given hasher with the data and returns the digest.
+pub(crate) fn hash_of<H, D>(mut hasher: H, data: D) -> Digest
+where
+    H: HashFunction,
+    D: IntoIterator<Item = u8>,
+{
+    for_each_chunk(data, |chunk| hasher.update(chunk));
+    hasher.finalize()
+}
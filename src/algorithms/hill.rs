@@ -0,0 +1,231 @@
+use std::iter::FromIterator;
+use std::iter::IntoIterator;
+
+use crate::algorithms::Alphabet;
+use crate::algorithms::alphabet::key_text;
+use crate::decrypt::Decrypt;
+use crate::encrypt::Encrypt;
+use crate::error::Error;
+use crate::key::Key;
+
+/// Number of letters of the Latin alphabet, the modulus of the cipher.
+const MODULUS: usize = 26;
+
+/// Index of X in the Latin alphabet, which pads the last block.
+const X: usize = 23;
+
+/// The Hill cipher, which multiplies blocks of letters, taken as column vectors
+/// of their indices in the Latin alphabet, by a square key matrix modulo 26.
+///
+/// Data are treated as UTF-8 text. Only letters are encrypted and they keep
+/// their case, other characters are passed through unchanged. If the number of
+/// letters is not a multiple of the size of the matrix, the text is padded by
+/// X at the end, which stays there after decryption.
+///
+/// ```
+/// use mdcrypt::algorithms::Hill;
+/// use mdcrypt::Encrypt;
+///
+/// let cipher = Hill::new([[3, 3], [2, 5]]).unwrap();
+/// let encrypted: Vec<u8> = cipher.encrypt(b"help".to_vec());
+/// assert_eq!(encrypted, b"hiat");
+/// ```
+#[derive(Clone, Debug)]
+pub struct Hill {
+    size: usize,
+    matrix: Vec<usize>,
+    inverse: Vec<usize>,
+}
+
+impl Hill {
+
+    /// Creates the cipher from a key matrix given by rows. Its entries are
+    /// reduced modulo 26.
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with the cipher
+    /// - [`Err`] with [`Error::InvalidKey`] if the matrix is empty or it is not
+    ///   invertible modulo 26
+    pub fn new<const N: usize>(matrix: [[usize; N]; N]) -> Result<Self, Error> {
+        Self::from_entries(N, matrix.iter().flatten().copied().collect())
+    }
+
+    /// Creates the cipher from a keyword whose letters fill the key matrix by
+    /// rows. The number of letters must be a square, e.g. `GYBNQKURP` gives
+    /// a 3&times;3 matrix.
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with the cipher
+    /// - [`Err`] with [`Error::InvalidKey`] if the keyword contains a character
+    ///   other than a Latin letter, or the number of letters is not a square, or
+    ///   the matrix is not invertible modulo 26
+    pub fn from_keyword(keyword: &str) -> Result<Self, Error> {
+        let entries = Alphabet::latin().key_indices(keyword)?;
+        let size = (1..=entries.len())
+            .find(|size| size * size >= entries.len())
+            .filter(|size| size * size == entries.len())
+            .ok_or_else(|| Error::InvalidKey {
+                reason: format!("{} letters do not form a square matrix", entries.len())
+            })?;
+        Self::from_entries(size, entries)
+    }
+
+    /// Creates the cipher from a keyword stored in a [`Key`] as UTF-8 text, see
+    /// [`from_keyword()`](Hill::from_keyword).
+    pub fn from_key(key: &Key) -> Result<Self, Error> {
+        Self::from_keyword(key_text(key)?)
+    }
+
+    /// Returns the key matrix by rows.
+    pub fn matrix(&self) -> Vec<Vec<usize>> {
+        self.matrix.chunks(self.size).map(<[usize]>::to_vec).collect()
+    }
+
+    fn from_entries(size: usize, matrix: Vec<usize>) -> Result<Self, Error> {
+        if size == 0 {
+            return Err(Error::InvalidKey { reason: "key matrix is empty".into() })
+        }
+        let matrix: Vec<usize> = matrix.into_iter().map(|entry| entry % MODULUS).collect();
+        // 26 is not a prime, so the matrix is inverted modulo its prime factors 2
+        // and 13 and the inverses are joined by the Chinese remainder theorem:
+        let inverse = invert(&matrix, size, 2)
+            .zip(invert(&matrix, size, 13))
+            .map(|(modulo_2, modulo_13)| modulo_2.into_iter()
+                .zip(modulo_13)
+                .map(|(modulo_2, modulo_13)| (13 * modulo_2 + 14 * modulo_13) % MODULUS)
+                .collect())
+            .ok_or_else(|| Error::InvalidKey {
+                reason: "key matrix is not invertible modulo 26".into()
+            })?;
+        Ok(Self { size, matrix, inverse })
+    }
+
+    /// Multiplies blocks of letters of the text by `matrix`.
+    fn apply(&self, text: &[u8], matrix: &[usize]) -> Vec<u8> {
+        let latin = Alphabet::latin();
+        let mut letters = latin.indices(text);
+        let padding = (self.size - letters.len() % self.size) % self.size;
+        letters.resize(letters.len() + padding, X);
+        let mut mapped = letters.chunks(self.size)
+            .flat_map(|block| matrix.chunks(self.size).map(move |row| row.iter()
+                .zip(block)
+                .map(|(entry, letter)| entry * letter)
+                .sum::<usize>()))
+            .collect::<Vec<usize>>()
+            .into_iter();
+
+        let mut output = latin.map_text(text, |_| mapped.next().unwrap());
+        output.extend(mapped.map(|letter| latin.symbol(letter, false) as u8));
+        output
+    }
+}
+
+impl Encrypt for Hill {
+
+    fn encrypt<D, E>(&self, data_to_encrypt: D) -> E
+    where
+        D:           IntoIterator<Item = u8>,
+        D::IntoIter: ExactSizeIterator,
+        E:           FromIterator<u8>
+    {
+        let data: Vec<u8> = data_to_encrypt.into_iter().collect();
+        self.apply(&data, &self.matrix).into_iter().collect()
+    }
+}
+
+impl Decrypt for Hill {
+
+    fn decrypt<E, D>(&self, encrypted_data: E) -> D
+    where
+        E:           IntoIterator<Item = u8>,
+        E::IntoIter: ExactSizeIterator,
+        D:           FromIterator<u8>
+    {
+        let data: Vec<u8> = encrypted_data.into_iter().collect();
+        self.apply(&data, &self.inverse).into_iter().collect()
+    }
+}
+
+/// Inverts a square matrix modulo a prime by Gauss&ndash;Jordan elimination.
+/// Returns [`None`] if the matrix is singular.
+fn invert(matrix: &[usize], size: usize, prime: usize) -> Option<Vec<usize>> {
+    let width = 2 * size;
+    // The matrix extended by the identity matrix on the right:
+    let mut extended: Vec<usize> = (0..size * width)
+        .map(|idx| {
+            let (row, column) = (idx / width, idx % width);
+            match column < size {
+                true  => matrix[row * size + column] % prime,
+                false => (column - size == row) as usize,
+            }
+        })
+        .collect();
+
+    for column in 0..size {
+        let pivot = (column..size).find(|&row| extended[row * width + column] != 0)?;
+        for idx in 0..width {
+            extended.swap(column * width + idx, pivot * width + idx);
+        }
+        let inverse = (1..prime).find(|candidate| {
+            candidate * extended[column * width + column] % prime == 1
+        })?;
+        for idx in 0..width {
+            extended[column * width + idx] = extended[column * width + idx] * inverse % prime;
+        }
+        for row in (0..size).filter(|&row| row != column) {
+            let factor = extended[row * width + column];
+            for idx in 0..width {
+                let subtrahend = factor * extended[column * width + idx] % prime;
+                extended[row * width + idx] = (extended[row * width + idx] + prime - subtrahend)
+                    % prime;
+            }
+        }
+    }
+    Some(extended.chunks(width).flat_map(|row| row[size..].to_vec()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn textbook() {
+        let cipher = Hill::from_keyword("GYBNQKURP").unwrap();
+        assert_eq!(cipher.matrix(), vec![vec![6, 24, 1], vec![13, 16, 10], vec![20, 17, 15]]);
+        let encrypted: Vec<u8> = cipher.encrypt(b"ACT".to_vec());
+        assert_eq!(encrypted, b"POH");
+        let decrypted: Vec<u8> = cipher.decrypt(encrypted);
+        assert_eq!(decrypted, b"ACT");
+
+        let cipher = Hill::new([[3, 3], [2, 5]]).unwrap();
+        let encrypted: Vec<u8> = cipher.encrypt(b"Help!".to_vec());
+        assert_eq!(encrypted, b"Hiat!");
+    }
+
+    #[test]
+    fn round_trip() {
+        let text = "The quick brown fox jumps over the lazy dog";
+        let key = Key::new(b"hillcipherkeyabc".to_vec());
+        for cipher in [Hill::new([[7]]).unwrap(), Hill::from_key(&key).unwrap()] {
+            let encrypted: Vec<u8> = cipher.encrypt(text.bytes());
+            assert_ne!(encrypted, text.as_bytes());
+            let decrypted: Vec<u8> = cipher.decrypt(encrypted);
+            // 35 letters are padded to a multiple of the size:
+            let padding = (cipher.size - 35 % cipher.size) % cipher.size;
+            assert_eq!(decrypted, format!("{}{}", text, "X".repeat(padding)).as_bytes());
+        }
+    }
+
+    #[test]
+    fn invalid_key() {
+        // Determinants 0, 13 and 2:
+        assert!(matches!(Hill::new([[1, 2], [2, 4]]), Err(Error::InvalidKey { .. })));
+        assert!(Hill::new([[13]]).is_err());
+        assert!(Hill::new([[2, 0], [0, 1]]).is_err());
+        assert!(Hill::new::<0>([]).is_err());
+        assert!(Hill::from_keyword("ABCDE").is_err());
+        assert!(Hill::from_keyword("").is_err());
+    }
+}
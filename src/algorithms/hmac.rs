@@ -0,0 +1,252 @@
+use std::iter::ExactSizeIterator;
+use std::iter::FromIterator;
+use std::iter::IntoIterator;
+
+use sha2::digest::core_api::BlockSizeUser;
+use subtle::ConstantTimeEq;
+use zeroize::Zeroizing;
+
+use crate::algorithms::Sha2;
+use crate::algorithms::Sha2Stream;
+use crate::Digest;
+use crate::Encrypt;
+use crate::Key;
+
+/// Byte XORed with the key for the inner hash.
+const IPAD: u8 = 0x36;
+
+/// Byte XORed with the key for the outer hash.
+const OPAD: u8 = 0x5c;
+
+/// Keyed-hash message authentication code (HMAC) as specified by RFC 2104, built
+/// on a hasher of the SHA-2 family. It implements [`Encrypt`] trait by computing
+/// the tag of the whole data at once. For authenticating data fed by chunks, get
+/// a stateful [`HmacStream`] by calling [`authenticator()`].
+///
+/// Use type aliases such as [`Hmac256`] rather than this struct directly.
+///
+/// [`authenticator()`]: Hmac::authenticator
+/// [`Encrypt`]: crate::Encrypt
+#[derive(Clone)]
+pub struct Hmac<T>
+where T: Default + Clone + sha2::Digest + BlockSizeUser,
+{
+    /// Inner hasher fed by the key.
+    keyed_inner: Sha2Stream<T>,
+    /// Outer hasher fed by the key.
+    keyed_outer: Sha2Stream<T>,
+}
+
+impl<T> Hmac<T>
+where T: Default + Clone + sha2::Digest + BlockSizeUser,
+{
+    /// Creates a new [`Hmac`] instance.
+    ///
+    /// # Parameters
+    ///
+    /// - `key`: secret key; keys longer than block size of the hash function are
+    ///   hashed first as RFC 2104 specifies
+    pub fn new(key: &Key) -> Self {
+        // Key is padded by zeros to the block size. Longer key is hashed first:
+        let block_len = T::block_size();
+        let mut key_block: Zeroizing<Vec<u8>> = Zeroizing::new(match key.len() > block_len {
+            true  => Sha2::<T>::default().encrypt(key.iter().copied()),
+            false => key[..].to_vec()
+        });
+        key_block.resize(block_len, 0);
+        // Feed both hashers with the key XORed with paddings:
+        let pad = |byte: u8| -> Zeroizing<Vec<u8>> {
+            Zeroizing::new(key_block.iter().map(|key_byte| key_byte ^ byte).collect())
+        };
+        let mut keyed_inner = Sha2Stream::new();
+        keyed_inner.update(&pad(IPAD));
+        let mut keyed_outer = Sha2Stream::new();
+        keyed_outer.update(&pad(OPAD));
+        Self { keyed_inner, keyed_outer }
+    }
+
+    /// Returns a new stateful authenticator with no data authenticated yet.
+    pub fn authenticator(&self) -> HmacStream<T> {
+        HmacStream { inner: self.keyed_inner.clone(), keyed_outer: self.keyed_outer.clone() }
+    }
+}
+
+impl<T> Encrypt for Hmac<T>
+where T: Default + Clone + sha2::Digest + BlockSizeUser,
+{
+    /// Computes the tag of given data.
+    fn encrypt<D, E>(&self, data_to_encrypt: D) -> E
+    where
+        D: IntoIterator<Item = u8>,
+        D::IntoIter: ExactSizeIterator,
+        E: FromIterator<u8>,
+    {
+        let mut authenticator = self.authenticator();
+        authenticator.update(&data_to_encrypt.into_iter().collect::<Vec<u8>>());
+        authenticator.finalize().into_iter().collect()
+    }
+}
+
+/// State of HMAC authentication fed by chunks of data. Feed it with data by
+/// calling [`update()`] any number of times and get the tag by calling
+/// [`finalize()`] or check a received tag by calling [`verify()`].
+///
+/// [`update()`]: HmacStream::update
+/// [`finalize()`]: HmacStream::finalize
+/// [`verify()`]: HmacStream::verify
+#[derive(Clone)]
+pub struct HmacStream<T>
+where T: Default + Clone + sha2::Digest + BlockSizeUser,
+{
+    /// Inner hasher fed by the key and by data given to `update()`.
+    inner: Sha2Stream<T>,
+    /// Outer hasher fed by the key.
+    keyed_outer: Sha2Stream<T>,
+}
+
+impl<T> HmacStream<T>
+where T: Default + Clone + sha2::Digest + BlockSizeUser,
+{
+    /// Feeds the authenticator with next chunk of data.
+    ///
+    /// # Parameters
+    ///
+    /// - `data`: data to authenticate
+    pub fn update(&mut self, data: &[u8]) {
+        self.inner.update(data);
+    }
+
+    /// Finishes authentication and returns the tag of all data fed into the
+    /// authenticator.
+    pub fn finalize(self) -> Digest {
+        let mut outer = self.keyed_outer;
+        outer.update(self.inner.finalize().as_bytes());
+        outer.finalize()
+    }
+
+    /// Finishes authentication and checks that given tag matches the tag of all
+    /// data fed into the authenticator. Comparison takes the same time no matter
+    /// how many bytes of the tags match, so it does not reveal the correct tag.
+    ///
+    /// # Parameters
+    ///
+    /// - `tag`: received tag to check
+    ///
+    /// # Returns
+    ///
+    /// `true` if the tag is correct, `false` otherwise
+    pub fn verify(self, tag: &[u8]) -> bool {
+        self.finalize().as_bytes().ct_eq(tag).into()
+    }
+}
+
+/// HMAC-SHA-224 authenticator implementing [`Encrypt`] trait from this crate.
+///
+/// [`Encrypt`]: crate::Encrypt
+pub type Hmac224 = Hmac<sha2::Sha224>;
+
+/// HMAC-SHA-256 authenticator implementing [`Encrypt`] trait from this crate.
+///
+/// [`Encrypt`]: crate::Encrypt
+pub type Hmac256 = Hmac<sha2::Sha256>;
+
+/// HMAC-SHA-384 authenticator implementing [`Encrypt`] trait from this crate.
+///
+/// [`Encrypt`]: crate::Encrypt
+pub type Hmac384 = Hmac<sha2::Sha384>;
+
+/// HMAC-SHA-512 authenticator implementing [`Encrypt`] trait from this crate.
+///
+/// [`Encrypt`]: crate::Encrypt
+pub type Hmac512 = Hmac<sha2::Sha512>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::hex;
+
+    /// Keys and data of RFC 4231 test cases 1, 2, 3, 4, 6 and 7 in this order.
+    fn rfc_4231_inputs() -> Vec<(Key, Vec<u8>)> {
+        vec![
+            (Key::new(vec![0x0b; 20]), b"Hi There".to_vec()),
+            (Key::new(b"Jefe".to_vec()), b"what do ya want for nothing?".to_vec()),
+            (Key::new(vec![0xaa; 20]), vec![0xdd; 50]),
+            (Key::new((0x01..=0x19).collect()), vec![0xcd; 50]),
+            (Key::new(vec![0xaa; 131]),
+                b"Test Using Larger Than Block-Size Key - Hash Key First".to_vec()),
+            (Key::new(vec![0xaa; 131]), b"This is a test using a larger than block-size \
+                key and a larger than block-size data. The key needs to be hashed before \
+                being used by the HMAC algorithm.".to_vec()),
+        ]
+    }
+
+    #[test]
+    fn rfc_4231_sha256() {
+        let expected = [
+            "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7",
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843",
+            "773ea91e36800e46854db8ebd09181a72959098b3ef8c122d9635514ced565fe",
+            "82558a389a443c0ea4cc819899f2083a85f0faa3e578f8077a2e3ff46729665b",
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54",
+            "9b09ffa71b942fcb27635fbcd5b0e944bfdc63644f0713938a7f51535c3a35e2",
+        ];
+
+        for ((key, data), expected) in rfc_4231_inputs().into_iter().zip(expected) {
+            let tag: Vec<u8> = Hmac256::new(&key).encrypt(data);
+            assert_eq!(tag, hex(expected));
+        }
+    }
+
+    #[test]
+    fn rfc_4231_sha512() {
+        let expected = [
+            concat!("87aa7cdea5ef619d4ff0b4241a1d6cb02379f4e2ce4ec2787ad0b30545e17cde",
+                "daa833b7d6b8a702038b274eaea3f4e4be9d914eeb61f1702e696c203a126854"),
+            concat!("164b7a7bfcf819e2e395fbe73b56e0a387bd64222e831fd610270cd7ea250554",
+                "9758bf75c05a994a6d034f65f8f0e6fdcaeab1a34d4a6b4b636e070a38bce737"),
+            concat!("fa73b0089d56a284efb0f0756c890be9b1b5dbdd8ee81a3655f83e33b2279d39",
+                "bf3e848279a722c806b485a47e67c807b946a337bee8942674278859e13292fb"),
+            concat!("b0ba465637458c6990e5a8c5f61d4af7e576d97ff94b872de76f8050361ee3db",
+                "a91ca5c11aa25eb4d679275cc5788063a5f19741120c4f2de2adebeb10a298dd"),
+            concat!("80b24263c7c1a3ebb71493c1dd7be8b49b46d1f41b4aeec1121b013783f8f352",
+                "6b56d037e05f2598bd0fd2215d6a1e5295e64f73f63f0aec8b915a985d786598"),
+            concat!("e37b6a775dc87dbaa4dfa9f96e5e3ffddebd71f8867289865df5a32d20cdc944",
+                "b6022cac3c4982b10d5eeb55c3e4de15134676fb6de0446065c97440fa8c6a58"),
+        ];
+
+        for ((key, data), expected) in rfc_4231_inputs().into_iter().zip(expected) {
+            let tag: Vec<u8> = Hmac512::new(&key).encrypt(data);
+            assert_eq!(tag, hex(expected));
+        }
+    }
+
+    #[test]
+    fn rfc_4231_sha224_sha384() {
+        let (key, data) = rfc_4231_inputs().swap_remove(1);
+        let tag: Vec<u8> = Hmac224::new(&key).encrypt(data.clone());
+        assert_eq!(tag, hex("a30e01098bc6dbbf45690f3a7e9e6d0f8bbea2a39e6148008fd05e44"));
+        let tag: Vec<u8> = Hmac384::new(&key).encrypt(data);
+        assert_eq!(tag, hex(concat!(
+            "af45d2e376484031617f78d2b58a6b1b9c7ef464f5a01b47e42ec3736322445e",
+            "8e2240ca5e69e2c78b3239ecfab21649"
+        )));
+    }
+
+    #[test]
+    fn streaming_and_verify() {
+        for (key, data) in rfc_4231_inputs() {
+            let expected: Vec<u8> = Hmac256::new(&key).encrypt(data.clone());
+            let mut hmac = Hmac256::new(&key).authenticator();
+            for chunk in data.chunks(7) {
+                hmac.update(chunk);
+            }
+            assert!(hmac.clone().verify(&expected));
+
+            let mut wrong = expected.clone();
+            wrong[31] ^= 1;
+            assert!(!hmac.clone().verify(&wrong));
+            assert!(!hmac.clone().verify(&expected[..16]));
+            assert_eq!(hmac.finalize().as_bytes(), &expected[..]);
+        }
+    }
+}
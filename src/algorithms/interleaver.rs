@@ -0,0 +1,443 @@
+use std::fmt;
+use std::iter::FromIterator;
+use std::iter::IntoIterator;
+
+use sha2::Digest;
+use sha2::digest::generic_array::GenericArray;
+use zeroize::Zeroize;
+
+use crate::decrypt::Decrypt;
+use crate::encrypt::Encrypt;
+use crate::error::Error;
+use crate::key::Key;
+
+/// A permutation of items which spreads consecutive items apart, so that a burst
+/// of errors in the interleaved sequence hits items far from each other in the
+/// original sequence. Placed in front of an error correction code, a burst then
+/// affects many codewords a little instead of a single codeword a lot.
+///
+/// Interleavers work for sequences of any length and do not change the length.
+/// Implementors in this crate also implement [`Encrypt`] and [`Decrypt`], which
+/// interleave and de-interleave bytes. [`HammingECC`] uses an interleaver for
+/// bits of its blocks.
+///
+/// [`HammingECC`]: crate::algorithms::HammingECC
+pub trait Interleaver {
+
+    /// Returns positions of items of a sequence of given length in the order
+    /// they appear in the interleaved sequence. The positions must be a
+    /// permutation of `0..len`. They are computed as they are iterated where
+    /// possible, so that long sequences can be interleaved without storing the
+    /// whole permutation.
+    fn order(&self, len: usize) -> impl Iterator<Item = usize>;
+
+    /// Returns given items in the interleaved order.
+    fn interleave<T: Copy>(&self, items: &[T]) -> Vec<T> {
+        self.order(items.len()).map(|position| items[position]).collect()
+    }
+
+    /// Returns interleaved items in the original order, the inverse of
+    /// [`interleave()`](Interleaver::interleave).
+    fn deinterleave<T: Copy>(&self, items: &[T]) -> Vec<T> {
+        let mut output = items.to_vec();
+        for (&item, position) in items.iter().zip(self.order(items.len())) {
+            output[position] = item;
+        }
+        output
+    }
+}
+
+/// An interleaver which keeps items in their order, e.g. disables interleaving.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct NoInterleaver;
+
+impl Interleaver for NoInterleaver {
+    fn order(&self, len: usize) -> impl Iterator<Item = usize> {
+        0..len
+    }
+}
+
+/// The block interleaver, which writes items in rows of a given number of
+/// columns and reads the columns from top to bottom. The number of rows is given
+/// by the length of the sequence and the last row is not padded.
+///
+/// Items which are consecutive in the interleaved sequence are one row apart in
+/// the original sequence. With rows as long as the codewords of an error
+/// correction code, consecutive items belong to consecutive codewords.
+///
+/// ```
+/// use mdcrypt::algorithms::BlockInterleaver;
+/// use mdcrypt::Encrypt;
+///
+/// let interleaver = BlockInterleaver::new(3).unwrap();
+/// let interleaved: Vec<u8> = interleaver.encrypt(b"abcdefgh".to_vec());
+/// assert_eq!(interleaved, b"adgbehcf");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BlockInterleaver {
+    columns: usize,
+}
+
+impl BlockInterleaver {
+
+    /// Creates the interleaver with given number of columns.
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with the interleaver
+    /// - [`Err`] with [`Error::InvalidParameters`] if `columns` is zero
+    pub fn new(columns: usize) -> Result<Self, Error> {
+        if columns == 0 {
+            return Err(Error::invalid_parameter("columns", "must not be zero"))
+        }
+        Ok(Self { columns })
+    }
+
+    /// Returns number of columns, e.g. length of each row.
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+}
+
+impl Interleaver for BlockInterleaver {
+    fn order(&self, len: usize) -> impl Iterator<Item = usize> {
+        let columns = self.columns;
+        (0..columns.min(len)).flat_map(move |column| (column..len).step_by(columns))
+    }
+}
+
+/// The convolutional interleaver, which deals items to a number of branches in
+/// turn and delays each branch by `delay` more turns than the previous one. The
+/// first branch is not delayed.
+///
+/// Unlike the classical convolutional interleaver, which is fed by an endless
+/// stream, this one is tail-biting: items delayed past the end of the sequence
+/// wrap around to the beginning of their branch. So the length of the sequence
+/// is kept and no memory has to be flushed.
+///
+/// Items which are consecutive in the interleaved sequence are about
+/// `branches * delay` items apart in the original sequence, so a burst of up
+/// to `branches` items is spread that far.
+///
+/// ```
+/// use mdcrypt::algorithms::ConvolutionalInterleaver;
+/// use mdcrypt::Encrypt;
+///
+/// let interleaver = ConvolutionalInterleaver::new(3, 1).unwrap();
+/// let interleaved: Vec<u8> = interleaver.encrypt(b"abcdefghi".to_vec());
+/// assert_eq!(interleaved, b"ahfdbigec");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ConvolutionalInterleaver {
+    branches: usize,
+    delay: usize,
+}
+
+impl ConvolutionalInterleaver {
+
+    /// Creates the interleaver.
+    ///
+    /// # Parameters
+    ///
+    /// - `branches`: number of branches items are dealt to
+    /// - `delay`: number of turns each branch is delayed by more than the
+    ///   previous one
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with the interleaver
+    /// - [`Err`] with [`Error::InvalidParameters`] if `branches` is zero
+    pub fn new(branches: usize, delay: usize) -> Result<Self, Error> {
+        if branches == 0 {
+            return Err(Error::invalid_parameter("branches", "must not be zero"))
+        }
+        Ok(Self { branches, delay })
+    }
+
+    /// Returns number of branches.
+    pub fn branches(&self) -> usize {
+        self.branches
+    }
+
+    /// Returns number of turns each branch is delayed by more than the previous
+    /// one.
+    pub fn delay(&self) -> usize {
+        self.delay
+    }
+}
+
+impl Interleaver for ConvolutionalInterleaver {
+    fn order(&self, len: usize) -> impl Iterator<Item = usize> {
+        let Self { branches, delay } = *self;
+        (0..len).map(move |position| {
+            let branch = position % branches;
+            let turn = position / branches;
+            // Number of items dealt to the branch and its delay within them:
+            let branch_len = (len - branch).div_ceil(branches);
+            let shift = branch * (delay % branch_len) % branch_len;
+            branch + branches * ((turn + branch_len - shift) % branch_len)
+        })
+    }
+}
+
+/// An interleaver which shuffles items by a pseudo-random permutation derived
+/// from a key. The permutation of a sequence depends on its length, so both
+/// parties have to agree on the length of interleaved data.
+///
+/// The permutation is generated by the Fisher&ndash;Yates shuffle fed by
+/// SHA-256 of the hashed key and a counter. It hides the order of the items
+/// from anyone without the key, but it is not meant to be a cipher. Unlike
+/// other interleavers, it has to generate the whole permutation at once.
+///
+/// The seed derived from the key is wiped from the memory on drop, and so are
+/// the digests and the SHA-256 states derived from it.
+#[derive(Clone, PartialEq, Eq)]
+pub struct PseudoRandomInterleaver {
+    seed: [u8; 32],
+}
+
+impl PseudoRandomInterleaver {
+
+    /// Creates the interleaver whose permutations are derived from given key.
+    pub fn new(key: &Key) -> Self {
+        let mut seed = [0; 32];
+        sha256_into(&[key.reveal()], &mut seed);
+        Self { seed }
+    }
+}
+
+impl Drop for PseudoRandomInterleaver {
+    fn drop(&mut self) {
+        self.seed.zeroize();
+    }
+}
+
+impl fmt::Debug for PseudoRandomInterleaver {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // The seed is derived from a secret key, so it is not shown:
+        formatter.debug_struct("PseudoRandomInterleaver").finish_non_exhaustive()
+    }
+}
+
+impl Interleaver for PseudoRandomInterleaver {
+    fn order(&self, len: usize) -> impl Iterator<Item = usize> {
+        let mut random = KeyStream { seed: &self.seed, counter: 0, block: [0; 32], used: 32 };
+        let mut order: Vec<usize> = (0..len).collect();
+        for position in (1..len).rev() {
+            // The modulo bias is negligible for any length that fits in memory:
+            let other = random.next_u64() % (position as u64 + 1);
+            order.swap(position, other as usize);
+        }
+        order.into_iter()
+    }
+}
+
+/// Stream of pseudo-random numbers made of SHA-256 digests of a seed followed
+/// by a counter. The digest is wiped from the memory on drop, like the seed.
+struct KeyStream<'a> {
+    seed: &'a [u8; 32],
+    counter: u64,
+    block: [u8; 32],
+    /// Number of bytes of the block already used.
+    used: usize,
+}
+
+impl KeyStream<'_> {
+
+    fn next_u64(&mut self) -> u64 {
+        if self.used == self.block.len() {
+            sha256_into(&[self.seed, &self.counter.to_be_bytes()], &mut self.block);
+            self.counter += 1;
+            self.used = 0;
+        }
+        let bytes = self.block[self.used..][..8].try_into().unwrap();
+        self.used += 8;
+        u64::from_be_bytes(bytes)
+    }
+}
+
+impl Drop for KeyStream<'_> {
+    fn drop(&mut self) {
+        self.block.zeroize();
+    }
+}
+
+/// Writes SHA-256 of the concatenated parts into `output`, without any copy of
+/// the digest, and wipes the state of the hasher, which absorbed the parts.
+fn sha256_into(parts: &[&[u8]], output: &mut [u8; 32]) {
+    let mut hasher = sha2::Sha256::new();
+    parts.iter().for_each(|part| hasher.update(part));
+    hasher.finalize_into_reset(GenericArray::from_mut_slice(output));
+    // SAFETY: the hasher is made of integers and arrays of them only, for which
+    // all zeros is a valid value
+    unsafe { zeroize::zeroize_flat_type(&mut hasher) };
+}
+
+impl Encrypt for BlockInterleaver {
+
+    fn encrypt<D, E>(&self, data_to_encrypt: D) -> E
+    where
+        D:           IntoIterator<Item = u8>,
+        D::IntoIter: ExactSizeIterator,
+        E:           FromIterator<u8>
+    {
+        interleave_bytes(self, data_to_encrypt)
+    }
+}
+
+impl Decrypt for BlockInterleaver {
+
+    fn decrypt<E, D>(&self, encrypted_data: E) -> D
+    where
+        E:           IntoIterator<Item = u8>,
+        E::IntoIter: ExactSizeIterator,
+        D:           FromIterator<u8>
+    {
+        deinterleave_bytes(self, encrypted_data)
+    }
+}
+
+impl Encrypt for ConvolutionalInterleaver {
+
+    fn encrypt<D, E>(&self, data_to_encrypt: D) -> E
+    where
+        D:           IntoIterator<Item = u8>,
+        D::IntoIter: ExactSizeIterator,
+        E:           FromIterator<u8>
+    {
+        interleave_bytes(self, data_to_encrypt)
+    }
+}
+
+impl Decrypt for ConvolutionalInterleaver {
+
+    fn decrypt<E, D>(&self, encrypted_data: E) -> D
+    where
+        E:           IntoIterator<Item = u8>,
+        E::IntoIter: ExactSizeIterator,
+        D:           FromIterator<u8>
+    {
+        deinterleave_bytes(self, encrypted_data)
+    }
+}
+
+impl Encrypt for PseudoRandomInterleaver {
+
+    fn encrypt<D, E>(&self, data_to_encrypt: D) -> E
+    where
+        D:           IntoIterator<Item = u8>,
+        D::IntoIter: ExactSizeIterator,
+        E:           FromIterator<u8>
+    {
+        interleave_bytes(self, data_to_encrypt)
+    }
+}
+
+impl Decrypt for PseudoRandomInterleaver {
+
+    fn decrypt<E, D>(&self, encrypted_data: E) -> D
+    where
+        E:           IntoIterator<Item = u8>,
+        E::IntoIter: ExactSizeIterator,
+        D:           FromIterator<u8>
+    {
+        deinterleave_bytes(self, encrypted_data)
+    }
+}
+
+fn interleave_bytes<I, D, E>(interleaver: &I, data: D) -> E
+where
+    I: Interleaver,
+    D: IntoIterator<Item = u8>,
+    E: FromIterator<u8>
+{
+    let data: Vec<u8> = data.into_iter().collect();
+    interleaver.interleave(&data).into_iter().collect()
+}
+
+fn deinterleave_bytes<I, E, D>(interleaver: &I, data: E) -> D
+where
+    I: Interleaver,
+    E: IntoIterator<Item = u8>,
+    D: FromIterator<u8>
+{
+    let data: Vec<u8> = data.into_iter().collect();
+    interleaver.deinterleave(&data).into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip<I>(interleaver: &I, len: usize) -> Vec<u8>
+    where I: Interleaver + Encrypt + Decrypt {
+        let data: Vec<u8> = (0..len).map(|item| item as u8).collect();
+        let interleaved: Vec<u8> = interleaver.encrypt(data.clone());
+        let deinterleaved: Vec<u8> = interleaver.decrypt(interleaved.clone());
+        assert_eq!(deinterleaved, data);
+        let mut sorted: Vec<usize> = interleaver.order(len).collect();
+        sorted.sort_unstable();
+        assert_eq!(sorted, (0..len).collect::<Vec<usize>>());
+        interleaved
+    }
+
+    #[test]
+    fn block() {
+        let interleaver = BlockInterleaver::new(4).unwrap();
+        assert_eq!(round_trip(&interleaver, 8), [0, 4, 1, 5, 2, 6, 3, 7]);
+        assert_eq!(round_trip(&interleaver, 6), [0, 4, 1, 5, 2, 3]);
+        assert_eq!(round_trip(&interleaver, 3), [0, 1, 2]);
+        for len in 0..30 {
+            for columns in 1..8 {
+                round_trip(&BlockInterleaver::new(columns).unwrap(), len);
+            }
+        }
+        assert!(BlockInterleaver::new(1).unwrap().order(5).eq(NoInterleaver.order(5)));
+        assert!(matches!(BlockInterleaver::new(0), Err(Error::InvalidParameters { .. })));
+    }
+
+    #[test]
+    fn convolutional() {
+        let interleaver = ConvolutionalInterleaver::new(3, 1).unwrap();
+        assert_eq!(round_trip(&interleaver, 12), [0, 10, 8, 3, 1, 11, 6, 4, 2, 9, 7, 5]);
+        for len in 0..30 {
+            for branches in 1..5 {
+                for delay in 0..4 {
+                    round_trip(&ConvolutionalInterleaver::new(branches, delay).unwrap(), len);
+                }
+            }
+        }
+        // Without a delay, items are kept in place:
+        let identity = ConvolutionalInterleaver::new(4, 0).unwrap();
+        assert!(identity.order(10).eq(NoInterleaver.order(10)));
+        assert!(matches!(ConvolutionalInterleaver::new(0, 1),
+            Err(Error::InvalidParameters { .. })));
+    }
+
+    #[test]
+    fn burst_is_spread() {
+        let interleaver = ConvolutionalInterleaver::new(8, 4).unwrap();
+        let order: Vec<usize> = interleaver.order(1000).collect();
+        // Items of a burst in the middle of the sequence are far apart:
+        let mut burst = order[500..508].to_vec();
+        burst.sort_unstable();
+        assert!(burst.windows(2).all(|pair| pair[1] - pair[0] >= 8 * 4 - 1));
+    }
+
+    #[test]
+    fn pseudo_random() {
+        let interleaver = PseudoRandomInterleaver::new(&Key::new(b"secret".to_vec()));
+        let interleaved = round_trip(&interleaver, 100);
+        assert_ne!(interleaved, (0..100).collect::<Vec<u8>>());
+        for len in 0..30 {
+            round_trip(&interleaver, len);
+        }
+        // The same key gives the same permutation, another key another one:
+        let same = PseudoRandomInterleaver::new(&Key::new(b"secret".to_vec()));
+        let other = PseudoRandomInterleaver::new(&Key::new(b"Secret".to_vec()));
+        assert_eq!(same, interleaver);
+        assert!(same.order(100).eq(interleaver.order(100)));
+        assert!(!other.order(100).eq(interleaver.order(100)));
+        assert_eq!(format!("{:?}", interleaver), "PseudoRandomInterleaver { .. }");
+    }
+}
@@ -0,0 +1,208 @@
+use std::iter::FromIterator;
+use std::iter::IntoIterator;
+
+use crate::algorithms::Alphabet;
+use crate::algorithms::alphabet::key_text;
+use crate::decrypt::Decrypt;
+use crate::encrypt::Encrypt;
+use crate::error::Error;
+use crate::key::Key;
+
+/// Side of the Playfair square.
+const SIDE: usize = 5;
+
+/// Index of J in the Latin alphabet, which is written as I.
+const J: usize = 9;
+
+/// Index of I in the Latin alphabet.
+const I: usize = 8;
+
+/// Index of X in the Latin alphabet, which separates doubled letters.
+const X: usize = 23;
+
+/// Index of Q in the Latin alphabet, which separates doubled X.
+const Q: usize = 16;
+
+/// The Playfair cipher, which encrypts pairs of letters using a 5&times;5 square
+/// of the Latin alphabet without J, filled by a keyword followed by the remaining
+/// letters.
+///
+/// The cipher works the textbook way: only letters of the text are encrypted,
+/// J is replaced by I, and the output is written in uppercase without any other
+/// characters. Before encryption the letters are split into pairs, X is inserted
+/// between two same letters of a pair (Q between two X) and X is appended if the
+/// number of letters is odd. Decryption gives the letters with the inserted X.
+///
+/// ```
+/// use mdcrypt::algorithms::Playfair;
+/// use mdcrypt::Encrypt;
+///
+/// let cipher = Playfair::new("playfair example").unwrap();
+/// let encrypted: Vec<u8> = cipher.encrypt(b"Hide the gold in the tree stump".to_vec());
+/// assert_eq!(encrypted, b"BMODZBXDNABEKUDMUIXMMOUVIF");
+/// ```
+#[derive(Clone, Debug)]
+pub struct Playfair {
+    square: [usize; SIDE * SIDE],
+    positions: [usize; 26],
+}
+
+impl Playfair {
+
+    /// Creates the cipher from a keyword. Letters of the keyword fill the square
+    /// in their order without repetitions, and spaces are ignored.
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with the cipher
+    /// - [`Err`] with [`Error::InvalidKey`] if the keyword has no letter or
+    ///   contains a character other than a Latin letter or a space
+    pub fn new(keyword: &str) -> Result<Self, Error> {
+        let keyword: String = keyword.chars().filter(|&character| character != ' ').collect();
+        let mut letters = Alphabet::latin().key_indices(&keyword)?;
+        letters.extend(0..26);
+        let mut positions = [usize::MAX; 26];
+        let mut square = [0; SIDE * SIDE];
+        let mut filled = 0;
+        for letter in letters.into_iter().map(normalize) {
+            if positions[letter] == usize::MAX {
+                positions[letter] = filled;
+                square[filled] = letter;
+                filled += 1;
+            }
+        }
+        positions[J] = positions[I];
+        Ok(Self { square, positions })
+    }
+
+    /// Creates the cipher from a keyword stored in a [`Key`] as UTF-8 text.
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with the cipher
+    /// - [`Err`] with [`Error::InvalidKey`] if the key is not UTF-8 text or it
+    ///   is not a valid keyword
+    pub fn from_key(key: &Key) -> Result<Self, Error> {
+        Self::new(key_text(key)?)
+    }
+
+    /// Returns the square as five rows of five letters.
+    pub fn square(&self) -> [String; SIDE] {
+        let latin = Alphabet::latin();
+        std::array::from_fn(|row| self.square[row * SIDE..(row + 1) * SIDE].iter()
+            .map(|&letter| latin.symbol(letter, false))
+            .collect())
+    }
+
+    /// Maps pairs of letters of the text, moving letters in the same row or
+    /// column by `step` positions.
+    fn apply(&self, letters: Vec<usize>, step: usize) -> Vec<u8> {
+        let latin = Alphabet::latin();
+        letters.chunks(2)
+            .flat_map(|pair| {
+                let (first, second) = (self.positions[pair[0]], self.positions[pair[1]]);
+                let (row1, column1) = (first / SIDE, first % SIDE);
+                let (row2, column2) = (second / SIDE, second % SIDE);
+                let [(row1, column1), (row2, column2)] = if row1 == row2 {
+                    [(row1, (column1 + step) % SIDE), (row2, (column2 + step) % SIDE)]
+                } else if column1 == column2 {
+                    [((row1 + step) % SIDE, column1), ((row2 + step) % SIDE, column2)]
+                } else {
+                    [(row1, column2), (row2, column1)]
+                };
+                [self.square[row1 * SIDE + column1], self.square[row2 * SIDE + column2]]
+            })
+            .map(|letter| latin.symbol(letter, false) as u8)
+            .collect()
+    }
+}
+
+impl Encrypt for Playfair {
+
+    fn encrypt<D, E>(&self, data_to_encrypt: D) -> E
+    where
+        D:           IntoIterator<Item = u8>,
+        D::IntoIter: ExactSizeIterator,
+        E:           FromIterator<u8>
+    {
+        let data: Vec<u8> = data_to_encrypt.into_iter().collect();
+        let mut letters = Vec::new();
+        for letter in Alphabet::latin().indices(&data).into_iter().map(normalize) {
+            // Separate same letters of a pair:
+            if letters.len() % 2 == 1 && letters.last() == Some(&letter) {
+                letters.push(if letter == X { Q } else { X });
+            }
+            letters.push(letter);
+        }
+        if letters.len() % 2 == 1 {
+            letters.push(if letters.last() == Some(&X) { Q } else { X });
+        }
+        self.apply(letters, 1).into_iter().collect()
+    }
+}
+
+impl Decrypt for Playfair {
+
+    /// Decrypts letters of the encrypted text, ignoring other characters. If the
+    /// number of letters is odd, X is appended.
+    fn decrypt<E, D>(&self, encrypted_data: E) -> D
+    where
+        E:           IntoIterator<Item = u8>,
+        E::IntoIter: ExactSizeIterator,
+        D:           FromIterator<u8>
+    {
+        let data: Vec<u8> = encrypted_data.into_iter().collect();
+        let mut letters: Vec<usize> = Alphabet::latin().indices(&data).into_iter()
+            .map(normalize)
+            .collect();
+        if letters.len() % 2 == 1 {
+            letters.push(X);
+        }
+        self.apply(letters, SIDE - 1).into_iter().collect()
+    }
+}
+
+/// Writes J as I.
+fn normalize(letter: usize) -> usize {
+    if letter == J { I } else { letter }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn textbook() {
+        let cipher = Playfair::new("PLAYFAIR EXAMPLE").unwrap();
+        assert_eq!(cipher.square(), ["PLAYF", "IREXM", "BCDGH", "KNOQS", "TUVWZ"]);
+        let encrypted: Vec<u8> = cipher.encrypt(b"Hide the gold in the tree stump".to_vec());
+        assert_eq!(encrypted, b"BMODZBXDNABEKUDMUIXMMOUVIF");
+        let decrypted: Vec<u8> = cipher.decrypt(encrypted);
+        assert_eq!(decrypted, b"HIDETHEGOLDINTHETREXESTUMP");
+    }
+
+    #[test]
+    fn fillers() {
+        let cipher = Playfair::from_key(&Key::new(b"monarchy".to_vec())).unwrap();
+        for (text, letters) in [
+            ("balloon", "BALXLOON"),
+            ("jazz", "IAZXZX"),
+            ("xx", "XQXQ"),
+            ("a", "AX"),
+        ] {
+            let encrypted: Vec<u8> = cipher.encrypt(text.bytes());
+            assert_eq!(encrypted.len(), letters.len());
+            let decrypted: Vec<u8> = cipher.decrypt(encrypted);
+            assert_eq!(decrypted, letters.as_bytes());
+        }
+        let encrypted: Vec<u8> = cipher.encrypt(b"instruments".to_vec());
+        assert_eq!(encrypted, b"GATLMZCLRQXA");
+    }
+
+    #[test]
+    fn invalid_key() {
+        assert!(matches!(Playfair::new(""), Err(Error::InvalidKey { .. })));
+        assert!(Playfair::new("  ").is_err());
+        assert!(Playfair::new("keyword!").is_err());
+    }
+}
@@ -0,0 +1,607 @@
+use std::iter::FromIterator;
+use std::iter::IntoIterator;
+
+use crate::decrypt::TryDecrypt;
+use crate::encrypt::TryEncrypt;
+use crate::error::Error;
+use crate::gf256;
+
+/// Reed&ndash;Solomon error correction code RS(*n*, *k*) over GF(2<sup>8</sup>),
+/// which encodes each *k* bytes of data into a codeword of *n* bytes. A codeword
+/// can be restored if it contains *e* wrong bytes at unknown positions and *f*
+/// erased bytes at known positions as long as 2*e* + *f* &le; *n* &ndash; *k*.
+/// Since whole bytes are corrected regardless of how many of their bits are
+/// wrong, the code handles burst errors much better than [`HammingECC`], e.g.
+/// RS(255, 223) corrects any 16 bytes in each codeword.
+///
+/// # ECC Implementation
+///
+/// The code is systematic: a codeword consists of the data bytes followed by
+/// *n* &ndash; *k* parity bytes. The field uses the reducing polynomial `0x11d`
+/// and the generator polynomial has roots 2<sup>0</sup>, &hellip;,
+/// 2<sup>*n*&ndash;*k*&ndash;1</sup>, which is compatible with the common RS
+/// implementations.
+///
+/// Data are split into codewords in [`TryEncrypt`] implementation. The last
+/// codeword is shortened when the data length is not a multiple of *k*: it holds
+/// only the remaining data bytes followed by the parity bytes. Therefore, the
+/// length of the data is given by the length of the encoded data, and encoding
+/// never fails.
+///
+/// [`TryDecrypt`] implementation corrects every codeword and fails if any of
+/// them contains too many errors. Use [`try_decrypt_with_report`] to get to know
+/// how many bytes were corrected, or [`try_decrypt_with_erasures`] to tell the
+/// decoder which bytes are known to be lost.
+///
+/// [`HammingECC`]: crate::algorithms::HammingECC
+/// [`try_decrypt_with_report`]: ReedSolomon::try_decrypt_with_report
+/// [`try_decrypt_with_erasures`]: ReedSolomon::try_decrypt_with_erasures
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReedSolomon {
+    n: usize,
+    k: usize,
+    generator: Vec<u8>,
+}
+
+impl ReedSolomon {
+
+    /// Creates a new RS(*n*, *k*) code.
+    ///
+    /// # Parameters
+    ///
+    /// - `n`: length of a codeword in bytes, at most 255
+    /// - `k`: number of data bytes in a codeword, at least 1 and less than `n`
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] if both parameters are valid
+    /// - [`Err`] with [`Error::InvalidParameters`] naming the invalid parameter
+    ///   otherwise
+    pub fn new(n: usize, k: usize) -> Result<Self, Error> {
+        if !(2..=255).contains(&n) {
+            return Err(Error::invalid_parameter("n", "must be between 2 and 255"))
+        }
+        if !(1..n).contains(&k) {
+            return Err(Error::invalid_parameter("k", format!("must be between 1 and {}", n - 1)))
+        }
+        // Product of (x - 2^i) for all roots, the highest power first:
+        let generator = (0..n - k).fold(vec![1], |generator, power| {
+            let mut product = generator.clone();
+            product.push(0);
+            for (idx, &coefficient) in generator.iter().enumerate() {
+                product[idx + 1] ^= gf256::mul(coefficient, gf256::exp(power));
+            }
+            product
+        });
+        Ok(Self { n, k, generator })
+    }
+
+    /// Returns length of a codeword in bytes.
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    /// Returns number of data bytes in a codeword.
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    /// Returns number of parity bytes in a codeword, e.g. *n* &ndash; *k*.
+    pub fn parity_len(&self) -> usize {
+        self.n - self.k
+    }
+
+    /// Returns maximal number of wrong bytes at unknown positions which can be
+    /// corrected in a single codeword, e.g. (*n* &ndash; *k*) / 2.
+    pub fn max_errors(&self) -> usize {
+        self.parity_len() / 2
+    }
+
+    /// Returns number of bytes which given number of data bytes is encoded into.
+    pub fn encoded_len(&self, data_len: usize) -> usize {
+        data_len + data_len.div_ceil(self.k) * self.parity_len()
+    }
+
+    /// Appends the codeword of given data to `output`.
+    fn encode_codeword(&self, data: &[u8], output: &mut Vec<u8>) {
+        // Remainder of division of the data shifted by the parity length by the
+        // generator polynomial:
+        let mut remainder = vec![0; self.parity_len()];
+        for &byte in data {
+            let feedback = byte ^ remainder[0];
+            remainder.rotate_left(1);
+            *remainder.last_mut().unwrap() = 0;
+            for (remainder, &coefficient) in remainder.iter_mut().zip(&self.generator[1..]) {
+                *remainder ^= gf256::mul(coefficient, feedback);
+            }
+        }
+        output.extend(data);
+        output.extend(remainder);
+    }
+
+    /// Returns syndromes of a codeword, which are all zero for a valid codeword.
+    fn syndromes(&self, codeword: &[u8]) -> Vec<u8> {
+        (0..self.parity_len())
+            .map(|power| evaluate_descending(codeword, gf256::exp(power)))
+            .collect()
+    }
+
+    /// Corrects errors and erasures of a single codeword in place. Positions of
+    /// erasures must be distinct and within the codeword.
+    fn correct(&self, codeword: &mut [u8], erasures: &[usize]) -> CodewordStatus {
+        let syndromes = self.syndromes(codeword);
+        if syndromes.iter().all(|&syndrome| syndrome == 0) {
+            return CodewordStatus::Clean
+        }
+        if erasures.len() > self.parity_len() {
+            return CodewordStatus::Uncorrectable
+        }
+        // Byte at `position` is the coefficient of x^power:
+        let power = |position: usize| codeword.len() - 1 - position;
+
+        // Erasure locator, the product of (1 + X x) for location X = 2^power of
+        // every erasure. Polynomials are stored from the lowest power from now on:
+        let erasure_locator = erasures.iter().fold(vec![1], |locator, &position| {
+            multiply(&locator, &[1, gf256::exp(power(position))])
+        });
+
+        // Berlekamp-Massey algorithm initialised by the erasure locator gives
+        // locator of both errors and erasures:
+        let mut locator = erasure_locator.clone();
+        let mut previous = erasure_locator;
+        let mut degree = erasures.len();
+        for step in erasures.len() + 1..=self.parity_len() {
+            let discrepancy = (0..locator.len().min(step))
+                .fold(0, |sum, idx| sum ^ gf256::mul(locator[idx], syndromes[step - 1 - idx]));
+            previous.insert(0, 0);
+            if discrepancy != 0 {
+                let mut updated = locator.clone();
+                updated.resize(updated.len().max(previous.len()), 0);
+                for (updated, &coefficient) in updated.iter_mut().zip(&previous) {
+                    *updated ^= gf256::mul(discrepancy, coefficient);
+                }
+                if 2 * degree < step + erasures.len() {
+                    let inverse = gf256::inv(discrepancy);
+                    previous = locator.iter()
+                        .map(|&coefficient| gf256::mul(coefficient, inverse))
+                        .collect();
+                    degree = step + erasures.len() - degree;
+                }
+                locator = updated;
+            }
+        }
+        while locator.last() == Some(&0) {
+            locator.pop();
+        }
+        if locator.len() - 1 != degree || 2 * degree > self.parity_len() + erasures.len() {
+            return CodewordStatus::Uncorrectable
+        }
+
+        // Chien search, roots of the locator are inverses of the locations:
+        let positions: Vec<usize> = (0..codeword.len())
+            .filter(|&position| {
+                evaluate_ascending(&locator, gf256::inv(gf256::exp(power(position)))) == 0
+            })
+            .collect();
+        if positions.len() != degree {
+            return CodewordStatus::Uncorrectable
+        }
+
+        // Forney algorithm computes the error values from the evaluator and from
+        // the formal derivative of the locator:
+        let mut evaluator = multiply(&syndromes, &locator);
+        evaluator.truncate(self.parity_len());
+        let derivative: Vec<u8> = locator.iter().enumerate().skip(1)
+            .map(|(idx, &coefficient)| if idx % 2 == 1 { coefficient } else { 0 })
+            .collect();
+        let mut corrected = codeword.to_vec();
+        for &position in &positions {
+            let inverse = gf256::inv(gf256::exp(power(position)));
+            let denominator = evaluate_ascending(&derivative, inverse);
+            if denominator == 0 {
+                return CodewordStatus::Uncorrectable
+            }
+            let value = gf256::div(evaluate_ascending(&evaluator, inverse), denominator);
+            corrected[position] ^= gf256::mul(gf256::exp(power(position)), value);
+        }
+        // Too many errors may lead to a wrong codeword, which is caught here:
+        if self.syndromes(&corrected).iter().any(|&syndrome| syndrome != 0) {
+            return CodewordStatus::Uncorrectable
+        }
+        codeword.copy_from_slice(&corrected);
+        CodewordStatus::Corrected {
+            errors: positions.iter().filter(|position| !erasures.contains(position)).count(),
+            erasures: erasures.len(),
+        }
+    }
+}
+
+impl TryEncrypt for ReedSolomon {
+
+    /// Encoding never fails, the type is given by the trait.
+    type ErrorType = Error;
+
+    /// Encodes data into codewords, each of them holding *k* bytes of data, except
+    /// for the last one, which may be shortened.
+    ///
+    /// # Returns
+    ///
+    /// Always [`Ok`] with encoded data.
+    fn try_encrypt<D, E>(
+        &self,
+        data_to_encrypt: D
+    ) -> Result<E, Self::ErrorType> where
+        D:           IntoIterator<Item = u8>,
+        D::IntoIter: ExactSizeIterator,
+        E:           FromIterator<u8> {
+
+        let data: Vec<u8> = data_to_encrypt.into_iter().collect();
+        let mut output = Vec::with_capacity(self.encoded_len(data.len()));
+        for chunk in data.chunks(self.k) {
+            self.encode_codeword(chunk, &mut output);
+        }
+        Ok(output.into_iter().collect())
+    }
+}
+
+/// Outcome of decoding a single Reed&ndash;Solomon codeword.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CodewordStatus {
+
+    /// The codeword contained no error.
+    Clean,
+
+    /// The codeword contained errors, which were corrected.
+    Corrected {
+        /// Number of corrected bytes at positions which were not known.
+        errors: usize,
+        /// Number of restored bytes at positions given as erasures.
+        erasures: usize,
+    },
+
+    /// The codeword contained more errors than can be corrected.
+    Uncorrectable,
+}
+
+/// Diagnostic report of decoding data encoded by [`ReedSolomon`]. It contains
+/// [`CodewordStatus`] of each codeword in order of the codewords and totals over
+/// all codewords. The report is returned by [`try_decrypt_with_report`] and
+/// [`try_decrypt_with_erasures`] methods.
+///
+/// [`try_decrypt_with_report`]: ReedSolomon::try_decrypt_with_report
+/// [`try_decrypt_with_erasures`]: ReedSolomon::try_decrypt_with_erasures
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReedSolomonReport {
+    codewords: Vec<CodewordStatus>,
+}
+
+impl ReedSolomonReport {
+
+    /// Returns status of each codeword in the order of the codewords.
+    pub fn codewords(&self) -> &[CodewordStatus] {
+        &self.codewords
+    }
+
+    /// Returns number of codewords which contained no error.
+    pub fn clean_count(&self) -> usize {
+        self.count(|status| status == CodewordStatus::Clean)
+    }
+
+    /// Returns number of codewords which contained errors that were corrected.
+    pub fn corrected_count(&self) -> usize {
+        self.count(|status| matches!(status, CodewordStatus::Corrected { .. }))
+    }
+
+    /// Returns number of codewords which contained too many errors.
+    pub fn uncorrectable_count(&self) -> usize {
+        self.count(|status| status == CodewordStatus::Uncorrectable)
+    }
+
+    /// Returns total number of bytes corrected or restored in all codewords.
+    pub fn corrected_symbols(&self) -> usize {
+        self.codewords.iter()
+            .map(|status| match status {
+                CodewordStatus::Corrected { errors, erasures } => errors + erasures,
+                _ => 0,
+            })
+            .sum()
+    }
+
+    fn count<P>(&self, predicate: P) -> usize
+    where P: Fn(CodewordStatus) -> bool {
+        self.codewords.iter().filter(|&&status| predicate(status)).count()
+    }
+}
+
+impl ReedSolomon {
+
+    /// Decodes data in the same way as [`try_decrypt`] does, but also returns
+    /// [`ReedSolomonReport`] with status of each codeword. All codewords are
+    /// examined even if some of them are uncorrectable, so the report is
+    /// complete. If the data cannot be split into codewords, the report contains
+    /// no codewords.
+    ///
+    /// # Returns
+    ///
+    /// A tuple of:
+    ///
+    /// - result of decoding, the same as [`try_decrypt`] would return
+    /// - report of all codewords
+    ///
+    /// [`try_decrypt`]: ReedSolomon::try_decrypt
+    pub fn try_decrypt_with_report<E, D>(
+        &self,
+        encrypted_data: E
+    ) -> (Result<D, Error>, ReedSolomonReport) where
+        E:           IntoIterator<Item = u8>,
+        E::IntoIter: ExactSizeIterator,
+        D:           FromIterator<u8> {
+
+        self.try_decrypt_with_erasures(encrypted_data, &[])
+    }
+
+    /// Decodes data like [`try_decrypt_with_report`] does, with some bytes known
+    /// to be wrong, e.g. because the receiver lost them. Since their positions do
+    /// not have to be found, a codeword can restore up to *n* &ndash; *k* erased
+    /// bytes, twice as many as wrong bytes at unknown positions. The value of an
+    /// erased byte does not matter.
+    ///
+    /// # Parameters
+    ///
+    /// - `encrypted_data`: encoded data
+    /// - `erasures`: positions of erased bytes in the encoded data, counted from
+    ///   zero; their order does not matter
+    ///
+    /// # Returns
+    ///
+    /// A tuple of:
+    ///
+    /// - [`Ok`] with decoded data, or [`Err`] with [`Error::InvalidParameters`] if
+    ///   an erasure is out of the data, or any error [`try_decrypt`] would return
+    /// - report of all codewords
+    ///
+    /// [`try_decrypt`]: ReedSolomon::try_decrypt
+    /// [`try_decrypt_with_report`]: ReedSolomon::try_decrypt_with_report
+    pub fn try_decrypt_with_erasures<E, D>(
+        &self,
+        encrypted_data: E,
+        erasures: &[usize],
+    ) -> (Result<D, Error>, ReedSolomonReport) where
+        E:           IntoIterator<Item = u8>,
+        E::IntoIter: ExactSizeIterator,
+        D:           FromIterator<u8> {
+
+        let mut bytes: Vec<u8> = encrypted_data.into_iter().collect();
+        let mut report = ReedSolomonReport { codewords: Vec::new() };
+        // The last codeword must hold at least one data byte:
+        let last_len = bytes.len() % self.n;
+        if last_len != 0 && last_len <= self.parity_len() {
+            let error = Error::malformed(format!(
+                "{} bytes do not form {}-byte codewords, the last one holding {} parity bytes",
+                bytes.len(),
+                self.n,
+                self.parity_len()
+            ));
+            return (Err(error), report)
+        }
+        // Positions of erasures within each codeword:
+        let mut codeword_erasures = vec![Vec::new(); bytes.len().div_ceil(self.n)];
+        for &erasure in erasures {
+            if erasure >= bytes.len() {
+                let error = Error::invalid_parameter(
+                    "erasures",
+                    format!("position {} is out of {} bytes", erasure, bytes.len())
+                );
+                return (Err(error), report)
+            }
+            let positions: &mut Vec<usize> = &mut codeword_erasures[erasure / self.n];
+            if !positions.contains(&(erasure % self.n)) {
+                positions.push(erasure % self.n);
+            }
+        }
+
+        let mut data = Vec::with_capacity(bytes.len());
+        for (codeword, erasures) in bytes.chunks_mut(self.n).zip(&codeword_erasures) {
+            report.codewords.push(self.correct(codeword, erasures));
+            data.extend(&codeword[..codeword.len() - self.parity_len()]);
+        }
+        if let Some(codeword) = report.codewords.iter()
+            .position(|&status| status == CodewordStatus::Uncorrectable) {
+            return (Err(Error::Uncorrectable { block: codeword }), report)
+        }
+        (Ok(data.into_iter().collect()), report)
+    }
+}
+
+impl TryDecrypt for ReedSolomon {
+
+    /// Error type to be returned when data cannot be decoded, e.g. when
+    /// a codeword contains too many errors or the data cannot be split into
+    /// codewords.
+    type ErrorType = Error;
+
+    /// Corrects every codeword and returns the data bytes. Use
+    /// [`try_decrypt_with_report`] to get to know which codewords were corrected.
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with decoded data
+    /// - [`Err`] with [`Error::Uncorrectable`] if any codeword contains too many
+    ///   errors, or [`Error::Malformed`] if the last codeword is not longer than
+    ///   the parity
+    ///
+    /// [`try_decrypt_with_report`]: ReedSolomon::try_decrypt_with_report
+    fn try_decrypt<E, D>(
+        &self,
+        encrypted_data: E
+    ) -> Result<D, Self::ErrorType> where
+        E:           IntoIterator<Item = u8>,
+        E::IntoIter: ExactSizeIterator,
+        D:           FromIterator<u8> {
+
+        self.try_decrypt_with_report(encrypted_data).0
+    }
+}
+
+/// Evaluates a polynomial stored from the highest power at given point.
+fn evaluate_descending(polynomial: &[u8], point: u8) -> u8 {
+    polynomial.iter().fold(0, |value, &coefficient| gf256::mul(value, point) ^ coefficient)
+}
+
+/// Evaluates a polynomial stored from the lowest power at given point.
+fn evaluate_ascending(polynomial: &[u8], point: u8) -> u8 {
+    polynomial.iter().rev().fold(0, |value, &coefficient| gf256::mul(value, point) ^ coefficient)
+}
+
+/// Multiplies two polynomials stored in the same order of powers.
+fn multiply(a: &[u8], b: &[u8]) -> Vec<u8> {
+    let mut product = vec![0; a.len() + b.len() - 1];
+    for (i, &a) in a.iter().enumerate() {
+        for (j, &b) in b.iter().enumerate() {
+            product[i + j] ^= gf256::mul(a, b);
+        }
+    }
+    product
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use rand::seq::index::sample;
+
+    use super::*;
+
+    /// Seed of the random data and errors, so that a failure can be reproduced.
+    const SEED: u64 = 0x5eed;
+
+    #[test]
+    fn encode() {
+        let rs = ReedSolomon::new(255, 245).unwrap();
+        let encoded: Vec<u8> = rs.try_encrypt(b"hello world".to_vec()).unwrap();
+        assert_eq!(&encoded[..11], b"hello world");
+        assert_eq!(&encoded[11..], [0xed, 0x25, 0x54, 0xc4, 0xfd, 0xfd, 0x89, 0xf3, 0xa8, 0xaa]);
+
+        let rs = ReedSolomon::new(6, 2).unwrap();
+        assert_eq!(rs.generator, [1, 15, 54, 120, 64]);
+        let encoded: Vec<u8> = rs.try_encrypt(vec![1, 2, 3, 4, 5]).unwrap();
+        assert_eq!(encoded.len(), rs.encoded_len(5));
+        assert_eq!(encoded.len(), 17);
+        let empty: Vec<u8> = rs.try_encrypt(vec![]).unwrap();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn random_errors() {
+        let mut rng = StdRng::seed_from_u64(SEED);
+        for (n, k) in [(255, 223), (255, 245), (15, 11), (20, 10), (3, 1)] {
+            let rs = ReedSolomon::new(n, k).unwrap();
+            for len in [0, 1, k - 1, k, k + 1, 3 * k + k / 2 + 1] {
+                let data: Vec<u8> = (0..len).map(|_| rng.gen()).collect();
+                let mut encoded: Vec<u8> = rs.try_encrypt(data.clone()).unwrap();
+                // Corrupt up to the limit in every codeword:
+                let mut corrupted = 0;
+                for codeword in encoded.chunks_mut(n) {
+                    let errors = rng.gen_range(0..=rs.max_errors());
+                    for position in sample(&mut rng, codeword.len(), errors) {
+                        codeword[position] ^= rng.gen_range(1..=255);
+                    }
+                    corrupted += errors;
+                }
+                let (decoded, report) = rs.try_decrypt_with_report(encoded);
+                let decoded: Vec<u8> = decoded.unwrap();
+                assert_eq!(decoded, data, "seed {}", SEED);
+                assert_eq!(report.corrected_symbols(), corrupted, "seed {}", SEED);
+                assert_eq!(report.codewords().len(), len.div_ceil(k), "seed {}", SEED);
+                assert_eq!(report.uncorrectable_count(), 0, "seed {}", SEED);
+            }
+        }
+    }
+
+    #[test]
+    fn random_erasures() {
+        let mut rng = StdRng::seed_from_u64(SEED);
+        let rs = ReedSolomon::new(255, 223).unwrap();
+        let data: Vec<u8> = (0..1000).map(|_| rng.gen()).collect();
+        let encoded: Vec<u8> = rs.try_encrypt(data.clone()).unwrap();
+        for _ in 0..20 {
+            let mut corrupted = encoded.clone();
+            let mut erasures = Vec::new();
+            let mut errors_total = 0;
+            for (idx, codeword) in corrupted.chunks_mut(255).enumerate() {
+                // Any mix of 2 * errors + erasures <= 32 can be corrected:
+                let erased = rng.gen_range(0..=rs.parity_len());
+                let errors = rng.gen_range(0..=(rs.parity_len() - erased) / 2);
+                for (nth, position) in sample(&mut rng, codeword.len(), erased + errors)
+                    .into_iter()
+                    .enumerate() {
+                    codeword[position] ^= rng.gen_range(1..=255);
+                    if nth < erased {
+                        erasures.push(idx * 255 + position);
+                    }
+                }
+                errors_total += errors;
+            }
+            let (decoded, report) = rs.try_decrypt_with_erasures(corrupted, &erasures);
+            let decoded: Vec<u8> = decoded.unwrap();
+            assert_eq!(decoded, data, "seed {}", SEED);
+            let errors: usize = report.codewords().iter()
+                .map(|status| match status {
+                    CodewordStatus::Corrected { errors, .. } => *errors,
+                    _ => 0,
+                })
+                .sum();
+            assert_eq!(errors, errors_total, "seed {}", SEED);
+        }
+    }
+
+    #[test]
+    fn too_many_errors() {
+        let mut rng = StdRng::seed_from_u64(SEED);
+        let rs = ReedSolomon::new(255, 223).unwrap();
+        let data: Vec<u8> = (0..500).map(|_| rng.gen()).collect();
+        let mut encoded: Vec<u8> = rs.try_encrypt(data.clone()).unwrap();
+        let wrong: Vec<usize> = sample(&mut rng, 255, rs.max_errors() + 1).into_iter()
+            .map(|position| 255 + position)
+            .collect();
+        for &position in &wrong {
+            encoded[position] ^= rng.gen_range(1..=255);
+        }
+        let (decoded, report) = rs.try_decrypt_with_report(encoded.clone());
+        let decoded: Result<Vec<u8>, _> = decoded;
+        assert!(matches!(decoded, Err(Error::Uncorrectable { block: 1 })), "seed {}", SEED);
+        assert_eq!(report.codewords()[0], CodewordStatus::Clean, "seed {}", SEED);
+        assert_eq!(report.codewords()[1], CodewordStatus::Uncorrectable, "seed {}", SEED);
+        assert_eq!(report.clean_count(), 2, "seed {}", SEED);
+
+        // The same bytes given as erasures can be restored:
+        let (decoded, report) = rs.try_decrypt_with_erasures(encoded, &wrong);
+        let decoded: Vec<u8> = decoded.unwrap();
+        assert_eq!(decoded, data, "seed {}", SEED);
+        let expected = CodewordStatus::Corrected { errors: 0, erasures: 17 };
+        assert_eq!(report.codewords()[1], expected, "seed {}", SEED);
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(matches!(
+            ReedSolomon::new(256, 200),
+            Err(Error::InvalidParameters { parameter, .. }) if parameter == "n"
+        ));
+        assert!(matches!(
+            ReedSolomon::new(10, 10),
+            Err(Error::InvalidParameters { parameter, .. }) if parameter == "k"
+        ));
+        assert!(ReedSolomon::new(10, 0).is_err());
+
+        let rs = ReedSolomon::new(10, 6).unwrap();
+        for len in [1, 4, 14] {
+            let decoded: Result<Vec<u8>, _> = rs.try_decrypt(vec![0; len]);
+            assert!(matches!(decoded, Err(Error::Malformed { .. })));
+        }
+        let (decoded, _) = rs.try_decrypt_with_erasures(vec![0; 10], &[10]);
+        let decoded: Result<Vec<u8>, _> = decoded;
+        assert!(matches!(decoded, Err(Error::InvalidParameters { .. })));
+    }
+}
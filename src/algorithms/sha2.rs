@@ -1,63 +1,242 @@
+use std::io;
+use std::io::Read;
 use std::iter::ExactSizeIterator;
 use std::iter::FromIterator;
 use std::iter::IntoIterator;
 use std::marker::PhantomData;
 
+use crate::algorithms::hash::hash_of;
+use crate::algorithms::HashFunction;
+use crate::stream::Encryptor;
+use crate::stream::StreamEncrypt;
+use crate::Digest;
 use crate::Encrypt;
 
+/// A hash function of the SHA-2 family, which implements [`Encrypt`] trait by
+/// hashing the whole data at once. For hashing data fed by chunks, get a
+/// stateful [`Sha2Stream`] by calling [`hasher()`].
+///
+/// Use type aliases such as [`Sha256`] rather than this struct directly.
+///
+/// ```
+/// use mdcrypt::algorithms::Sha256;
+/// use mdcrypt::Encrypt;
+///
+/// let mut hasher = Sha256::new().hasher();
+/// hasher.update(b"a");
+/// hasher.update(b"bc");
+/// let digest: Vec<u8> = Sha256::new().encrypt(b"abc".to_vec());
+/// assert_eq!(hasher.finalize().as_bytes(), &digest[..]);
+/// ```
+///
+/// [`hasher()`]: Sha2::hasher
+/// [`Encrypt`]: crate::Encrypt
 pub struct Sha2<T>(PhantomData<T>)
-where T: Default + sha2::Digest;
+where T: Default + Clone + sha2::Digest;
+
+impl<T> Sha2<T>
+where T: Default + Clone + sha2::Digest,
+{
+    /// Creates the hash function.
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+
+    /// Returns a new stateful hasher with no data hashed yet.
+    pub fn hasher(&self) -> Sha2Stream<T> {
+        Sha2Stream::new()
+    }
+
+    /// Hashes everything that can be read from given reader, so that the data
+    /// do not need to be buffered in the memory.
+    ///
+    /// # Parameters
+    ///
+    /// - `reader`: reader to read data to hash from
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with the digest of all read data
+    /// - [`Err`] if reading has failed
+    pub fn hash_reader<R: Read>(reader: R) -> io::Result<Digest> {
+        Sha2Stream::<T>::new().hash_reader(reader)
+    }
+}
+
+impl<T> Clone for Sha2<T>
+where T: Default + Clone + sha2::Digest,
+{
+    fn clone(&self) -> Self {
+        Self::new()
+    }
+}
 
 impl<T> Default for Sha2<T>
-where T: Default + sha2::Digest,
+where T: Default + Clone + sha2::Digest,
 {
     fn default() -> Self {
-        Self (PhantomData)
+        Self::new()
     }
 }
 
 impl<T> Encrypt for Sha2<T>
-where T: Default + sha2::Digest,
+where T: Default + Clone + sha2::Digest,
 {
+    /// Hashes given data and returns the digest.
     fn encrypt<D, E>(&self, data_to_encrypt: D) -> E
     where
         D: IntoIterator<Item = u8>,
         D::IntoIter: ExactSizeIterator,
         E: FromIterator<u8>,
     {
-        let vec: Vec<u8> = data_to_encrypt.into_iter().collect();
-        let mut encr = T::default();
-        encr.update(vec);
-        return encr.finalize()[..].iter().map(|&b| b).collect();
+        hash_of(self.hasher(), data_to_encrypt).into_iter().collect()
+    }
+}
+
+impl<T> StreamEncrypt for Sha2<T>
+where T: Default + Clone + sha2::Digest,
+{
+    type Encryptor<'a> = Sha2Stream<T> where Self: 'a;
+
+    fn encryptor(&self) -> Sha2Stream<T> {
+        self.hasher()
+    }
+}
+
+/// State of SHA-2 hashing fed by chunks of data. Feed it with data by calling
+/// [`update()`] any number of times and get the digest by calling
+/// [`finalize()`]. It also implements [`HashFunction`] and [`Encryptor`], which
+/// make it usable by [`EncryptWriter`].
+///
+/// [`update()`]: Sha2Stream::update
+/// [`finalize()`]: Sha2Stream::finalize
+/// [`EncryptWriter`]: crate::EncryptWriter
+#[derive(Clone)]
+pub struct Sha2Stream<T>(T)
+where T: Default + Clone + sha2::Digest;
+
+impl<T> Sha2Stream<T>
+where T: Default + Clone + sha2::Digest,
+{
+    /// Creates a new hasher with no data hashed yet.
+    pub fn new() -> Self {
+        Self(T::default())
+    }
+
+    /// Feeds the hasher with next chunk of data.
+    ///
+    /// # Parameters
+    ///
+    /// - `data`: data to hash
+    pub fn update(&mut self, data: &[u8]) {
+        self.0.update(data);
+    }
+
+    /// Finishes hashing and returns the digest of all data fed into the hasher.
+    pub fn finalize(self) -> Digest {
+        Digest::new(self.0.finalize().to_vec())
+    }
+}
+
+impl<T> Default for Sha2Stream<T>
+where T: Default + Clone + sha2::Digest,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> HashFunction for Sha2Stream<T>
+where T: Default + Clone + sha2::Digest,
+{
+    fn output_len(&self) -> usize {
+        <T as sha2::Digest>::output_size()
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        Sha2Stream::update(self, data);
+    }
+
+    fn finalize(self) -> Digest {
+        Sha2Stream::finalize(self)
+    }
+}
+
+impl<T> Encryptor for Sha2Stream<T>
+where T: Default + Clone + sha2::Digest,
+{
+    fn update(&mut self, input: &[u8], _output: &mut Vec<u8>) -> io::Result<()> {
+        Sha2Stream::update(self, input);
+        Ok(())
+    }
+
+    fn finish(self, output: &mut Vec<u8>) -> io::Result<()> {
+        output.extend(self.finalize());
+        Ok(())
     }
 }
 
 /// SHA-224 hasher implementing [`Encrypt`] trait from this crate.
 /// 
-/// [`Encrypt`]: crate::crypt::Encrypt
+/// [`Encrypt`]: crate::Encrypt
 pub type Sha224 = Sha2<sha2::Sha224>;
 
 /// SHA-256 hasher implementing [`Encrypt`] trait from this crate.
 /// 
-/// [`Encrypt`]: crate::crypt::Encrypt
+/// [`Encrypt`]: crate::Encrypt
 pub type Sha256 = Sha2<sha2::Sha256>;
 
 /// SHA-384 hasher implementing [`Encrypt`] trait from this crate.
 /// 
-/// [`Encrypt`]: crate::crypt::Encrypt
+/// [`Encrypt`]: crate::Encrypt
 pub type Sha384 = Sha2<sha2::Sha384>;
 
 /// SHA-512 hasher implementing [`Encrypt`] trait from this crate.
 /// 
-/// [`Encrypt`]: crate::crypt::Encrypt
+/// [`Encrypt`]: crate::Encrypt
 pub type Sha512 = Sha2<sha2::Sha512>;
 
 /// SHA-512/224 hasher implementing [`Encrypt`] trait from this crate.
 /// 
-/// [`Encrypt`]: crate::crypt::Encrypt
+/// [`Encrypt`]: crate::Encrypt
 pub type Sha512_224 = Sha2<sha2::Sha512_224>;
 
 /// SHA-512/256 hasher implementing [`Encrypt`] trait from this crate.
 /// 
-/// [`Encrypt`]: crate::crypt::Encrypt
-pub type Sha512_256 = Sha2<sha2::Sha512_256>;
\ No newline at end of file
+/// [`Encrypt`]: crate::Encrypt
+pub type Sha512_256 = Sha2<sha2::Sha512_256>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::to_hex;
+
+    #[test]
+    fn known_answers() {
+        let test_cases: Vec<(Vec<u8>, &str)> = vec![
+            (Sha224::default().encrypt(b"abc".to_vec()),
+                "23097d223405d8228642a477bda255b32aadbce4bda0b3f7e36c9da7"),
+            (Sha256::default().encrypt(b"abc".to_vec()),
+                "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"),
+            (Sha256::default().encrypt(vec![]),
+                "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"),
+        ];
+
+        for (actual, expected) in test_cases {
+            assert_eq!(to_hex(&actual), expected)
+        }
+    }
+
+    #[test]
+    fn incremental() {
+        let data: Vec<u8> = (0..=255).cycle().take(20000).collect();
+        let expected: Vec<u8> = Sha512::default().encrypt(data.clone());
+
+        let mut hasher = Sha512::new().hasher();
+        for chunk in data.chunks(777) {
+            hasher.update(chunk);
+        }
+        assert_eq!(hasher.finalize().as_bytes(), &expected[..]);
+        assert_eq!(Sha512::hash_reader(&data[..]).unwrap().as_bytes(), &expected[..]);
+    }
+}
@@ -0,0 +1,427 @@
+use std::io;
+use std::io::Read;
+use std::iter::ExactSizeIterator;
+use std::iter::FromIterator;
+use std::iter::IntoIterator;
+use std::marker::PhantomData;
+
+use sha3::digest::ExtendableOutput;
+use sha3::digest::Update;
+
+use crate::algorithms::hash::hash_of;
+use crate::algorithms::hash::MAX_OUTPUT_LEN;
+use crate::algorithms::HashFunction;
+use crate::error::Error;
+use crate::stream::Encryptor;
+use crate::stream::StreamEncrypt;
+use crate::Digest;
+use crate::Encrypt;
+
+/// A hash function of the SHA-3 family as specified by FIPS 202. Like [`Sha2`],
+/// it implements [`Encrypt`] trait by hashing the whole data at once. For
+/// hashing data fed by chunks, get a stateful [`Sha3Stream`] by calling
+/// [`hasher()`].
+///
+/// Use type aliases such as [`Sha3_256`] rather than this struct directly.
+///
+/// [`Sha2`]: crate::algorithms::Sha2
+/// [`hasher()`]: Sha3::hasher
+/// [`Encrypt`]: crate::Encrypt
+pub struct Sha3<T>(PhantomData<T>)
+where T: Default + Clone + sha3::Digest;
+
+impl<T> Sha3<T>
+where T: Default + Clone + sha3::Digest,
+{
+    /// Creates the hash function.
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+
+    /// Returns a new stateful hasher with no data hashed yet.
+    pub fn hasher(&self) -> Sha3Stream<T> {
+        Sha3Stream::new()
+    }
+
+    /// Hashes everything that can be read from given reader, so that the data
+    /// do not need to be buffered in the memory.
+    ///
+    /// # Parameters
+    ///
+    /// - `reader`: reader to read data to hash from
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with the digest of all read data
+    /// - [`Err`] if reading has failed
+    pub fn hash_reader<R: Read>(reader: R) -> io::Result<Digest> {
+        Sha3Stream::<T>::new().hash_reader(reader)
+    }
+}
+
+impl<T> Clone for Sha3<T>
+where T: Default + Clone + sha3::Digest,
+{
+    fn clone(&self) -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Default for Sha3<T>
+where T: Default + Clone + sha3::Digest,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Encrypt for Sha3<T>
+where T: Default + Clone + sha3::Digest,
+{
+    /// Hashes given data and returns the digest.
+    fn encrypt<D, E>(&self, data_to_encrypt: D) -> E
+    where
+        D:           IntoIterator<Item = u8>,
+        D::IntoIter: ExactSizeIterator,
+        E:           FromIterator<u8>
+    {
+        hash_of(self.hasher(), data_to_encrypt).into_iter().collect()
+    }
+}
+
+impl<T> StreamEncrypt for Sha3<T>
+where T: Default + Clone + sha3::Digest,
+{
+    type Encryptor<'a> = Sha3Stream<T> where Self: 'a;
+
+    fn encryptor(&self) -> Sha3Stream<T> {
+        self.hasher()
+    }
+}
+
+/// State of SHA-3 hashing fed by chunks of data, used in the same way as
+/// [`Sha2Stream`].
+///
+/// [`Sha2Stream`]: crate::algorithms::Sha2Stream
+#[derive(Clone)]
+pub struct Sha3Stream<T>(T)
+where T: Default + Clone + sha3::Digest;
+
+impl<T> Sha3Stream<T>
+where T: Default + Clone + sha3::Digest,
+{
+    /// Creates a new hasher with no data hashed yet.
+    pub fn new() -> Self {
+        Self(T::default())
+    }
+
+    /// Feeds the hasher with next chunk of data.
+    ///
+    /// # Parameters
+    ///
+    /// - `data`: data to hash
+    pub fn update(&mut self, data: &[u8]) {
+        sha3::Digest::update(&mut self.0, data);
+    }
+
+    /// Finishes hashing and returns the digest of all data fed into the hasher.
+    pub fn finalize(self) -> Digest {
+        Digest::new(self.0.finalize().to_vec())
+    }
+}
+
+impl<T> Default for Sha3Stream<T>
+where T: Default + Clone + sha3::Digest,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> HashFunction for Sha3Stream<T>
+where T: Default + Clone + sha3::Digest,
+{
+    fn output_len(&self) -> usize {
+        <T as sha3::Digest>::output_size()
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        Sha3Stream::update(self, data);
+    }
+
+    fn finalize(self) -> Digest {
+        Sha3Stream::finalize(self)
+    }
+}
+
+impl<T> Encryptor for Sha3Stream<T>
+where T: Default + Clone + sha3::Digest,
+{
+    fn update(&mut self, input: &[u8], _output: &mut Vec<u8>) -> io::Result<()> {
+        Sha3Stream::update(self, input);
+        Ok(())
+    }
+
+    fn finish(self, output: &mut Vec<u8>) -> io::Result<()> {
+        output.extend(self.finalize());
+        Ok(())
+    }
+}
+
+/// SHA3-224 hasher implementing [`Encrypt`] trait from this crate.
+///
+/// [`Encrypt`]: crate::Encrypt
+pub type Sha3_224 = Sha3<sha3::Sha3_224>;
+
+/// SHA3-256 hasher implementing [`Encrypt`] trait from this crate.
+///
+/// [`Encrypt`]: crate::Encrypt
+pub type Sha3_256 = Sha3<sha3::Sha3_256>;
+
+/// SHA3-384 hasher implementing [`Encrypt`] trait from this crate.
+///
+/// [`Encrypt`]: crate::Encrypt
+pub type Sha3_384 = Sha3<sha3::Sha3_384>;
+
+/// SHA3-512 hasher implementing [`Encrypt`] trait from this crate.
+///
+/// [`Encrypt`]: crate::Encrypt
+pub type Sha3_512 = Sha3<sha3::Sha3_512>;
+
+/// An extendable-output function (XOF) of the SHA-3 family as specified by
+/// FIPS 202, whose digest can have any length up to 1 MiB. It is used in the
+/// same way as [`Sha3`], its stateful hasher is [`ShakeStream`].
+///
+/// Use type aliases [`Shake128`] and [`Shake256`] rather than this struct
+/// directly. Their default output lengths are 32 and 64 bytes, respectively,
+/// which give their full security strength.
+///
+/// ```
+/// use mdcrypt::algorithms::Shake128;
+/// use mdcrypt::Encrypt;
+///
+/// let digest: Vec<u8> = Shake128::new(4).unwrap().encrypt(vec![]);
+/// assert_eq!(digest, [0x7f, 0x9c, 0x2b, 0xa4]);
+/// ```
+pub struct Shake<T>
+where T: Default + Clone + Update + ExtendableOutput,
+{
+    output_len: usize,
+    marker: PhantomData<T>,
+}
+
+impl<T> Shake<T>
+where T: Default + Clone + Update + ExtendableOutput,
+{
+    /// Creates the hash function.
+    ///
+    /// # Parameters
+    ///
+    /// - `output_len`: length of the digest in bytes
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with the hash function
+    /// - [`Err`] with [`Error::InvalidParameters`] if `output_len` is zero or
+    ///   greater than 1 MiB
+    pub fn new(output_len: usize) -> Result<Self, Error> {
+        if !(1..=MAX_OUTPUT_LEN).contains(&output_len) {
+            return Err(Error::invalid_parameter(
+                "output_len",
+                format!("must be from 1 to {}", MAX_OUTPUT_LEN)
+            ))
+        }
+        Ok(Self { output_len, marker: PhantomData })
+    }
+
+    /// Returns a new stateful hasher with no data hashed yet.
+    pub fn hasher(&self) -> ShakeStream<T> {
+        ShakeStream { state: T::default(), output_len: self.output_len }
+    }
+
+    /// Hashes everything that can be read from given reader, so that the data
+    /// do not need to be buffered in the memory.
+    ///
+    /// # Parameters
+    ///
+    /// - `reader`: reader to read data to hash from
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with the digest of all read data
+    /// - [`Err`] if reading has failed
+    pub fn hash_reader<R: Read>(&self, reader: R) -> io::Result<Digest> {
+        self.hasher().hash_reader(reader)
+    }
+}
+
+impl<T> Clone for Shake<T>
+where T: Default + Clone + Update + ExtendableOutput,
+{
+    fn clone(&self) -> Self {
+        Self { output_len: self.output_len, marker: PhantomData }
+    }
+}
+
+impl Default for Shake<sha3::Shake128> {
+    fn default() -> Self {
+        Self { output_len: 32, marker: PhantomData }
+    }
+}
+
+impl Default for Shake<sha3::Shake256> {
+    fn default() -> Self {
+        Self { output_len: 64, marker: PhantomData }
+    }
+}
+
+impl<T> Encrypt for Shake<T>
+where T: Default + Clone + Update + ExtendableOutput,
+{
+    /// Hashes given data and returns the digest.
+    fn encrypt<D, E>(&self, data_to_encrypt: D) -> E
+    where
+        D:           IntoIterator<Item = u8>,
+        D::IntoIter: ExactSizeIterator,
+        E:           FromIterator<u8>
+    {
+        hash_of(self.hasher(), data_to_encrypt).into_iter().collect()
+    }
+}
+
+impl<T> StreamEncrypt for Shake<T>
+where T: Default + Clone + Update + ExtendableOutput,
+{
+    type Encryptor<'a> = ShakeStream<T> where Self: 'a;
+
+    fn encryptor(&self) -> ShakeStream<T> {
+        self.hasher()
+    }
+}
+
+/// State of [`Shake`] hashing fed by chunks of data, used in the same way as
+/// [`Sha3Stream`].
+#[derive(Clone)]
+pub struct ShakeStream<T>
+where T: Default + Clone + Update + ExtendableOutput,
+{
+    state: T,
+    output_len: usize,
+}
+
+impl<T> ShakeStream<T>
+where T: Default + Clone + Update + ExtendableOutput,
+{
+    /// Feeds the hasher with next chunk of data.
+    ///
+    /// # Parameters
+    ///
+    /// - `data`: data to hash
+    pub fn update(&mut self, data: &[u8]) {
+        self.state.update(data);
+    }
+
+    /// Finishes hashing and returns the digest of all data fed into the hasher.
+    pub fn finalize(self) -> Digest {
+        let mut digest = vec![0; self.output_len];
+        self.state.finalize_xof_into(&mut digest);
+        Digest::new(digest)
+    }
+}
+
+impl<T> HashFunction for ShakeStream<T>
+where T: Default + Clone + Update + ExtendableOutput,
+{
+    fn output_len(&self) -> usize {
+        self.output_len
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        ShakeStream::update(self, data);
+    }
+
+    fn finalize(self) -> Digest {
+        ShakeStream::finalize(self)
+    }
+}
+
+impl<T> Encryptor for ShakeStream<T>
+where T: Default + Clone + Update + ExtendableOutput,
+{
+    fn update(&mut self, input: &[u8], _output: &mut Vec<u8>) -> io::Result<()> {
+        ShakeStream::update(self, input);
+        Ok(())
+    }
+
+    fn finish(self, output: &mut Vec<u8>) -> io::Result<()> {
+        output.extend(self.finalize());
+        Ok(())
+    }
+}
+
+/// SHAKE128 extendable-output function implementing [`Encrypt`] trait from this
+/// crate.
+///
+/// [`Encrypt`]: crate::Encrypt
+pub type Shake128 = Shake<sha3::Shake128>;
+
+/// SHAKE256 extendable-output function implementing [`Encrypt`] trait from this
+/// crate.
+///
+/// [`Encrypt`]: crate::Encrypt
+pub type Shake256 = Shake<sha3::Shake256>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::to_hex;
+
+    #[test]
+    fn known_answers() {
+        let test_cases: Vec<(Vec<u8>, &str)> = vec![
+            (Sha3_224::default().encrypt(b"abc".to_vec()),
+                "e642824c3f8cf24ad09234ee7d3c766fc9a3a5168d0c94ad73b46fdf"),
+            (Sha3_256::default().encrypt(b"abc".to_vec()),
+                "3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532"),
+            (Sha3_256::default().encrypt(vec![]),
+                "a7ffc6f8bf1ed76651c14756a061d662f580ff4de43b49fa82d80a4b80f8434a"),
+            (Shake128::default().encrypt(vec![]),
+                "7f9c2ba4e88f827d616045507605853ed73b8093f6efbc88eb1a6eacfa66ef26"),
+            (Shake256::new(16).unwrap().encrypt(vec![]),
+                "46b9dd2b0ba88d13233b3feb743eeb24"),
+        ];
+
+        for (actual, expected) in test_cases {
+            assert_eq!(to_hex(&actual), expected)
+        }
+    }
+
+    #[test]
+    fn incremental() {
+        let data: Vec<u8> = (0..=255).cycle().take(20000).collect();
+        let expected: Vec<u8> = Sha3_512::default().encrypt(data.clone());
+        let mut hasher = Sha3_512::new().hasher();
+        for chunk in data.chunks(777) {
+            hasher.update(chunk);
+        }
+        assert_eq!(hasher.output_len(), 64);
+        assert_eq!(hasher.finalize().as_bytes(), &expected[..]);
+        assert_eq!(Sha3_512::hash_reader(&data[..]).unwrap().as_bytes(), &expected[..]);
+
+        // Longer output of an XOF extends the shorter one:
+        let short: Vec<u8> = Shake256::new(100).unwrap().encrypt(data.clone());
+        let mut hasher = Shake256::new(300).unwrap().hasher();
+        for chunk in data.chunks(777) {
+            hasher.update(chunk);
+        }
+        assert_eq!(hasher.finalize().as_bytes()[..100], short[..]);
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(matches!(Shake128::new(0), Err(Error::InvalidParameters { .. })));
+        assert!(Shake128::new(MAX_OUTPUT_LEN).is_ok());
+        assert!(Shake128::new(MAX_OUTPUT_LEN + 1).is_err());
+        assert!(Shake256::new(usize::MAX).is_err());
+    }
+}
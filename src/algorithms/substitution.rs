@@ -0,0 +1,339 @@
+use std::iter::FromIterator;
+use std::iter::IntoIterator;
+
+use crate::algorithms::Alphabet;
+use crate::algorithms::alphabet::key_text;
+use crate::decrypt::Decrypt;
+use crate::encrypt::Encrypt;
+use crate::error::Error;
+use crate::key::Key;
+
+/// The Caesar cipher, which shifts every symbol of an [`Alphabet`] by a fixed
+/// number of positions; ROT13 is the Caesar cipher with shift 13.
+///
+/// Like [`ClassicalVigenere`], the cipher treats data as UTF-8 text, keeps the
+/// case of letters and passes characters outside the alphabet through unchanged.
+///
+/// [`ClassicalVigenere`]: crate::algorithms::ClassicalVigenere
+#[derive(Clone, Debug)]
+pub struct Caesar {
+    alphabet: Alphabet,
+    shift: usize,
+}
+
+impl Caesar {
+
+    /// Creates the Caesar cipher over the Latin alphabet A&ndash;Z. The shift is
+    /// reduced modulo 26.
+    pub fn new(shift: usize) -> Self {
+        Self::with_alphabet(Alphabet::latin(), shift)
+    }
+
+    /// Creates ROT13, which is its own inverse.
+    pub fn rot13() -> Self {
+        Self::new(13)
+    }
+
+    /// Creates the Caesar cipher over given alphabet. The shift is reduced modulo
+    /// the length of the alphabet.
+    pub fn with_alphabet(alphabet: Alphabet, shift: usize) -> Self {
+        let shift = shift % alphabet.len();
+        Self { alphabet, shift }
+    }
+
+    /// Returns the shift, reduced modulo the length of the alphabet.
+    pub fn shift(&self) -> usize {
+        self.shift
+    }
+}
+
+impl Encrypt for Caesar {
+
+    fn encrypt<D, E>(&self, data_to_encrypt: D) -> E
+    where
+        D:           IntoIterator<Item = u8>,
+        D::IntoIter: ExactSizeIterator,
+        E:           FromIterator<u8>
+    {
+        let data: Vec<u8> = data_to_encrypt.into_iter().collect();
+        self.alphabet.map_text(&data, |index| index + self.shift)
+            .into_iter()
+            .collect()
+    }
+}
+
+impl Decrypt for Caesar {
+
+    fn decrypt<E, D>(&self, encrypted_data: E) -> D
+    where
+        E:           IntoIterator<Item = u8>,
+        E::IntoIter: ExactSizeIterator,
+        D:           FromIterator<u8>
+    {
+        let data: Vec<u8> = encrypted_data.into_iter().collect();
+        let len = self.alphabet.len();
+        self.alphabet.map_text(&data, |index| index + len - self.shift)
+            .into_iter()
+            .collect()
+    }
+}
+
+/// The affine cipher, which maps a symbol with index `x` in the [`Alphabet`] to
+/// the symbol with index `a * x + b` modulo the length of the alphabet.
+///
+/// Like [`ClassicalVigenere`], the cipher treats data as UTF-8 text, keeps the
+/// case of letters and passes characters outside the alphabet through unchanged.
+///
+/// [`ClassicalVigenere`]: crate::algorithms::ClassicalVigenere
+#[derive(Clone, Debug)]
+pub struct Affine {
+    alphabet: Alphabet,
+    a: usize,
+    a_inverse: usize,
+    b: usize,
+}
+
+impl Affine {
+
+    /// Creates the affine cipher over the Latin alphabet A&ndash;Z.
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with the cipher
+    /// - [`Err`] with [`Error::InvalidKey`] if `a` is not coprime with 26
+    pub fn new(a: usize, b: usize) -> Result<Self, Error> {
+        Self::with_alphabet(Alphabet::latin(), a, b)
+    }
+
+    /// Creates the affine cipher over given alphabet. Both `a` and `b` are reduced
+    /// modulo the length of the alphabet.
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with the cipher
+    /// - [`Err`] with [`Error::InvalidKey`] if `a` is not coprime with the length
+    ///   of the alphabet
+    pub fn with_alphabet(alphabet: Alphabet, a: usize, b: usize) -> Result<Self, Error> {
+        let len = alphabet.len();
+        let (a, b) = (a % len, b % len);
+        let a_inverse = (1..len).find(|candidate| a * candidate % len == 1)
+            .ok_or_else(|| Error::InvalidKey {
+                reason: format!("{} is not coprime with the length of the alphabet {}", a, len)
+            })?;
+        Ok(Self { alphabet, a, a_inverse, b })
+    }
+
+    /// Returns the multiplier `a` and the shift `b`.
+    pub fn parameters(&self) -> (usize, usize) {
+        (self.a, self.b)
+    }
+}
+
+impl Encrypt for Affine {
+
+    fn encrypt<D, E>(&self, data_to_encrypt: D) -> E
+    where
+        D:           IntoIterator<Item = u8>,
+        D::IntoIter: ExactSizeIterator,
+        E:           FromIterator<u8>
+    {
+        let data: Vec<u8> = data_to_encrypt.into_iter().collect();
+        self.alphabet.map_text(&data, |index| self.a * index + self.b)
+            .into_iter()
+            .collect()
+    }
+}
+
+impl Decrypt for Affine {
+
+    fn decrypt<E, D>(&self, encrypted_data: E) -> D
+    where
+        E:           IntoIterator<Item = u8>,
+        E::IntoIter: ExactSizeIterator,
+        D:           FromIterator<u8>
+    {
+        let data: Vec<u8> = encrypted_data.into_iter().collect();
+        let len = self.alphabet.len();
+        self.alphabet.map_text(&data, |index| self.a_inverse * (index + len - self.b))
+            .into_iter()
+            .collect()
+    }
+}
+
+/// The monoalphabetic substitution cipher, which replaces every symbol of an
+/// [`Alphabet`] by the symbol at the same position in a permutation of the
+/// alphabet.
+///
+/// Like [`ClassicalVigenere`], the cipher treats data as UTF-8 text, keeps the
+/// case of letters and passes characters outside the alphabet through unchanged.
+///
+/// [`ClassicalVigenere`]: crate::algorithms::ClassicalVigenere
+#[derive(Clone, Debug)]
+pub struct Substitution {
+    alphabet: Alphabet,
+    forward: Vec<usize>,
+    backward: Vec<usize>,
+}
+
+impl Substitution {
+
+    /// Creates the substitution cipher over the Latin alphabet A&ndash;Z.
+    ///
+    /// # Parameters
+    ///
+    /// - `substitutes`: all 26 letters in the order they replace A to Z; the case
+    ///   does not matter
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with the cipher
+    /// - [`Err`] with [`Error::InvalidKey`] if `substitutes` is not
+    ///   a permutation of the alphabet
+    pub fn new(substitutes: &str) -> Result<Self, Error> {
+        Self::with_alphabet(Alphabet::latin(), substitutes)
+    }
+
+    /// Creates the substitution cipher over given alphabet.
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with the cipher
+    /// - [`Err`] with [`Error::InvalidKey`] if `substitutes` is not
+    ///   a permutation of the alphabet
+    pub fn with_alphabet(alphabet: Alphabet, substitutes: &str) -> Result<Self, Error> {
+        let forward = alphabet.key_indices(substitutes)?;
+        let mut backward = vec![usize::MAX; alphabet.len()];
+        for (index, &substitute) in forward.iter().enumerate() {
+            if backward[substitute] != usize::MAX {
+                return Err(Error::InvalidKey {
+                    reason: "substitutes must not repeat a symbol".into()
+                })
+            }
+            backward[substitute] = index;
+        }
+        if forward.len() != alphabet.len() {
+            return Err(Error::InvalidKey {
+                reason: format!("{} substitutes needed, {} given", alphabet.len(), forward.len())
+            })
+        }
+        Ok(Self { alphabet, forward, backward })
+    }
+
+    /// Creates the keyword cipher over given alphabet. The substitutes are the
+    /// symbols of the keyword without repetitions followed by the remaining symbols
+    /// of the alphabet in their order.
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with the cipher
+    /// - [`Err`] with [`Error::InvalidKey`] if the keyword is empty or contains
+    ///   a character outside the alphabet
+    pub fn from_keyword(alphabet: Alphabet, keyword: &str) -> Result<Self, Error> {
+        let mut substitutes = alphabet.key_indices(keyword)?;
+        substitutes.extend(0..alphabet.len());
+        let mut used = vec![false; alphabet.len()];
+        substitutes.retain(|&index| !std::mem::replace(&mut used[index], true));
+        let substitutes: String = substitutes.into_iter()
+            .map(|index| alphabet.symbol(index, false))
+            .collect();
+        Self::with_alphabet(alphabet, &substitutes)
+    }
+
+    /// Creates the substitution cipher over given alphabet with substitutes stored
+    /// in a [`Key`] as UTF-8 text.
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with the cipher
+    /// - [`Err`] with [`Error::InvalidKey`] if the key is not UTF-8 text or it
+    ///   is not a permutation of the alphabet
+    pub fn from_key(alphabet: Alphabet, key: &Key) -> Result<Self, Error> {
+        Self::with_alphabet(alphabet, key_text(key)?)
+    }
+
+    /// Returns the substitutes in the order they replace symbols of the alphabet.
+    pub fn substitutes(&self) -> String {
+        self.forward.iter()
+            .map(|&index| self.alphabet.symbol(index, false))
+            .collect()
+    }
+}
+
+impl Encrypt for Substitution {
+
+    fn encrypt<D, E>(&self, data_to_encrypt: D) -> E
+    where
+        D:           IntoIterator<Item = u8>,
+        D::IntoIter: ExactSizeIterator,
+        E:           FromIterator<u8>
+    {
+        let data: Vec<u8> = data_to_encrypt.into_iter().collect();
+        self.alphabet.map_text(&data, |index| self.forward[index])
+            .into_iter()
+            .collect()
+    }
+}
+
+impl Decrypt for Substitution {
+
+    fn decrypt<E, D>(&self, encrypted_data: E) -> D
+    where
+        E:           IntoIterator<Item = u8>,
+        E::IntoIter: ExactSizeIterator,
+        D:           FromIterator<u8>
+    {
+        let data: Vec<u8> = encrypted_data.into_iter().collect();
+        self.alphabet.map_text(&data, |index| self.backward[index])
+            .into_iter()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::round_trip_text;
+
+    #[test]
+    fn caesar() {
+        let text = "The quick brown fox jumps over the lazy dog.";
+        assert_eq!(
+            round_trip_text(&Caesar::new(3), text),
+            "Wkh txlfn eurzq ira mxpsv ryhu wkh odcb grj."
+        );
+        assert_eq!(round_trip_text(&Caesar::new(29), "XYZ"), "ABC");
+        let rot13 = Caesar::rot13();
+        assert_eq!(round_trip_text(&rot13, "Hello, World!"), "Uryyb, Jbeyq!");
+        assert_eq!(round_trip_text(&rot13, "Uryyb, Jbeyq!"), "Hello, World!");
+        let digits = Alphabet::new("0123456789").unwrap();
+        assert_eq!(round_trip_text(&Caesar::with_alphabet(digits, 5), "a1b9"), "a6b4");
+    }
+
+    #[test]
+    fn affine() {
+        let cipher = Affine::new(5, 8).unwrap();
+        assert_eq!(round_trip_text(&cipher, "AFFINE cipher"), "IHHWVC swfrcp");
+        assert_eq!(cipher.parameters(), (5, 8));
+        assert!(matches!(Affine::new(13, 1), Err(Error::InvalidKey { .. })));
+        assert!(Affine::new(2, 1).is_err());
+        // Affine cipher with `a` one is the Caesar cipher:
+        assert_eq!(round_trip_text(&Affine::new(1, 3).unwrap(), "Caesar"), "Fdhvdu");
+    }
+
+    #[test]
+    fn substitution() {
+        let cipher = Substitution::from_keyword(Alphabet::latin(), "ZEBRAS").unwrap();
+        assert_eq!(cipher.substitutes(), "ZEBRASCDFGHIJKLMNOPQTUVWXY");
+        assert_eq!(
+            round_trip_text(&cipher, "Flee at once. We are discovered!"),
+            "Siaa zq lkba. Va zoa rfpbluaoar!"
+        );
+        let key = Key::new(b"zebrascdfghijklmnopqtuvwxy".to_vec());
+        let from_key = Substitution::from_key(Alphabet::latin(), &key).unwrap();
+        assert_eq!(from_key.substitutes(), cipher.substitutes());
+
+        assert!(Substitution::new("ZEBRAS").is_err());
+        assert!(Substitution::new("ZZBRASCDFGHIJKLMNOPQTUVWXY").is_err());
+        assert!(Substitution::new("ZEBRASCDFGHIJKLMNOPQTUVWXY!").is_err());
+    }
+}
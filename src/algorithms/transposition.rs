@@ -0,0 +1,223 @@
+use std::iter::FromIterator;
+use std::iter::IntoIterator;
+
+use crate::decrypt::Decrypt;
+use crate::encrypt::Encrypt;
+use crate::error::Error;
+use crate::key::Key;
+
+/// The rail fence cipher, which writes bytes in a zigzag over a number of rails
+/// and reads the rails one after another. It moves bytes, but does not change
+/// them.
+#[derive(Clone, Debug)]
+pub struct RailFence {
+    rails: usize,
+}
+
+impl RailFence {
+
+    /// Creates the cipher with given number of rails.
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with the cipher
+    /// - [`Err`] with [`Error::InvalidKey`] if there are fewer than two rails
+    pub fn new(rails: usize) -> Result<Self, Error> {
+        if rails < 2 {
+            return Err(Error::InvalidKey { reason: "at least two rails are needed".into() })
+        }
+        Ok(Self { rails })
+    }
+
+    /// Returns number of rails.
+    pub fn rails(&self) -> usize {
+        self.rails
+    }
+
+    /// Returns positions of bytes of the plaintext of given length in the order
+    /// they appear in the encrypted data.
+    fn order(&self, len: usize) -> Vec<usize> {
+        let cycle = 2 * (self.rails - 1);
+        let rail = |position: usize| {
+            let phase = position % cycle;
+            phase.min(cycle - phase)
+        };
+        let mut order: Vec<usize> = (0..len).collect();
+        order.sort_by_key(|&position| rail(position));
+        order
+    }
+}
+
+impl Encrypt for RailFence {
+
+    fn encrypt<D, E>(&self, data_to_encrypt: D) -> E
+    where
+        D:           IntoIterator<Item = u8>,
+        D::IntoIter: ExactSizeIterator,
+        E:           FromIterator<u8>
+    {
+        let data: Vec<u8> = data_to_encrypt.into_iter().collect();
+        gather(&data, &self.order(data.len()))
+    }
+}
+
+impl Decrypt for RailFence {
+
+    fn decrypt<E, D>(&self, encrypted_data: E) -> D
+    where
+        E:           IntoIterator<Item = u8>,
+        E::IntoIter: ExactSizeIterator,
+        D:           FromIterator<u8>
+    {
+        let data: Vec<u8> = encrypted_data.into_iter().collect();
+        scatter(&data, &self.order(data.len()))
+    }
+}
+
+/// The columnar transposition cipher, which writes bytes in rows as wide as the
+/// keyword and reads the columns in the alphabetical order of the keyword. The
+/// last row is not padded. It moves bytes, but does not change them.
+///
+/// ```
+/// use mdcrypt::algorithms::ColumnarTransposition;
+/// use mdcrypt::Encrypt;
+///
+/// let cipher = ColumnarTransposition::new("ZEBRAS").unwrap();
+/// let encrypted: Vec<u8> = cipher.encrypt(b"WEAREDISCOVEREDFLEEATONCE".to_vec());
+/// assert_eq!(encrypted, b"EVLNACDTESEAROFODEECWIREE");
+/// ```
+#[derive(Clone, Debug)]
+pub struct ColumnarTransposition {
+    columns: Vec<usize>,
+}
+
+impl ColumnarTransposition {
+
+    /// Creates the cipher from a keyword. Its characters are compared by their
+    /// code points regardless of case, and columns under the same characters are
+    /// read from left to right.
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with the cipher
+    /// - [`Err`] with [`Error::InvalidKey`] if the keyword is empty
+    pub fn new(keyword: &str) -> Result<Self, Error> {
+        let keyword: Vec<char> = keyword.chars()
+            .map(|character| character.to_lowercase().next().unwrap())
+            .collect();
+        Self::from_order(&keyword)
+    }
+
+    /// Creates the cipher from a key, whose bytes are compared as numbers instead
+    /// of characters of a keyword.
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with the cipher
+    /// - [`Err`] with [`Error::InvalidKey`] if the key is empty
+    pub fn from_key(key: &Key) -> Result<Self, Error> {
+        Self::from_order(key.reveal())
+    }
+
+    /// Returns indices of the columns in the order they are read.
+    pub fn columns(&self) -> &[usize] {
+        &self.columns
+    }
+
+    fn from_order<T: Ord>(keyword: &[T]) -> Result<Self, Error> {
+        if keyword.is_empty() {
+            return Err(Error::InvalidKey { reason: "key must not be empty".into() })
+        }
+        let mut columns: Vec<usize> = (0..keyword.len()).collect();
+        columns.sort_by_key(|&column| &keyword[column]);
+        Ok(Self { columns })
+    }
+
+    /// Returns positions of bytes of the plaintext of given length in the order
+    /// they appear in the encrypted data.
+    fn order(&self, len: usize) -> Vec<usize> {
+        let width = self.columns.len();
+        self.columns.iter()
+            .flat_map(|&column| (column..len).step_by(width))
+            .collect()
+    }
+}
+
+impl Encrypt for ColumnarTransposition {
+
+    fn encrypt<D, E>(&self, data_to_encrypt: D) -> E
+    where
+        D:           IntoIterator<Item = u8>,
+        D::IntoIter: ExactSizeIterator,
+        E:           FromIterator<u8>
+    {
+        let data: Vec<u8> = data_to_encrypt.into_iter().collect();
+        gather(&data, &self.order(data.len()))
+    }
+}
+
+impl Decrypt for ColumnarTransposition {
+
+    fn decrypt<E, D>(&self, encrypted_data: E) -> D
+    where
+        E:           IntoIterator<Item = u8>,
+        E::IntoIter: ExactSizeIterator,
+        D:           FromIterator<u8>
+    {
+        let data: Vec<u8> = encrypted_data.into_iter().collect();
+        scatter(&data, &self.order(data.len()))
+    }
+}
+
+/// Returns bytes of `data` at positions given by `order`.
+fn gather<T: FromIterator<u8>>(data: &[u8], order: &[usize]) -> T {
+    order.iter().map(|&position| data[position]).collect()
+}
+
+/// Puts bytes of `data` to positions given by `order`, the inverse of [`gather`].
+fn scatter<T: FromIterator<u8>>(data: &[u8], order: &[usize]) -> T {
+    let mut output = vec![0; data.len()];
+    for (&byte, &position) in data.iter().zip(order) {
+        output[position] = byte;
+    }
+    output.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::round_trip;
+
+    #[test]
+    fn rail_fence() {
+        let cipher = RailFence::new(3).unwrap();
+        assert_eq!(
+            round_trip(&cipher, b"WEAREDISCOVEREDFLEEATONCE"),
+            b"WECRLTEERDSOEEFEAOCAIVDEN"
+        );
+        assert_eq!(round_trip(&RailFence::new(2).unwrap(), b"abcdefg"), b"acegbdf");
+        for len in 0..20 {
+            let text: Vec<u8> = (0..len).collect();
+            for rails in 2..6 {
+                round_trip(&RailFence::new(rails).unwrap(), &text);
+            }
+        }
+        assert!(matches!(RailFence::new(1), Err(Error::InvalidKey { .. })));
+    }
+
+    #[test]
+    fn columnar() {
+        let cipher = ColumnarTransposition::new("zebras").unwrap();
+        assert_eq!(cipher.columns(), [4, 2, 1, 3, 5, 0]);
+        assert_eq!(
+            round_trip(&cipher, b"WEAREDISCOVEREDFLEEATONCEQKJEU"),
+            b"EVLNEACDTKESEAQROFOJDEECUWIREE"
+        );
+        let from_key = ColumnarTransposition::from_key(&Key::new(vec![9, 1, 9, 0])).unwrap();
+        assert_eq!(from_key.columns(), [3, 1, 0, 2]);
+        for len in 0..20 {
+            round_trip(&from_key, &(0..len).collect::<Vec<u8>>());
+        }
+        assert!(ColumnarTransposition::new("").is_err());
+    }
+}
@@ -1,8 +1,13 @@
+use std::io;
 use std::iter::FromIterator;
 use std::iter::IntoIterator;
 
 use crate::decrypt::Decrypt;
 use crate::encrypt::Encrypt;
+use crate::stream::Decryptor;
+use crate::stream::Encryptor;
+use crate::stream::StreamDecrypt;
+use crate::stream::StreamEncrypt;
 use crate::Key;
 
 pub struct Vigener {
@@ -77,4 +82,62 @@ impl Decrypt for Vigener {
             // Collect into an instance of `D`
             .collect()
     }
-}
\ No newline at end of file
+}
+
+impl StreamEncrypt for Vigener {
+    type Encryptor<'a> = VigenerStream<'a>;
+
+    fn encryptor(&self) -> VigenerStream<'_> {
+        VigenerStream { key: &self.key, offset: 0 }
+    }
+}
+
+impl StreamDecrypt for Vigener {
+    type Decryptor<'a> = VigenerStream<'a>;
+
+    fn decryptor(&self) -> VigenerStream<'_> {
+        VigenerStream { key: &self.key, offset: 0 }
+    }
+}
+
+/// State of [`Vigener`] encryption or decryption fed by chunks of data. It
+/// remembers position in the key, where the next chunk begins.
+pub struct VigenerStream<'a> {
+    key: &'a Key,
+    offset: usize,
+}
+
+impl VigenerStream<'_> {
+
+    /// Maps each byte of `input` with the matching byte of the key and moves the
+    /// position in the key.
+    fn apply<F>(&mut self, input: &[u8], output: &mut Vec<u8>, map: F)
+    where F: Fn(u8, u8) -> u8 {
+        output.extend(input.iter()
+            .zip(self.key.iter().cycle().skip(self.offset))
+            .map(|(&byte, &mask)| map(byte, mask)));
+        self.offset = (self.offset + input.len()) % self.key.len();
+    }
+}
+
+impl Encryptor for VigenerStream<'_> {
+    fn update(&mut self, input: &[u8], output: &mut Vec<u8>) -> io::Result<()> {
+        self.apply(input, output, u8::wrapping_add);
+        Ok(())
+    }
+
+    fn finish(self, _output: &mut Vec<u8>) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Decryptor for VigenerStream<'_> {
+    fn update(&mut self, input: &[u8], output: &mut Vec<u8>) -> io::Result<()> {
+        self.apply(input, output, u8::wrapping_sub);
+        Ok(())
+    }
+
+    fn finish(self, _output: &mut Vec<u8>) -> io::Result<()> {
+        Ok(())
+    }
+}
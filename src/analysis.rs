@@ -0,0 +1,481 @@
+use std::collections::HashMap;
+
+use crate::error::Error;
+use crate::key::Key;
+
+/// Length of repeated sequences looked for by Kasiski examination.
+const KASISKI_SEQUENCE_LEN: usize = 3;
+
+/// Smallest probability of a byte in a [`LanguageModel`], so that a byte never
+/// seen in the sample does not make a text impossible.
+const MIN_PROBABILITY: f64 = 1e-5;
+
+/// Relative frequencies of English letters `a` to `z` in percent.
+const ENGLISH_LETTERS: [f64; 26] = [
+    8.167, 1.492, 2.782, 4.253, 12.702, 2.228, 2.015, 6.094, 6.966, 0.153, 0.772, 4.025, 2.406,
+    6.749, 7.507, 1.929, 0.095, 5.987, 6.327, 9.056, 2.758, 0.978, 2.360, 0.150, 1.974, 0.074,
+];
+
+/// Probability distribution of plaintext bytes used to score candidate
+/// decryptions. Use [`english()`] for English text, or [`from_sample()`] to learn
+/// the distribution of any data, including binary formats.
+///
+/// [`english()`]: LanguageModel::english
+/// [`from_sample()`]: LanguageModel::from_sample
+#[derive(Clone, Debug, PartialEq)]
+pub struct LanguageModel {
+    probabilities: [f64; 256],
+}
+
+impl LanguageModel {
+
+    /// Creates a model from relative frequencies of each byte value. Frequencies
+    /// do not need to sum up to one, they are normalized. Every byte gets at
+    /// least a tiny probability.
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with the model
+    /// - [`Err`] with [`Error::InvalidParameters`] if a frequency is negative or
+    ///   not finite, or all frequencies are zero
+    pub fn from_frequencies(frequencies: [f64; 256]) -> Result<Self, Error> {
+        if !frequencies.iter().all(|frequency| frequency.is_finite() && *frequency >= 0.0) {
+            return Err(Error::invalid_parameter(
+                "frequencies",
+                "must be finite and non-negative"
+            ))
+        }
+        if frequencies.iter().sum::<f64>() <= 0.0 {
+            return Err(Error::invalid_parameter(
+                "frequencies",
+                "at least one frequency must be positive"
+            ))
+        }
+        Ok(Self::normalized(frequencies))
+    }
+
+    /// Learns the model from a sample of typical plaintext, e.g. files of the
+    /// same format as the encrypted one.
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with the model
+    /// - [`Err`] with [`Error::InvalidParameters`] if the sample is empty
+    pub fn from_sample(sample: &[u8]) -> Result<Self, Error> {
+        if sample.is_empty() {
+            return Err(Error::invalid_parameter("sample", "must not be empty"))
+        }
+        Ok(Self::normalized(histogram(sample).map(|count| count as f64)))
+    }
+
+    /// Creates a model from valid frequencies, see
+    /// [`from_frequencies()`](LanguageModel::from_frequencies).
+    fn normalized(frequencies: [f64; 256]) -> Self {
+        let total: f64 = frequencies.iter().sum();
+        let mut probabilities = frequencies
+            .map(|frequency| (frequency / total).max(MIN_PROBABILITY));
+        let total: f64 = probabilities.iter().sum();
+        probabilities.iter_mut().for_each(|probability| *probability /= total);
+        Self { probabilities }
+    }
+
+    /// Model of English text written in ASCII: lowercase and uppercase letters
+    /// by their frequency in English, spaces, line breaks, common punctuation and
+    /// digits.
+    pub fn english() -> Self {
+        let mut frequencies = [0.0; 256];
+        for (letter, frequency) in (b'a'..=b'z').zip(ENGLISH_LETTERS) {
+            frequencies[letter as usize] = 0.78 * frequency;
+            frequencies[letter.to_ascii_uppercase() as usize] = 0.03 * frequency;
+        }
+        frequencies[b' ' as usize] = 17.0;
+        for (symbol, frequency) in [(b'\n', 0.8), (b'.', 1.0), (b',', 1.0), (b'\'', 0.3),
+                (b'"', 0.2), (b'-', 0.2), (b'?', 0.05), (b'!', 0.05), (b';', 0.05), (b':', 0.05)] {
+            frequencies[symbol as usize] = frequency;
+        }
+        (b'0'..=b'9').for_each(|digit| frequencies[digit as usize] = 0.05);
+        Self::normalized(frequencies)
+    }
+
+    /// Returns probability of given byte.
+    pub fn probability(&self, byte: u8) -> f64 {
+        self.probabilities[byte as usize]
+    }
+
+    /// Returns the index of coincidence of the model, e.g. the probability that
+    /// two random bytes of a text are equal.
+    pub fn index_of_coincidence(&self) -> f64 {
+        self.probabilities.iter().map(|probability| probability * probability).sum()
+    }
+
+    /// Returns how many bits per byte the model saves compared to uniformly
+    /// random bytes when describing given text, on average. Text fitting the
+    /// model well gives a positive value, random bytes give a negative one.
+    pub fn bits_per_byte(&self, text: &[u8]) -> f64 {
+        if text.is_empty() {
+            return 0.0
+        }
+        let total: f64 = text.iter().map(|&byte| self.probability(byte).log2() + 8.0).sum();
+        total / text.len() as f64
+    }
+
+    /// Returns Pearson's chi-squared statistic of byte counts of given text
+    /// against the model. Lower value means better fit.
+    pub fn chi_squared(&self, text: &[u8]) -> f64 {
+        chi_squared(&histogram(text), text.len(), self, 0)
+    }
+}
+
+/// Estimated length of a [`Vigener`](crate::algorithms::Vigener) key returned by
+/// [`VigenerAnalyzer::key_lengths`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyLengthEstimate {
+    /// Length of the key in bytes.
+    pub len: usize,
+    /// Share of distances between repeated sequences divisible by the length,
+    /// from 0 to 1, found by Kasiski examination.
+    pub kasiski: f64,
+    /// Average index of coincidence of bytes encrypted by the same key byte.
+    pub index_of_coincidence: f64,
+    /// Combined score; higher is more likely.
+    pub score: f64,
+}
+
+/// Candidate key found by [`VigenerAnalyzer::candidates`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Candidate {
+    /// The key.
+    pub key: Key,
+    /// Plausibility of the key: bits per byte the language model saves when
+    /// describing the decrypted text, minus bits needed to describe the key
+    /// spread over the text. Higher is more plausible, values around zero or
+    /// below mean the decrypted text does not fit the model.
+    pub score: f64,
+}
+
+/// Breaks [`Vigener`](crate::algorithms::Vigener) cipher without the key.
+///
+/// The analysis has two steps:
+///
+///  1. Length of the key is estimated by Kasiski examination, which looks for
+///     repeated sequences in the encrypted data, since their distances tend to
+///     be multiples of the key length, and by the index of coincidence, which
+///     is high only when bytes encrypted by the same key byte are grouped
+///     together.
+///  2. For the most likely lengths, every key byte is chosen so that the bytes
+///     it decrypts fit the [`LanguageModel`] best by the chi-squared statistic.
+///
+/// ```
+/// use mdcrypt::algorithms::Vigener;
+/// use mdcrypt::analysis::LanguageModel;
+/// use mdcrypt::analysis::VigenerAnalyzer;
+/// use mdcrypt::Encrypt;
+/// use mdcrypt::Key;
+///
+/// let text = "It was the best of times, it was the worst of times, it was the age \
+///     of wisdom, it was the age of foolishness, it was the epoch of belief, it \
+///     was the epoch of incredulity, it was the season of Light, it was the \
+///     season of Darkness, it was the spring of hope, it was the winter of \
+///     despair, we had everything before us, we had nothing before us.";
+/// let key = Key::new(b"dickens".to_vec());
+/// let encrypted: Vec<u8> = Vigener::new(key.clone()).encrypt(text.bytes());
+///
+/// let candidates = VigenerAnalyzer::new(LanguageModel::english()).candidates(&encrypted);
+/// assert_eq!(candidates[0].key, key);
+/// ```
+#[derive(Clone, Debug)]
+pub struct VigenerAnalyzer {
+    model: LanguageModel,
+    max_key_len: usize,
+    max_candidates: usize,
+}
+
+impl VigenerAnalyzer {
+
+    /// Creates an analyzer scoring decrypted data by given model. It considers
+    /// keys up to 32 bytes long and returns at most 5 candidates.
+    pub fn new(model: LanguageModel) -> Self {
+        Self { model, max_key_len: 32, max_candidates: 5 }
+    }
+
+    /// Sets the maximal length of the key in bytes considered.
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with the modified analyzer
+    /// - [`Err`] with [`Error::InvalidParameters`] if `max_key_len` is zero
+    pub fn with_max_key_len(mut self, max_key_len: usize) -> Result<Self, Error> {
+        if max_key_len == 0 {
+            return Err(Error::invalid_parameter("max_key_len", "must be non-zero"))
+        }
+        self.max_key_len = max_key_len;
+        Ok(self)
+    }
+
+    /// Sets the maximal number of candidates returned by
+    /// [`candidates()`](VigenerAnalyzer::candidates), which is also the number of
+    /// key lengths tried.
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with the modified analyzer
+    /// - [`Err`] with [`Error::InvalidParameters`] if `max_candidates` is zero
+    pub fn with_max_candidates(mut self, max_candidates: usize) -> Result<Self, Error> {
+        if max_candidates == 0 {
+            return Err(Error::invalid_parameter("max_candidates", "must be non-zero"))
+        }
+        self.max_candidates = max_candidates;
+        Ok(self)
+    }
+
+    /// Returns the language model.
+    pub fn model(&self) -> &LanguageModel {
+        &self.model
+    }
+
+    /// Estimates length of the key. Lengths are limited by the maximal key length
+    /// and by half of the data length, so that every key byte encrypts at least
+    /// two bytes.
+    ///
+    /// # Returns
+    ///
+    /// All considered lengths, the most likely first. Empty if the data are
+    /// shorter than two bytes.
+    pub fn key_lengths(&self, encrypted_data: &[u8]) -> Vec<KeyLengthEstimate> {
+        let max_len = self.max_key_len.min(encrypted_data.len() / 2);
+        let distances = kasiski_distances(encrypted_data);
+        let random_ic = 1.0 / 256.0;
+        let model_ic = self.model.index_of_coincidence();
+
+        let mut estimates: Vec<KeyLengthEstimate> = (1..=max_len)
+            .map(|len| {
+                let kasiski = match distances.len() {
+                    0 => 0.0,
+                    count => distances.iter().filter(|&&distance| distance % len == 0).count()
+                        as f64 / count as f64,
+                };
+                let ic = (0..len)
+                    .map(|offset| index_of_coincidence(&column(encrypted_data, len, offset)))
+                    .sum::<f64>() / len as f64;
+                // Index of coincidence relative to the model, about 1 for the right
+                // length and its multiples. Kasiski examination prefers the right
+                // length over its multiples, since fewer distances divide them:
+                let relative_ic = (ic - random_ic) / (model_ic - random_ic);
+                let score = relative_ic * (1.0 + kasiski);
+                KeyLengthEstimate { len, kasiski, index_of_coincidence: ic, score }
+            })
+            .collect();
+        // Shorter key wins a tie, e.g. when no sequence repeats:
+        estimates.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.len.cmp(&b.len)));
+        estimates
+    }
+
+    /// Recovers candidate keys for the most likely key lengths.
+    ///
+    /// # Returns
+    ///
+    /// Distinct candidates, the most plausible first. A key repeating itself,
+    /// such as `abab`, is reduced to its period, `ab`.
+    pub fn candidates(&self, encrypted_data: &[u8]) -> Vec<Candidate> {
+        let mut candidates: Vec<Candidate> = Vec::new();
+        for estimate in self.key_lengths(encrypted_data).iter().take(self.max_candidates) {
+            let key = reduce_to_period(
+                (0..estimate.len)
+                    .map(|offset| self.best_shift(&column(encrypted_data, estimate.len, offset)))
+                    .collect()
+            );
+            if candidates.iter().any(|candidate| candidate.key.reveal() == &key[..]) {
+                continue
+            }
+            let decrypted: Vec<u8> = encrypted_data.iter()
+                .zip(key.iter().cycle())
+                .map(|(byte, mask)| byte.wrapping_sub(*mask))
+                .collect();
+            let key_cost = (key.len() * 8) as f64 / encrypted_data.len() as f64;
+            let score = self.model.bits_per_byte(&decrypted) - key_cost;
+            candidates.push(Candidate { key: Key::new(key), score });
+        }
+        candidates.sort_by(|a, b| b.score.total_cmp(&a.score));
+        candidates
+    }
+
+    /// Returns the key byte which makes the bytes encrypted by it fit the model
+    /// best.
+    fn best_shift(&self, encrypted_column: &[u8]) -> u8 {
+        let counts = histogram(encrypted_column);
+        (0..=u8::MAX)
+            .map(|shift| (shift, chi_squared(&counts, encrypted_column.len(), &self.model, shift)))
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(shift, _)| shift)
+            .unwrap()
+    }
+}
+
+/// Returns the index of coincidence of given data, e.g. the probability that
+/// two bytes at different random positions are equal. English text has about
+/// 0.065 when only letters are counted, uniformly random bytes have 1/256.
+///
+/// # Returns
+///
+/// The index, or zero if the data are shorter than two bytes.
+pub fn index_of_coincidence(data: &[u8]) -> f64 {
+    if data.len() < 2 {
+        return 0.0
+    }
+    let pairs: usize = histogram(data).iter().map(|&count| count * count.saturating_sub(1)).sum();
+    pairs as f64 / (data.len() * (data.len() - 1)) as f64
+}
+
+/// Performs Kasiski examination: finds sequences of 3 bytes occurring more than
+/// once and counts, for every possible key length up to `max_key_len`, how many
+/// distances between consecutive occurrences it divides.
+///
+/// # Returns
+///
+/// Counts for key lengths 1 to `max_key_len`; the first item is for length 1,
+/// which divides every distance.
+pub fn kasiski(encrypted_data: &[u8], max_key_len: usize) -> Vec<usize> {
+    let distances = kasiski_distances(encrypted_data);
+    (1..=max_key_len)
+        .map(|len| distances.iter().filter(|&&distance| distance % len == 0).count())
+        .collect()
+}
+
+/// Returns distances between consecutive occurrences of repeated sequences.
+fn kasiski_distances(data: &[u8]) -> Vec<usize> {
+    let mut last_positions: HashMap<&[u8], usize> = HashMap::new();
+    let mut distances = Vec::new();
+    for (position, sequence) in data.windows(KASISKI_SEQUENCE_LEN).enumerate() {
+        if let Some(last) = last_positions.insert(sequence, position) {
+            distances.push(position - last);
+        }
+    }
+    distances
+}
+
+/// Returns every `len`-th byte starting at `offset`.
+fn column(data: &[u8], len: usize, offset: usize) -> Vec<u8> {
+    data.iter().skip(offset).step_by(len).copied().collect()
+}
+
+fn histogram(data: &[u8]) -> [usize; 256] {
+    let mut counts = [0; 256];
+    data.iter().for_each(|&byte| counts[byte as usize] += 1);
+    counts
+}
+
+/// Chi-squared statistic of the bytes counted by `counts` decrypted by `shift`
+/// against the model.
+fn chi_squared(counts: &[usize; 256], len: usize, model: &LanguageModel, shift: u8) -> f64 {
+    (0..=u8::MAX)
+        .map(|plain| {
+            let observed = counts[plain.wrapping_add(shift) as usize] as f64;
+            let expected = model.probability(plain) * len as f64;
+            (observed - expected).powi(2) / expected
+        })
+        .sum()
+}
+
+/// Shortens the key to its smallest period, e.g. `abcabc` to `abc`.
+fn reduce_to_period(key: Vec<u8>) -> Vec<u8> {
+    let period = (1..key.len())
+        .filter(|period| key.len() % *period == 0)
+        .find(|&period| key.iter().zip(key.iter().skip(period)).all(|(a, b)| a == b))
+        .unwrap_or(key.len());
+    key[..period].to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::Vigener;
+    use crate::Encrypt;
+    use rand::Rng;
+    use rand::SeedableRng;
+
+    const TEXT: &str = "Call me Ishmael. Some years ago, never mind how long precisely, \
+        having little or no money in my purse, and nothing particular to interest me on \
+        shore, I thought I would sail about a little and see the watery part of the world. \
+        It is a way I have of driving off the spleen and regulating the circulation. \
+        Whenever I find myself growing grim about the mouth; whenever it is a damp, \
+        drizzly November in my soul; whenever I find myself involuntarily pausing before \
+        coffin warehouses, and bringing up the rear of every funeral I meet; and \
+        especially whenever my hypos get such an upper hand of me, that it requires a \
+        strong moral principle to prevent me from deliberately stepping into the street, \
+        and methodically knocking people's hats off, then, I account it high time to get \
+        to sea as soon as I can.";
+
+    #[test]
+    fn english() {
+        let key = Key::new(b"Moby Dick".to_vec());
+        let encrypted: Vec<u8> = Vigener::new(key.clone()).encrypt(TEXT.bytes());
+        let analyzer = VigenerAnalyzer::new(LanguageModel::english());
+
+        assert_eq!(analyzer.key_lengths(&encrypted)[0].len, 9);
+        let candidates = analyzer.candidates(&encrypted);
+        assert_eq!(candidates[0].key, key);
+        assert!(candidates[0].score > 2.0);
+        assert!(candidates.windows(2).all(|pair| pair[0].score >= pair[1].score));
+        assert!(candidates.iter().skip(1).all(|candidate| candidate.key != key));
+    }
+
+    #[test]
+    fn binary() {
+        // Structured binary data, e.g. records of small little-endian numbers:
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let mut record = || {
+            let value: u16 = rng.gen_range(0..300);
+            [value.to_le_bytes()[0], value.to_le_bytes()[1], 0, 0, b'R', 0]
+        };
+        let sample: Vec<u8> = (0..200).flat_map(|_| record()).collect();
+        let data: Vec<u8> = (0..200).flat_map(|_| record()).collect();
+
+        let key = Key::new(vec![0x13, 0xf0, 0x77, 0x01, 0xaa]);
+        let encrypted: Vec<u8> = Vigener::new(key.clone()).encrypt(data);
+        let analyzer = VigenerAnalyzer::new(LanguageModel::from_sample(&sample).unwrap())
+            .with_max_key_len(16).unwrap()
+            .with_max_candidates(3).unwrap();
+        let candidates = analyzer.candidates(&encrypted);
+        assert!(candidates.len() <= 3);
+        assert_eq!(candidates[0].key, key);
+    }
+
+    #[test]
+    fn statistics() {
+        assert_eq!(index_of_coincidence(b"aaaa"), 1.0);
+        assert_eq!(index_of_coincidence(b"abcd"), 0.0);
+        assert_eq!(index_of_coincidence(b"a"), 0.0);
+        // Repeats at distances 6 and 12 are divisible by 1, 2, 3 and 6:
+        let counts = kasiski(b"abcxyzabcuvwabc", 6);
+        assert_eq!(counts, vec![2, 2, 2, 0, 0, 2]);
+
+        let model = LanguageModel::english();
+        let probabilities: f64 = (0..=255).map(|byte| model.probability(byte)).sum();
+        assert!((probabilities - 1.0).abs() < 1e-9);
+        assert!(model.probability(b'e') > model.probability(b'z'));
+        assert!(model.bits_per_byte(TEXT.as_bytes()) > 2.0);
+        assert!(model.bits_per_byte(&[0x00, 0xff, 0x80, 0x7f]) < 0.0);
+        assert!(model.chi_squared(TEXT.as_bytes()) < model.chi_squared(&[0xff; 100]));
+        assert_eq!(reduce_to_period(b"abab".to_vec()), b"ab");
+        assert_eq!(reduce_to_period(b"aba".to_vec()), b"aba");
+    }
+
+    #[test]
+    fn invalid_parameters() {
+        assert!(matches!(LanguageModel::from_sample(&[]), Err(Error::InvalidParameters { .. })));
+        for frequencies in [[0.0; 256], [-1.0; 256], [f64::NAN; 256], [f64::INFINITY; 256]] {
+            assert!(LanguageModel::from_frequencies(frequencies).is_err());
+        }
+        assert!(LanguageModel::from_frequencies([1.0; 256]).is_ok());
+
+        let analyzer = || VigenerAnalyzer::new(LanguageModel::english());
+        assert!(matches!(
+            analyzer().with_max_key_len(0),
+            Err(Error::InvalidParameters { parameter, .. }) if parameter == "max_key_len"
+        ));
+        assert!(matches!(
+            analyzer().with_max_candidates(0),
+            Err(Error::InvalidParameters { parameter, .. }) if parameter == "max_candidates"
+        ));
+    }
+}
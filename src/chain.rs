@@ -0,0 +1,207 @@
+use std::iter::FromIterator;
+use std::iter::IntoIterator;
+
+use crate::decrypt::TryDecrypt;
+use crate::encrypt::TryEncrypt;
+use crate::error::Error;
+
+/// Composition of two algorithms. Encryption applies the first algorithm and then
+/// the second one on its output, decryption undoes them in the reverse order.
+/// For example, chaining [`Vigener`] and [`HammingECC`] encrypts data and then
+/// protects them against errors during transmission.
+///
+/// Stages are numbered from zero. Use [`then`] to append more stages, so that
+/// [`Error::Stage`] reports index of the failing stage within the whole chain.
+/// An algorithm given to [`new`] counts as a single stage, even if it is a
+/// [`Chain`] itself. Then the error of the nested chain, which reports its own
+/// stage, is the source of the error of that stage.
+///
+/// Each stage can be fallible or infallible, since [`Encrypt`] and [`Decrypt`]
+/// implementors implement [`TryEncrypt`] and [`TryDecrypt`] as well.
+///
+/// [`Vigener`]: crate::algorithms::Vigener
+/// [`HammingECC`]: crate::algorithms::HammingECC
+/// [`Encrypt`]: crate::Encrypt
+/// [`Decrypt`]: crate::Decrypt
+/// [`new`]: Chain::new
+/// [`then`]: Chain::then
+pub struct Chain<A, B> {
+    first: A,
+    second: B,
+    first_stages: usize,
+}
+
+impl<A, B> Chain<A, B> {
+
+    /// Creates a new [`Chain`] of two stages.
+    ///
+    /// # Parameters
+    ///
+    /// - `first`: algorithm applied first when encrypting (stage 0)
+    /// - `second`: algorithm applied second when encrypting (stage 1)
+    pub fn new(first: A, second: B) -> Self {
+        Self { first, second, first_stages: 1 }
+    }
+
+    /// Appends another stage to the chain.
+    ///
+    /// # Parameters
+    ///
+    /// - `next`: algorithm applied after all the stages of this chain when
+    ///   encrypting
+    pub fn then<C>(self, next: C) -> Chain<Self, C> {
+        let first_stages = self.stages();
+        Chain { first: self, second: next, first_stages }
+    }
+
+    /// Returns number of stages in the chain.
+    pub fn stages(&self) -> usize {
+        self.first_stages + 1
+    }
+
+    /// Returns the first part of the chain.
+    pub fn first(&self) -> &A {
+        &self.first
+    }
+
+    /// Returns the second part of the chain, e.g. the last stage.
+    pub fn second(&self) -> &B {
+        &self.second
+    }
+}
+
+impl<A, B> TryEncrypt for Chain<A, B>
+where
+    A: TryEncrypt,
+    B: TryEncrypt,
+    A::ErrorType: Into<Error>,
+    B::ErrorType: Into<Error>,
+{
+    /// [`Error::Stage`] with error of the failing stage together with its index.
+    type ErrorType = Error;
+
+    fn try_encrypt<D, E>(
+        &self,
+        data_to_encrypt: D,
+    ) -> Result<E, Error> where
+        D:           IntoIterator<Item = u8>,
+        D::IntoIter: ExactSizeIterator,
+        E:           FromIterator<u8>
+    {
+        let intermediate: Vec<u8> = self.first.try_encrypt(data_to_encrypt)
+            .map_err(|error| self.first_error(error))?;
+        self.second.try_encrypt(intermediate)
+            .map_err(|error| self.second_error(error))
+    }
+}
+
+impl<A, B> TryDecrypt for Chain<A, B>
+where
+    A: TryDecrypt,
+    B: TryDecrypt,
+    A::ErrorType: Into<Error>,
+    B::ErrorType: Into<Error>,
+{
+    /// [`Error::Stage`] with error of the failing stage together with its index.
+    type ErrorType = Error;
+
+    fn try_decrypt<E, D>(
+        &self,
+        encrypted_data: E,
+    ) -> Result<D, Error> where
+        E:           IntoIterator<Item = u8>,
+        E::IntoIter: ExactSizeIterator,
+        D:           FromIterator<u8>
+    {
+        // Undo the stages in the reverse order:
+        let intermediate: Vec<u8> = self.second.try_decrypt(encrypted_data)
+            .map_err(|error| self.second_error(error))?;
+        self.first.try_decrypt(intermediate)
+            .map_err(|error| self.first_error(error))
+    }
+}
+
+impl<A, B> Chain<A, B> {
+
+    /// Wraps error of the first part into [`Error::Stage`]. If the first part is
+    /// a chain extended by [`then`](Chain::then), its stages are stages of this
+    /// chain, so its error is kept.
+    fn first_error<E: Into<Error>>(&self, error: E) -> Error {
+        match error.into() {
+            error @ Error::Stage { .. } if self.first_stages > 1 => error,
+            error => Error::Stage { stage: 0, source: Box::new(error) }
+        }
+    }
+
+    /// Wraps error of the second part, e.g. the last stage, into [`Error::Stage`].
+    fn second_error<E: Into<Error>>(&self, error: E) -> Error {
+        Error::Stage { stage: self.first_stages, source: Box::new(error.into()) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::HammingECC;
+    use crate::algorithms::Vigener;
+    use crate::Key;
+
+    #[test]
+    fn round_trip() {
+        let chain = Chain::new(
+            Vigener::new(Key::new(vec![1, 2, 3])),
+            HammingECC::new(4, 8).unwrap()
+        )
+            .then(Vigener::new(Key::new(vec![200, 100])));
+        assert_eq!(chain.stages(), 3);
+
+        let data = b"encrypt then protect".to_vec();
+        let encrypted: Vec<u8> = chain.try_encrypt(data.clone()).unwrap();
+        let hamming_only: Vec<u8> = HammingECC::new(4, 8).unwrap()
+            .try_encrypt(data.clone()).unwrap();
+        assert_eq!(encrypted.len(), hamming_only.len());
+        let decrypted: Vec<u8> = chain.try_decrypt(encrypted).unwrap();
+        assert_eq!(decrypted, data);
+    }
+
+    #[test]
+    fn stage_index() {
+        let vigener = || Vigener::new(Key::new(vec![42]));
+        let chain = Chain::new(vigener(), vigener())
+            .then(HammingECC::new(3, 2).unwrap())
+            .then(vigener());
+
+        // Hamming can encode at most 3 bytes:
+        let result: Result<Vec<u8>, _> = chain.try_encrypt(vec![0; 4]);
+        assert!(matches!(result.unwrap_err(), Error::Stage { stage: 2, .. }));
+        // 5 zero bytes do not make whole blocks:
+        let result: Result<Vec<u8>, _> = chain.try_decrypt(vec![0; 5]);
+        match result.unwrap_err() {
+            Error::Stage { stage: 2, source } => {
+                assert!(matches!(*source, Error::Malformed { .. }))
+            },
+            error => panic!("Unexpected error: {}", error)
+        }
+
+        // A nested chain is a single stage, whose error reports the stage of the
+        // nested chain:
+        let inner = || Chain::new(vigener(), HammingECC::new(3, 2).unwrap());
+        let chain = Chain::new(vigener(), inner());
+        assert_eq!(chain.stages(), 2);
+        let result: Result<Vec<u8>, _> = chain.try_encrypt(vec![0; 4]);
+        match result.unwrap_err() {
+            Error::Stage { stage: 1, source } => {
+                assert!(matches!(*source, Error::Stage { stage: 1, .. }))
+            },
+            error => panic!("Unexpected error: {}", error)
+        }
+        let chain = Chain::new(inner(), vigener());
+        let result: Result<Vec<u8>, _> = chain.try_encrypt(vec![0; 4]);
+        match result.unwrap_err() {
+            Error::Stage { stage: 0, source } => {
+                assert!(matches!(*source, Error::Stage { stage: 1, .. }))
+            },
+            error => panic!("Unexpected error: {}", error)
+        }
+    }
+}
@@ -0,0 +1,602 @@
+use std::iter::ExactSizeIterator;
+use std::iter::FromIterator;
+use std::iter::IntoIterator;
+
+use rand::Rng;
+use zeroize::Zeroizing;
+
+use crate::algorithms::AesGcm;
+use crate::algorithms::ChaCha20Poly1305;
+use crate::algorithms::HammingECC;
+use crate::algorithms::NONCE_LEN;
+use crate::algorithms::TAG_LEN;
+use crate::decrypt::TryDecrypt;
+use crate::encrypt::TryEncrypt;
+use crate::error::Error;
+use crate::kdf::Hkdf256;
+use crate::kdf::KdfParams;
+use crate::key::Key;
+
+/// First bytes of every container.
+const MAGIC: [u8; 4] = *b"MDCR";
+
+/// Version of the container format written by [`Container`].
+pub const CONTAINER_VERSION: u8 = 1;
+
+/// Length of the random salt written into every container.
+const SALT_LEN: usize = 16;
+
+/// Label of the cipher key derived by HKDF.
+const KEY_INFO: &[u8] = b"mdcrypt container v1 cipher key";
+
+/// Length of the cipher key in bytes.
+const KEY_LEN: usize = 32;
+
+/// Self-describing encrypted container. Its header records everything needed to
+/// decrypt it except the key or password, so a container can be decrypted
+/// without knowing in advance how it was produced.
+///
+/// Encryption by [`TryEncrypt`] generates a random salt and nonce, encrypts the
+/// data by an AEAD cipher using the header as associated data, so that the tag
+/// covers the header as well, and optionally protects the encrypted data by
+/// [`HammingECC`]. Decryption by [`TryDecrypt`] takes all parameters from the
+/// header and ignores those set on this instance, only the key or password is
+/// used.
+///
+/// # Format, version 1
+///
+/// All integers are big-endian.
+///
+/// | Size   | Field                                                      |
+/// |--------|------------------------------------------------------------|
+/// | 4      | magic bytes `MDCR`                                         |
+/// | 1      | version, `1`                                               |
+/// | 1      | cipher: `1` AES-256-GCM, `2` ChaCha20-Poly1305             |
+/// | 1      | length *k* of the KDF parameters                           |
+/// | *k*    | [`KdfParams`] as ASCII text; empty if a key is used        |
+/// | 1      | length *s* of the salt                                     |
+/// | *s*    | salt                                                       |
+/// | 1      | error correction: `0` none, `1` [`HammingECC`]             |
+/// | 0 or 2 | `blk_log_size` and `size_field_bits` of Hamming code       |
+/// | 12     | nonce                                                      |
+/// | 8      | length *c* of the encrypted data including the 16-byte tag |
+/// | rest   | payload: the encrypted data, encoded if ECC is used        |
+///
+/// Everything before the payload is the header. Nothing may follow the payload.
+///
+/// The cipher key is derived by HKDF-SHA-256 with the salt from the master key,
+/// which is the key given to [`new()`](Container::new) or the key derived from
+/// the password by the KDF parameters and the salt. The label of the cipher key
+/// is `mdcrypt container v1 cipher key`.
+///
+/// ```
+/// use mdcrypt::algorithms::HammingECC;
+/// use mdcrypt::Container;
+/// use mdcrypt::KdfParams;
+/// use mdcrypt::TryDecrypt;
+/// use mdcrypt::TryEncrypt;
+///
+/// let container = Container::from_password(b"correct horse")
+///     .with_kdf(KdfParams::Pbkdf2Sha256 { iterations: 1000, key_len: 32 })
+///     .with_ecc(HammingECC::new(5, 16).unwrap());
+/// let sealed: Vec<u8> = container.try_encrypt(b"secret".to_vec()).unwrap();
+///
+/// let header = Container::read_header(&sealed).unwrap();
+/// assert_eq!(header.ecc(), Some(HammingECC::new(5, 16).unwrap()));
+/// let opened: Vec<u8> = Container::from_password(b"correct horse")
+///     .try_decrypt(sealed)
+///     .unwrap();
+/// assert_eq!(opened, b"secret");
+/// ```
+pub struct Container {
+    secret: Secret,
+    cipher: ContainerCipher,
+    kdf: KdfParams,
+    ecc: Option<HammingECC>,
+}
+
+/// Key or password protecting a [`Container`].
+enum Secret {
+    Key(Key),
+    Password(Zeroizing<Vec<u8>>),
+}
+
+impl Container {
+
+    /// Creates a container protected by a key. The key can have any length, but
+    /// it should have at least 128 bits of entropy. Uses ChaCha20-Poly1305 and no
+    /// error correction by default.
+    pub fn new(key: Key) -> Self {
+        Self::with_secret(Secret::Key(key))
+    }
+
+    /// Creates a container protected by a password, from which the key is
+    /// derived by [`KdfParams::default`] unless [`with_kdf()`] is used. Uses
+    /// ChaCha20-Poly1305 and no error correction by default.
+    ///
+    /// [`with_kdf()`]: Container::with_kdf
+    pub fn from_password(password: &[u8]) -> Self {
+        Self::with_secret(Secret::Password(Zeroizing::new(password.to_vec())))
+    }
+
+    fn with_secret(secret: Secret) -> Self {
+        Self {
+            secret,
+            cipher: ContainerCipher::ChaCha20Poly1305,
+            kdf: KdfParams::default(),
+            ecc: None,
+        }
+    }
+
+    /// Sets the cipher used for encryption.
+    pub fn with_cipher(mut self, cipher: ContainerCipher) -> Self {
+        self.cipher = cipher;
+        self
+    }
+
+    /// Sets parameters of the key derivation used for encryption. They are used
+    /// only if the container is protected by a password.
+    pub fn with_kdf(mut self, kdf: KdfParams) -> Self {
+        self.kdf = kdf;
+        self
+    }
+
+    /// Protects the encrypted data by Hamming code, so that single-bit errors in
+    /// every block of the payload can be corrected.
+    pub fn with_ecc(mut self, ecc: HammingECC) -> Self {
+        self.ecc = Some(ecc);
+        self
+    }
+
+    /// Reads the header of a container without decrypting it.
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with the header
+    /// - [`Err`] with [`Error::Malformed`] if the data are not a container or its
+    ///   KDF parameters are beyond the limits described by [`KdfParams`], or
+    ///   with [`Error::Unsupported`] if the version or an algorithm is unknown
+    pub fn read_header(data: &[u8]) -> Result<ContainerHeader, Error> {
+        ContainerHeader::parse(data).map(|(header, _)| header)
+    }
+
+    /// Encrypts the data with given salt and nonce.
+    fn seal(&self, data: &[u8], salt: Vec<u8>, nonce: [u8; NONCE_LEN]) -> Result<Vec<u8>, Error> {
+        let header = ContainerHeader {
+            version: CONTAINER_VERSION,
+            cipher: self.cipher,
+            kdf: match self.secret {
+                Secret::Key(_)      => None,
+                Secret::Password(_) => Some(self.kdf),
+            },
+            salt,
+            ecc: self.ecc,
+            nonce,
+            ciphertext_len: (data.len() + TAG_LEN) as u64,
+        };
+        let header_bytes = header.to_bytes()?;
+
+        let key = self.cipher_key(&header)?;
+        let encrypted: Vec<u8> = match header.cipher {
+            ContainerCipher::Aes256Gcm => AesGcm::new(&key, &header_bytes)?
+                .seal(nonce, data)?,
+            ContainerCipher::ChaCha20Poly1305 => ChaCha20Poly1305::new(&key, &header_bytes)?
+                .seal(nonce, data)?,
+        };
+        let payload: Vec<u8> = match &header.ecc {
+            Some(ecc) => ecc.try_encrypt(encrypted)?,
+            None      => encrypted,
+        };
+        Ok([header_bytes, payload].concat())
+    }
+
+    /// Decrypts the container.
+    fn open(&self, data: &[u8]) -> Result<Vec<u8>, Error> {
+        let (header, header_len) = ContainerHeader::parse(data)?;
+        let (header_bytes, payload) = data.split_at(header_len);
+
+        let encrypted: Vec<u8> = match &header.ecc {
+            Some(ecc) => ecc.try_decrypt(payload.iter().copied())?,
+            None      => payload.to_vec(),
+        };
+        if encrypted.len() as u64 != header.ciphertext_len {
+            return Err(Error::malformed(format!(
+                "expected {} bytes of encrypted data but {} were found",
+                header.ciphertext_len,
+                encrypted.len()
+            )))
+        }
+
+        let key = self.cipher_key(&header)?;
+        match header.cipher {
+            ContainerCipher::Aes256Gcm => AesGcm::new(&key, header_bytes)?
+                .open(header.nonce, &encrypted),
+            ContainerCipher::ChaCha20Poly1305 => ChaCha20Poly1305::new(&key, header_bytes)?
+                .open(header.nonce, &encrypted),
+        }
+    }
+
+    /// Derives the cipher key for the container with given header.
+    fn cipher_key(&self, header: &ContainerHeader) -> Result<Key, Error> {
+        let derived;
+        let master = match (&self.secret, &header.kdf) {
+            (Secret::Key(key), None) => key,
+            (Secret::Password(password), Some(kdf)) => {
+                derived = Key::derive_from_password(password, &header.salt, kdf)?;
+                &derived
+            },
+            (Secret::Key(_), Some(_)) => return Err(Error::InvalidKey {
+                reason: "the container is protected by a password".into()
+            }),
+            (Secret::Password(_), None) => return Err(Error::InvalidKey {
+                reason: "the container is protected by a key".into()
+            }),
+        };
+        Hkdf256::new(&header.salt, master).expand(KEY_INFO, KEY_LEN)
+    }
+}
+
+impl TryEncrypt for Container {
+
+    /// Error type to be returned when the data are too long, or when the key
+    /// derivation parameters are invalid.
+    type ErrorType = Error;
+
+    /// Encrypts the data into a container with a random salt and nonce.
+    fn try_encrypt<D, E>(
+        &self,
+        data_to_encrypt: D
+    ) -> Result<E, Self::ErrorType> where
+        D:           IntoIterator<Item = u8>,
+        D::IntoIter: ExactSizeIterator,
+        E:           FromIterator<u8> {
+
+        let data = Zeroizing::new(data_to_encrypt.into_iter().collect::<Vec<u8>>());
+        let mut rng = rand::thread_rng();
+        let mut salt = vec![0; SALT_LEN];
+        rng.fill(&mut salt[..]);
+        let nonce = rng.gen();
+        self.seal(&data, salt, nonce).map(|sealed| sealed.into_iter().collect())
+    }
+}
+
+impl TryDecrypt for Container {
+
+    /// Error type to be returned when the container is malformed, cannot be
+    /// corrected or fails authentication.
+    type ErrorType = Error;
+
+    /// Decrypts the container using parameters from its header.
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with the decrypted data
+    /// - [`Err`] with [`Error::AuthenticationFailed`] if the key or password is
+    ///   wrong or the container has been modified, with [`Error::InvalidKey`]
+    ///   if the container needs a password instead of a key or the other way
+    ///   around, or with an error of [`Container::read_header`] or
+    ///   [`HammingECC`]
+    fn try_decrypt<E, D>(
+        &self,
+        encrypted_data: E
+    ) -> Result<D, Self::ErrorType> where
+        E:           IntoIterator<Item = u8>,
+        E::IntoIter: ExactSizeIterator,
+        D:           FromIterator<u8> {
+
+        let data: Vec<u8> = encrypted_data.into_iter().collect();
+        let decrypted = Zeroizing::new(self.open(&data)?);
+        Ok(decrypted.iter().copied().collect())
+    }
+}
+
+/// AEAD cipher used by a [`Container`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ContainerCipher {
+    /// [`AesGcm`] with 256-bit key.
+    Aes256Gcm,
+    /// [`ChaCha20Poly1305`].
+    ChaCha20Poly1305,
+}
+
+impl ContainerCipher {
+
+    /// Returns identifier of the cipher in the header.
+    fn id(self) -> u8 {
+        match self {
+            Self::Aes256Gcm        => 1,
+            Self::ChaCha20Poly1305 => 2,
+        }
+    }
+
+    /// Returns the cipher with given identifier.
+    fn from_id(id: u8) -> Result<Self, Error> {
+        match id {
+            1 => Ok(Self::Aes256Gcm),
+            2 => Ok(Self::ChaCha20Poly1305),
+            _ => Err(Error::Unsupported { operation: format!("container cipher {}", id) }),
+        }
+    }
+}
+
+/// Header of a [`Container`], see its documentation for the format.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ContainerHeader {
+    version: u8,
+    cipher: ContainerCipher,
+    kdf: Option<KdfParams>,
+    salt: Vec<u8>,
+    ecc: Option<HammingECC>,
+    nonce: [u8; NONCE_LEN],
+    ciphertext_len: u64,
+}
+
+impl ContainerHeader {
+
+    /// Returns version of the format.
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    /// Returns the cipher.
+    pub fn cipher(&self) -> ContainerCipher {
+        self.cipher
+    }
+
+    /// Returns the key derivation parameters, or [`None`] if the container is
+    /// protected by a key instead of a password.
+    pub fn kdf(&self) -> Option<KdfParams> {
+        self.kdf
+    }
+
+    /// Returns the salt.
+    pub fn salt(&self) -> &[u8] {
+        &self.salt
+    }
+
+    /// Returns the error correction code protecting the payload, if any.
+    pub fn ecc(&self) -> Option<HammingECC> {
+        self.ecc
+    }
+
+    /// Returns the nonce.
+    pub fn nonce(&self) -> [u8; NONCE_LEN] {
+        self.nonce
+    }
+
+    /// Returns length of the encrypted data including the authentication tag,
+    /// before error correction is applied.
+    pub fn ciphertext_len(&self) -> u64 {
+        self.ciphertext_len
+    }
+
+    fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        let kdf = self.kdf.map(|kdf| kdf.to_string()).unwrap_or_default();
+        let kdf_len = u8::try_from(kdf.len())
+            .map_err(|_| Error::invalid_parameter("kdf", "parameters are too long"))?;
+
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend([self.version, self.cipher.id(), kdf_len]);
+        bytes.extend(kdf.as_bytes());
+        bytes.push(self.salt.len() as u8);
+        bytes.extend(&self.salt);
+        match &self.ecc {
+            Some(ecc) => bytes.extend([1, ecc.blk_log_size(), ecc.size_field_bits()]),
+            None      => bytes.push(0),
+        }
+        bytes.extend(self.nonce);
+        bytes.extend(self.ciphertext_len.to_be_bytes());
+        Ok(bytes)
+    }
+
+    /// Parses the header at the beginning of the data.
+    ///
+    /// # Returns
+    ///
+    /// The header and its length in bytes.
+    fn parse(data: &[u8]) -> Result<(Self, usize), Error> {
+        let mut reader = HeaderReader { data, position: 0 };
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err(Error::malformed("not an mdcrypt container"))
+        }
+        let version = reader.byte()?;
+        if version != CONTAINER_VERSION {
+            return Err(Error::Unsupported { operation: format!("container version {}", version) })
+        }
+        let cipher = ContainerCipher::from_id(reader.byte()?)?;
+
+        let kdf_len = reader.byte()? as usize;
+        let kdf = match kdf_len {
+            0 => None,
+            _ => {
+                let text = std::str::from_utf8(reader.take(kdf_len)?)
+                    .map_err(|_| Error::malformed("KDF parameters are not a text"))?;
+                // The parameters are not authenticated yet, so parameters which
+                // would make deriving the key too costly are a forgery:
+                Some(text.parse().map_err(|error| match error {
+                    Error::InvalidParameters { .. } =>
+                        Error::malformed(format!("invalid KDF parameters: {}", error)),
+                    error => error,
+                })?)
+            },
+        };
+        let salt_len = reader.byte()? as usize;
+        let salt = reader.take(salt_len)?.to_vec();
+        let ecc = match reader.byte()? {
+            0 => None,
+            1 => Some(HammingECC::new(reader.byte()?, reader.byte()?)?),
+            id => return Err(Error::Unsupported { operation: format!("container ECC {}", id) }),
+        };
+        let nonce = reader.take(NONCE_LEN)?.try_into().unwrap();
+        let ciphertext_len = u64::from_be_bytes(reader.take(8)?.try_into().unwrap());
+
+        let header = Self { version, cipher, kdf, salt, ecc, nonce, ciphertext_len };
+        Ok((header, reader.position))
+    }
+}
+
+/// Reads fields of a header one by one.
+struct HeaderReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> HeaderReader<'a> {
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        let field = self.data.get(self.position..self.position + len)
+            .ok_or_else(|| Error::malformed("container header is truncated"))?;
+        self.position += len;
+        Ok(field)
+    }
+
+    fn byte(&mut self) -> Result<u8, Error> {
+        self.take(1).map(|field| field[0])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLAINTEXT: &[u8] = b"The quick brown fox jumps over the lazy dog\n";
+    const PASSWORD: &[u8] = b"correct horse battery staple";
+
+    /// Key used by the golden files: bytes 0 to 31.
+    fn golden_key() -> Key {
+        Key::new((0..32).collect())
+    }
+
+    /// Salt and nonce used by the golden files.
+    fn golden_salt_nonce() -> (Vec<u8>, [u8; NONCE_LEN]) {
+        ((0xa0..0xb0).collect(), [0x5a; NONCE_LEN])
+    }
+
+    /// Containers stored in `tests/data` together with the settings which
+    /// produced them.
+    fn golden_files() -> [(Container, &'static [u8]); 2] {
+        [
+            (
+                Container::new(golden_key()).with_cipher(ContainerCipher::Aes256Gcm),
+                include_bytes!("../tests/data/container-key-aes256gcm.mdc"),
+            ),
+            (
+                Container::from_password(PASSWORD)
+                    .with_kdf(KdfParams::Pbkdf2Sha256 { iterations: 1000, key_len: 32 })
+                    .with_ecc(HammingECC::new(5, 16).unwrap()),
+                include_bytes!("../tests/data/container-password-chacha20poly1305-hamming.mdc"),
+            ),
+        ]
+    }
+
+    #[test]
+    fn golden() {
+        for (container, golden) in golden_files() {
+            // Decryption uses only the header and the secret:
+            let opened: Vec<u8> = container.try_decrypt(golden.iter().copied()).unwrap();
+            assert_eq!(opened, PLAINTEXT);
+            // The format has not changed:
+            let (salt, nonce) = golden_salt_nonce();
+            assert_eq!(container.seal(PLAINTEXT, salt, nonce).unwrap(), golden);
+        }
+
+        let header = Container::read_header(golden_files()[1].1).unwrap();
+        assert_eq!(header.version(), 1);
+        assert_eq!(header.cipher(), ContainerCipher::ChaCha20Poly1305);
+        assert_eq!(header.kdf(), Some(KdfParams::Pbkdf2Sha256 { iterations: 1000, key_len: 32 }));
+        assert_eq!(header.salt(), &golden_salt_nonce().0[..]);
+        assert_eq!(header.ecc(), Some(HammingECC::new(5, 16).unwrap()));
+        assert_eq!(header.nonce(), golden_salt_nonce().1);
+        assert_eq!(header.ciphertext_len(), (PLAINTEXT.len() + TAG_LEN) as u64);
+    }
+
+    #[test]
+    fn round_trip() {
+        let container = Container::new(Key::new(vec![7; 5]))
+            .with_ecc(HammingECC::new(4, 32).unwrap());
+        let sealed: Vec<u8> = container.try_encrypt(PLAINTEXT.iter().copied()).unwrap();
+        let other: Vec<u8> = container.try_encrypt(PLAINTEXT.iter().copied()).unwrap();
+        assert_ne!(sealed, other, "salt and nonce must be random");
+
+        // Single-bit error in the payload is corrected:
+        let mut damaged = sealed.clone();
+        *damaged.last_mut().unwrap() ^= 0x10;
+        let opened: Vec<u8> = container.try_decrypt(damaged).unwrap();
+        assert_eq!(opened, PLAINTEXT);
+        let opened: Vec<u8> = Container::new(Key::new(vec![7; 5])).try_decrypt(sealed).unwrap();
+        assert_eq!(opened, PLAINTEXT);
+    }
+
+    #[test]
+    fn tampering() {
+        let (container, golden) = &golden_files()[0];
+        let open = |data: Vec<u8>| container.try_decrypt::<_, Vec<u8>>(data);
+
+        // Every byte of the header and the payload is authenticated; changing
+        // the magic, version, cipher or lengths fails before authentication:
+        for idx in 0..golden.len() {
+            let mut damaged = golden.to_vec();
+            damaged[idx] ^= 0x01;
+            assert!(open(damaged).is_err(), "byte {}", idx);
+        }
+        let mut damaged = golden.to_vec();
+        damaged[20] ^= 0x01;
+        assert!(matches!(open(damaged), Err(Error::AuthenticationFailed)));
+
+        assert!(matches!(open(golden[..golden.len() - 1].to_vec()), Err(Error::Malformed { .. })));
+        assert!(matches!(open([&golden[..], &[0]].concat()), Err(Error::Malformed { .. })));
+        assert!(matches!(open(golden[..10].to_vec()), Err(Error::Malformed { .. })));
+        assert!(matches!(open(b"not a container".to_vec()), Err(Error::Malformed { .. })));
+        let mut future = golden.to_vec();
+        future[4] = 2;
+        assert!(matches!(open(future), Err(Error::Unsupported { .. })));
+    }
+
+    #[test]
+    fn forged_kdf_parameters() {
+        let (container, golden) = &golden_files()[1];
+        let kdf = b"pbkdf2-sha256:1000:32";
+        let start = golden.windows(kdf.len()).position(|window| window == kdf).unwrap();
+        let forgeries = [
+            "pbkdf2-sha256:1:18446744073709551615",
+            "pbkdf2-sha256:4294967295:32",
+            "scrypt:64:8:1:32",
+            "argon2id:4294967295:1:1:32",
+        ];
+        for forged_kdf in forgeries {
+            let forged = [
+                &golden[..start - 1],
+                &[forged_kdf.len() as u8],
+                forged_kdf.as_bytes(),
+                &golden[start + kdf.len()..],
+            ].concat();
+            let result = container.try_decrypt::<_, Vec<u8>>(forged);
+            assert!(matches!(result, Err(Error::Malformed { .. })), "{}", forged_kdf);
+        }
+    }
+
+    #[test]
+    fn wrong_secret() {
+        let [(_, with_key), (_, with_password)] = golden_files();
+        let open = |container: Container, data: &[u8]| {
+            container.try_decrypt::<_, Vec<u8>>(data.iter().copied())
+        };
+        assert!(matches!(
+            open(Container::new(Key::new(vec![1; 32])), with_key),
+            Err(Error::AuthenticationFailed)
+        ));
+        assert!(matches!(
+            open(Container::from_password(b"wrong"), with_password),
+            Err(Error::AuthenticationFailed)
+        ));
+        assert!(matches!(
+            open(Container::new(golden_key()), with_password),
+            Err(Error::InvalidKey { .. })
+        ));
+        assert!(matches!(
+            open(Container::from_password(PASSWORD), with_key),
+            Err(Error::InvalidKey { .. })
+        ));
+    }
+}
@@ -1,8 +1,12 @@
-use std::convert::Infallible;
-use std::error::Error;
+// Continuation lines of the parameter lists are kept as they were written:
+#![allow(clippy::doc_overindented_list_items)]
+
+use std::error;
 use std::iter::FromIterator;
 use std::iter::IntoIterator;
 
+use crate::Error;
+
 /// Represents an algorithm that can decrypt encrypted data represented as an
 /// iterable over bytes. [`try_decrypt`] method is used for the decryption. Note that
 /// the decryption might not be successful.
@@ -11,7 +15,7 @@ use std::iter::IntoIterator;
 pub trait TryDecrypt {
 
     /// The type when decryption will not be successful.
-    type ErrorType: Error;
+    type ErrorType: error::Error;
 
     /// This method is used for decryption. Encryption may or may not be successful.
     /// The implementor's documentation should state when decryption will result in
@@ -66,11 +70,9 @@ pub trait Decrypt {
 impl<T> TryDecrypt for T
 where T: Decrypt {
 
-    /// Error can never happen, so [`Infallible`] enum is used. Consult
-    /// [`Infallible`]'s documentation for more information.
-    /// 
-    /// [`Infallible`]: std::convert::Infallible
-    type ErrorType = Infallible;
+    /// Error can never happen, but crate's [`Error`] is used anyway, so that all
+    /// algorithms from this crate share the same error type.
+    type ErrorType = Error;
 
     /// This method is used for decryption. Decryption should be always successful,
     /// e.g. this method should never panic.
@@ -82,7 +84,7 @@ where T: Decrypt {
     fn try_decrypt<E, D>(
         &self,
         encrypted_data: E,
-    ) -> Result<D, Infallible> where
+    ) -> Result<D, Error> where
         E:           IntoIterator<Item = u8>,
         E::IntoIter: ExactSizeIterator,
         D:           FromIterator<u8>
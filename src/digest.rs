@@ -0,0 +1,112 @@
+use std::fmt;
+use std::slice;
+
+/// Output of a hash function, e.g. a digest of hashed data. Digest can be
+/// formatted as a hexadecimal number using `{:x}`, `{:X}` or `{}`.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Digest {
+    data: Vec<u8>,
+}
+
+impl Digest {
+    /// Creates a [`Digest`](Digest) instance from bytes output by a hash function.
+    pub(crate) fn new(data: Vec<u8>) -> Self {
+        Self { data }
+    }
+
+    /// Returns bytes of the digest.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Returns length of the digest in bytes.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns `true` if the digest contains no bytes.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns an iterator that does not consume the [`Digest`](Digest) instance
+    /// itself.
+    pub fn iter(&self) -> slice::Iter<'_, u8> {
+        self.data.iter()
+    }
+}
+
+impl AsRef<[u8]> for Digest {
+    fn as_ref(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+impl From<Digest> for Vec<u8> {
+    fn from(digest: Digest) -> Self {
+        digest.data
+    }
+}
+
+impl IntoIterator for Digest {
+    type Item = u8;
+    type IntoIter = std::vec::IntoIter<u8>;
+
+    /// Returns an iterator over bytes of the digest. This will consume the
+    /// [`Digest`](Digest) instance.
+    fn into_iter(self) -> Self::IntoIter {
+        self.data.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Digest {
+    type Item = &'a u8;
+    type IntoIter = slice::Iter<'a, u8>;
+
+    /// Returns an iterator over bytes of the digest. This will not consume the
+    /// [`Digest`](Digest) instance since we iterate over `&u8`.
+    fn into_iter(self) -> Self::IntoIter {
+        self.data.iter()
+    }
+}
+
+// Formatting implementation
+//===================================================================================
+
+impl fmt::LowerHex for Digest {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Format each byte as a hexadecimal number and append it to the `Formatter`:
+        for byte in &self.data {
+            formatter.write_fmt(format_args!("{:02x}", byte))?;
+        }
+
+        // Everything went fine:
+        Ok(())
+    }
+}
+
+impl fmt::UpperHex for Digest {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Format each byte as a hexadecimal number and append it to the `Formatter`:
+        for byte in &self.data {
+            formatter.write_fmt(format_args!("{:02X}", byte))?;
+        }
+
+        // Everything went fine:
+        Ok(())
+    }
+}
+
+impl fmt::Display for Digest {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Use `LowerHex` trait implementation
+        fmt::LowerHex::fmt(self, formatter)
+    }
+}
+
+impl fmt::Debug for Digest {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Use `LowerHex` trait implementation
+        fmt::LowerHex::fmt(self, formatter)
+    }
+}
@@ -0,0 +1,75 @@
+use crate::decrypt::TryDecrypt;
+use crate::encrypt::TryEncrypt;
+use crate::error::Error;
+
+/// Object-safe counterpart of [`TryEncrypt`] trait, so that the algorithm can be
+/// chosen at runtime and used as `dyn DynEncrypt`. It is implemented for every
+/// [`TryEncrypt`] implementor whose error can be converted into crate's [`Error`],
+/// so there is no need to implement it manually.
+pub trait DynEncrypt {
+
+    /// Encrypts given data. See [`TryEncrypt::try_encrypt`] for more information.
+    ///
+    /// # Parameters
+    ///
+    /// - `data_to_encrypt`: data to encrypt
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with encrypted data if encryption was successful
+    /// - [`Err`] with error of the algorithm otherwise
+    fn encrypt_bytes(&self, data_to_encrypt: &[u8]) -> Result<Vec<u8>, Error>;
+}
+
+/// Object-safe counterpart of [`TryDecrypt`] trait, so that the algorithm can be
+/// chosen at runtime and used as `dyn DynDecrypt`. It is implemented for every
+/// [`TryDecrypt`] implementor whose error can be converted into crate's [`Error`],
+/// so there is no need to implement it manually.
+pub trait DynDecrypt {
+
+    /// Decrypts given data. See [`TryDecrypt::try_decrypt`] for more information.
+    ///
+    /// # Parameters
+    ///
+    /// - `encrypted_data`: data to decrypt
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with decrypted data if decryption was successful
+    /// - [`Err`] with error of the algorithm otherwise
+    fn decrypt_bytes(&self, encrypted_data: &[u8]) -> Result<Vec<u8>, Error>;
+}
+
+/// Object-safe trait for algorithms which can both encrypt and decrypt. It is
+/// implemented for everything implementing both [`DynEncrypt`] and
+/// [`DynDecrypt`].
+pub trait DynCipher: DynEncrypt + DynDecrypt {}
+
+/// Blanket implementation of DynEncrypt when TryEncrypt is implemented
+impl<T> DynEncrypt for T
+where
+    T: TryEncrypt,
+    T::ErrorType: Into<Error>,
+{
+    fn encrypt_bytes(&self, data_to_encrypt: &[u8]) -> Result<Vec<u8>, Error> {
+        // Use [`TryEncrypt`]'s implementation and convert the error:
+        self.try_encrypt(data_to_encrypt.iter().copied()).map_err(Into::into)
+    }
+}
+
+/// Blanket implementation of DynDecrypt when TryDecrypt is implemented
+impl<T> DynDecrypt for T
+where
+    T: TryDecrypt,
+    T::ErrorType: Into<Error>,
+{
+    fn decrypt_bytes(&self, encrypted_data: &[u8]) -> Result<Vec<u8>, Error> {
+        // Use [`TryDecrypt`]'s implementation and convert the error:
+        self.try_decrypt(encrypted_data.iter().copied()).map_err(Into::into)
+    }
+}
+
+/// Blanket implementation of DynCipher when DynEncrypt and DynDecrypt are
+/// implemented
+impl<T> DynCipher for T
+where T: DynEncrypt + DynDecrypt + ?Sized {}
@@ -1,8 +1,12 @@
-use std::convert::Infallible;
-use std::error::Error;
+// Continuation lines of the parameter lists are kept as they were written:
+#![allow(clippy::doc_overindented_list_items)]
+
+use std::error;
 use std::iter::FromIterator;
 use std::iter::IntoIterator;
 
+use crate::Error;
+
 /// Represents an algorithm that can encrypt data represented as an iterable object
 /// over bytes. [`try_encrypt`] method is used for the encryption. Note that the
 /// encryption might not be successful.
@@ -11,7 +15,7 @@ use std::iter::IntoIterator;
 pub trait TryEncrypt {
 
     /// The type when encryption will not be successful.
-    type ErrorType: Error;
+    type ErrorType: error::Error;
 
     /// This method is used for encryption. Encryption may or may not be successful.
     /// The implementor's documentation should state when encryption will result in
@@ -66,11 +70,9 @@ pub trait Encrypt {
 impl<T> TryEncrypt for T
 where T: Encrypt {
 
-    /// Error can never happen, so [`Infallible`] enum is used. Consult
-    /// [`Infallible`]'s documentation for more information.
-    /// 
-    /// [`Infallible`]: std::convert::Infallible
-    type ErrorType = Infallible;
+    /// Error can never happen, but crate's [`Error`] is used anyway, so that all
+    /// algorithms from this crate share the same error type.
+    type ErrorType = Error;
 
     /// This method is used for encryption. Encryption should be always successful,
     /// e.g. this method should never panic.
@@ -82,7 +84,7 @@ where T: Encrypt {
     fn try_encrypt<E, D>(
         &self,
         data_to_encrypt: E,
-    ) -> Result<D, Infallible> where
+    ) -> Result<D, Error> where
         E:           IntoIterator<Item = u8>,
         E::IntoIter: ExactSizeIterator,
         D:           FromIterator<u8>
@@ -0,0 +1,229 @@
+use std::convert::Infallible;
+use std::error;
+use std::fmt;
+use std::io;
+
+/// Error type of this crate. All algorithms from this crate report their failures
+/// using this type, so that callers can match on the reason of the failure.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+
+    /// Given input is longer than the algorithm can process.
+    InputTooLong {
+        /// Maximal length of the input in bytes.
+        max: usize,
+        /// Actual length of the input in bytes.
+        actual: usize,
+    },
+
+    /// A block of error correction code contains more errors than can be
+    /// corrected.
+    Uncorrectable {
+        /// Index of the block, counted from zero.
+        block: usize,
+    },
+
+    /// Integrity check of the data has failed, e.g. the data have been modified
+    /// or a wrong key has been used.
+    AuthenticationFailed,
+
+    /// Given key cannot be used by the algorithm.
+    InvalidKey {
+        /// Why the key cannot be used.
+        reason: String,
+    },
+
+    /// A parameter of the algorithm has an invalid value.
+    InvalidParameters {
+        /// Name of the invalid parameter.
+        parameter: String,
+        /// Why the value is invalid.
+        reason: String,
+    },
+
+    /// Data to decrypt do not have the shape the algorithm produces, e.g. they
+    /// have wrong length.
+    Malformed {
+        /// What is wrong with the data.
+        reason: String,
+    },
+
+    /// Text to decode is not valid in its encoding, e.g. in Base64. The error
+    /// does not quote the text, so that it can be reported even if the text is
+    /// a secret, such as a key.
+    InvalidEncoding {
+        /// Name of the encoding.
+        encoding: String,
+        /// Position of the offending symbol in the text, counted from zero. For
+        /// a whole group of symbols it is the last symbol of the group.
+        position: usize,
+        /// What is wrong with the text.
+        kind: EncodingErrorKind,
+    },
+
+    /// The algorithm does not support the operation, e.g. a hash function cannot
+    /// decrypt.
+    Unsupported {
+        /// Name of the unsupported operation.
+        operation: String,
+    },
+
+    /// A stage of a [`Chain`] has failed.
+    ///
+    /// [`Chain`]: crate::Chain
+    Stage {
+        /// Index of the failing stage, counted from zero in the order of
+        /// encryption.
+        stage: usize,
+        /// Error of the failing stage.
+        source: Box<Error>,
+    },
+
+    /// Reading or writing has failed.
+    Io(io::Error),
+}
+
+impl Error {
+
+    /// Creates [`Error::InvalidParameters`] variant.
+    pub(crate) fn invalid_parameter<P, R>(parameter: P, reason: R) -> Self
+    where P: Into<String>, R: Into<String> {
+        Self::InvalidParameters { parameter: parameter.into(), reason: reason.into() }
+    }
+
+    /// Creates [`Error::Malformed`] variant.
+    pub(crate) fn malformed<R: Into<String>>(reason: R) -> Self {
+        Self::Malformed { reason: reason.into() }
+    }
+
+    /// Creates [`Error::InvalidEncoding`] variant.
+    pub(crate) fn invalid_encoding<N: Into<String>>(
+        encoding: N,
+        position: usize,
+        kind: EncodingErrorKind
+    ) -> Self {
+        Self::InvalidEncoding { encoding: encoding.into(), position, kind }
+    }
+}
+
+/// What is wrong with text which is not valid in its encoding, see
+/// [`Error::InvalidEncoding`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum EncodingErrorKind {
+
+    /// The symbol is not in the alphabet of the encoding or it is not allowed at
+    /// its position, e.g. `z` of Ascii85 inside a group.
+    InvalidSymbol,
+
+    /// Padding is missing, misplaced or not expected.
+    BadPadding,
+
+    /// The last group has a number of symbols which encoding never produces, e.g.
+    /// a single symbol of Base64.
+    TruncatedGroup,
+
+    /// Bits of the last symbol which do not belong to any byte are not zero, so
+    /// the text cannot be produced by encoding.
+    TrailingBits,
+
+    /// A group of symbols encodes a number too large for its bytes.
+    GroupOverflow,
+}
+
+impl fmt::Display for EncodingErrorKind {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(match self {
+            Self::InvalidSymbol  => "invalid symbol",
+            Self::BadPadding     => "bad padding",
+            Self::TruncatedGroup => "truncated group",
+            Self::TrailingBits   => "non-zero trailing bits",
+            Self::GroupOverflow  => "group exceeds its bytes",
+        })
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InputTooLong { max, actual } => write!(
+                formatter,
+                "Expected at most {} bytes but {} bytes were given",
+                max,
+                actual
+            ),
+            Self::Uncorrectable { block } => write!(
+                formatter,
+                "Block {} contains an uncorrectable error",
+                block
+            ),
+            Self::AuthenticationFailed => write!(formatter, "Authentication failed"),
+            Self::InvalidKey { reason } => write!(formatter, "Invalid key: {}", reason),
+            Self::InvalidParameters { parameter, reason } => write!(
+                formatter,
+                "Invalid value of `{}`: {}",
+                parameter,
+                reason
+            ),
+            Self::Malformed { reason } => write!(formatter, "Malformed data: {}", reason),
+            Self::InvalidEncoding { encoding, position, kind } => write!(
+                formatter,
+                "Invalid {}: {} at position {}",
+                encoding,
+                kind,
+                position
+            ),
+            Self::Unsupported { operation } => write!(
+                formatter,
+                "Operation `{}` is not supported",
+                operation
+            ),
+            Self::Stage { stage, source } => write!(
+                formatter,
+                "Stage {} failed: {}",
+                stage,
+                source
+            ),
+            Self::Io(error) => fmt::Display::fmt(error, formatter),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Stage { source, .. } => Some(source.as_ref()),
+            Self::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<Infallible> for Error {
+    fn from(infallible: Infallible) -> Self {
+        match infallible {}
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+/// Converts [`Error`] into [`io::Error`], so it can be returned from [`io::Read`]
+/// and [`io::Write`] implementors. The original [`Error`] can be obtained using
+/// [`io::Error::into_inner`] and downcasting.
+impl From<Error> for io::Error {
+    fn from(error: Error) -> Self {
+        let kind = match error {
+            Error::Io(io_error) => return io_error,
+            Error::InvalidKey { .. } => io::ErrorKind::InvalidInput,
+            Error::InvalidParameters { .. } => io::ErrorKind::InvalidInput,
+            Error::Unsupported { .. } => io::ErrorKind::Unsupported,
+            _ => io::ErrorKind::InvalidData,
+        };
+        io::Error::new(kind, error)
+    }
+}
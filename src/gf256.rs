@@ -0,0 +1,162 @@
+// Arithmetic in the Galois field GF(2^8) with the reducing polynomial
+// x^8 + x^4 + x^3 + x^2 + 1 (`0x11d`) and generator 2. Addition and subtraction
+// are both XOR, multiplication and division use logarithm tables.
+//
+// Table lookups depend on the values, so the table arithmetic is not
+// constant-time. Secret values, e.g. in Shamir's secret sharing, must use
+// `mul_ct()` and `inv_ct()` instead, which use no tables and no branches.
+
+/// Reducing polynomial of the field.
+const POLYNOMIAL: u16 = 0x11d;
+
+/// Powers of the generator. The table is doubled, so that the sum of two
+/// logarithms can be used as an index without reduction.
+const EXP: [u8; 510] = exp_table();
+
+/// Logarithms to the base of the generator; logarithm of zero is undefined.
+const LOG: [u8; 256] = log_table();
+
+const fn exp_table() -> [u8; 510] {
+    let mut table = [0; 510];
+    let mut value: u16 = 1;
+    let mut power = 0;
+    while power < 510 {
+        table[power] = value as u8;
+        value <<= 1;
+        if value & 0x100 != 0 {
+            value ^= POLYNOMIAL;
+        }
+        power += 1;
+    }
+    table
+}
+
+const fn log_table() -> [u8; 256] {
+    let mut table = [0; 256];
+    let mut power = 0;
+    while power < 255 {
+        table[EXP[power] as usize] = power as u8;
+        power += 1;
+    }
+    table
+}
+
+/// Multiplies two elements.
+pub(crate) fn mul(a: u8, b: u8) -> u8 {
+    match a == 0 || b == 0 {
+        true  => 0,
+        false => EXP[LOG[a as usize] as usize + LOG[b as usize] as usize],
+    }
+}
+
+/// Divides `a` by `b`.
+///
+/// # Panics
+///
+/// - if `b` is zero
+pub(crate) fn div(a: u8, b: u8) -> u8 {
+    assert_ne!(b, 0, "Division by zero in GF(256)");
+    match a {
+        0 => 0,
+        _ => EXP[LOG[a as usize] as usize + 255 - LOG[b as usize] as usize],
+    }
+}
+
+/// Returns inverse of `a`.
+///
+/// # Panics
+///
+/// - if `a` is zero
+pub(crate) fn inv(a: u8) -> u8 {
+    div(1, a)
+}
+
+/// Multiplies two elements in constant time, by carry-less multiplication and
+/// reduction. The time does not depend on the values, so they can be secret.
+pub(crate) fn mul_ct(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0;
+    for _ in 0..8 {
+        // Masks are either all ones or all zeros, so there are no branches:
+        product ^= a & (b & 1).wrapping_neg();
+        let overflow = (a >> 7).wrapping_neg();
+        a = (a << 1) ^ (overflow & (POLYNOMIAL & 0xff) as u8);
+        b >>= 1;
+    }
+    product
+}
+
+/// Returns inverse of `a` in constant time, computed as `a^254` since `a^255`
+/// is one. Unlike [`inv()`], zero gives zero.
+pub(crate) fn inv_ct(a: u8) -> u8 {
+    // 254 = 2 + 4 + ... + 128, so multiply all squares except `a` itself:
+    let mut inverse = 1;
+    let mut square = a;
+    for _ in 0..7 {
+        square = mul_ct(square, square);
+        inverse = mul_ct(inverse, square);
+    }
+    inverse
+}
+
+/// Returns the generator raised to `power`.
+pub(crate) fn exp(power: usize) -> u8 {
+    EXP[power % 255]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Multiplication by shifting and reducing, without tables.
+    fn slow_mul(mut a: u8, mut b: u8) -> u8 {
+        let mut product = 0;
+        while b != 0 {
+            if b & 1 != 0 {
+                product ^= a;
+            }
+            let overflow = a & 0x80 != 0;
+            a <<= 1;
+            if overflow {
+                a ^= (POLYNOMIAL & 0xff) as u8;
+            }
+            b >>= 1;
+        }
+        product
+    }
+
+    #[test]
+    fn mul_div() {
+        for a in 0..=255 {
+            for b in 0..=255 {
+                let product = mul(a, b);
+                assert_eq!(product, slow_mul(a, b));
+                assert_eq!(mul_ct(a, b), product);
+                if b != 0 {
+                    assert_eq!(div(product, b), a);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn inv_exp() {
+        for a in 1..=255 {
+            assert_eq!(mul(a, inv(a)), 1);
+            assert_eq!(inv_ct(a), inv(a));
+        }
+        assert_eq!(inv_ct(0), 0);
+        for (power, &value) in EXP[..255].iter().enumerate() {
+            assert_eq!(exp(power), value);
+            assert_eq!(exp(power + 255), value);
+        }
+    }
+
+    #[test]
+    fn generator() {
+        // Generator must give all non-zero elements before it repeats:
+        let mut seen = [false; 256];
+        EXP[..255].iter().for_each(|&value| seen[value as usize] = true);
+        assert!(!seen[0]);
+        assert!(seen[1..].iter().all(|&seen| seen));
+    }
+}
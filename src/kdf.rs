@@ -0,0 +1,628 @@
+use std::fmt;
+use std::str::FromStr;
+
+use sha2::digest::core_api::BlockSizeUser;
+use zeroize::Zeroizing;
+
+use crate::algorithms::Hmac;
+use crate::encrypt::Encrypt;
+use crate::error::Error;
+use crate::key::Key;
+
+/// Maximal length of a derived key in bytes.
+const MAX_KEY_LEN: usize = 64;
+
+/// Maximal number of PBKDF2 iterations.
+const MAX_PBKDF2_ITERATIONS: u32 = 10_000_000;
+
+/// Maximal memory used by scrypt and Argon2id in kibibytes, which is 1 GiB.
+const MAX_MEMORY_KIB: u64 = 1 << 20;
+
+/// Maximal number of Argon2id passes over the memory.
+const MAX_ARGON2_ITERATIONS: u32 = 100;
+
+/// Maximal parallelization parameter of scrypt and degree of parallelism of
+/// Argon2id.
+const MAX_PARALLELISM: u32 = 64;
+
+/// Algorithm and its parameters used to derive a [`Key`] from a password by
+/// [`Key::derive_from_password`].
+///
+/// The parameters are not secret and must be stored together with the encrypted
+/// data, since the same parameters and salt are needed to derive the same key
+/// again. For this reason [`KdfParams`] can be converted to a string and parsed
+/// back, the format is the name of the algorithm followed by its parameters, all
+/// separated by `:` in the order of the variant's fields:
+///
+/// - `pbkdf2-sha256:ITERATIONS:KEY_LEN`
+/// - `pbkdf2-sha512:ITERATIONS:KEY_LEN`
+/// - `scrypt:LOG_N:R:P:KEY_LEN`
+/// - `argon2id:MEMORY_KIB:ITERATIONS:PARALLELISM:KEY_LEN`
+///
+/// Since the parameters usually come along with the encrypted data, their cost
+/// is limited, so that forged parameters cannot exhaust the memory or CPU before
+/// the derived key is found to be wrong. The key is at most 64 bytes long,
+/// PBKDF2 runs at most 10 million iterations, scrypt and Argon2id use at most
+/// 1 GiB of memory, Argon2id makes at most 100 passes and the parallelism of
+/// both is at most 64.
+///
+/// ```
+/// use mdcrypt::KdfParams;
+///
+/// let params: KdfParams = "scrypt:15:8:1:32".parse().unwrap();
+/// assert_eq!(params, KdfParams::Scrypt { log_n: 15, r: 8, p: 1, key_len: 32 });
+/// assert_eq!(params.to_string(), "scrypt:15:8:1:32");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum KdfParams {
+
+    /// PBKDF2 (RFC 8018) with HMAC-SHA-256 as the pseudorandom function.
+    Pbkdf2Sha256 {
+        /// Number of iterations, must be non-zero.
+        iterations: u32,
+        /// Length of the derived key in bytes.
+        key_len: usize,
+    },
+
+    /// PBKDF2 (RFC 8018) with HMAC-SHA-512 as the pseudorandom function.
+    Pbkdf2Sha512 {
+        /// Number of iterations, must be non-zero.
+        iterations: u32,
+        /// Length of the derived key in bytes.
+        key_len: usize,
+    },
+
+    /// scrypt (RFC 7914).
+    Scrypt {
+        /// Binary logarithm of the CPU/memory cost parameter N.
+        log_n: u8,
+        /// Block size parameter.
+        r: u32,
+        /// Parallelization parameter.
+        p: u32,
+        /// Length of the derived key in bytes.
+        key_len: usize,
+    },
+
+    /// Argon2id version 0x13 (RFC 9106).
+    Argon2id {
+        /// Memory size in kibibytes.
+        memory_kib: u32,
+        /// Number of passes over the memory.
+        iterations: u32,
+        /// Degree of parallelism.
+        parallelism: u32,
+        /// Length of the derived key in bytes.
+        key_len: usize,
+    },
+}
+
+impl KdfParams {
+
+    /// Returns length of the derived key in bytes.
+    pub fn key_len(&self) -> usize {
+        match *self {
+            Self::Pbkdf2Sha256 { key_len, .. } => key_len,
+            Self::Pbkdf2Sha512 { key_len, .. } => key_len,
+            Self::Scrypt { key_len, .. }       => key_len,
+            Self::Argon2id { key_len, .. }     => key_len,
+        }
+    }
+
+    /// Checks that the parameters are within the limits described by
+    /// [`KdfParams`], so that deriving a key cannot exhaust the memory or CPU.
+    fn check_limits(&self) -> Result<(), Error> {
+        let key_len = self.key_len();
+        if key_len == 0 || key_len > MAX_KEY_LEN {
+            return Err(Error::invalid_parameter(
+                "key_len",
+                format!("must be from 1 to {}, {} given", MAX_KEY_LEN, key_len)
+            ))
+        }
+        let check = |parameter, value: u64, max: u64| match value <= max {
+            true  => Ok(()),
+            false => Err(Error::invalid_parameter(
+                parameter,
+                format!("must be at most {}, {} given", max, value)
+            )),
+        };
+        match *self {
+            Self::Pbkdf2Sha256 { iterations, .. } | Self::Pbkdf2Sha512 { iterations, .. } =>
+                check("iterations", iterations.into(), MAX_PBKDF2_ITERATIONS.into()),
+            Self::Scrypt { log_n, r, p, .. } => {
+                check("p", p.into(), MAX_PARALLELISM.into())?;
+                // scrypt uses 128 * r * N bytes of memory:
+                let memory_kib = 1u64.checked_shl(log_n.into())
+                    .and_then(|n| n.checked_mul(u64::from(r)))
+                    .map(|blocks| blocks.saturating_mul(128) / 1024)
+                    .unwrap_or(u64::MAX);
+                check("log_n", memory_kib, MAX_MEMORY_KIB)
+            },
+            Self::Argon2id { memory_kib, iterations, parallelism, .. } => {
+                check("memory_kib", memory_kib.into(), MAX_MEMORY_KIB)?;
+                check("iterations", iterations.into(), MAX_ARGON2_ITERATIONS.into())?;
+                check("parallelism", parallelism.into(), MAX_PARALLELISM.into())
+            },
+        }
+    }
+}
+
+impl Default for KdfParams {
+    /// Argon2id with 19 MiB of memory, 2 iterations and no parallelism, deriving
+    /// a 32 bytes long key, as recommended by OWASP.
+    fn default() -> Self {
+        Self::Argon2id { memory_kib: 19 * 1024, iterations: 2, parallelism: 1, key_len: 32 }
+    }
+}
+
+impl fmt::Display for KdfParams {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Pbkdf2Sha256 { iterations, key_len } =>
+                write!(f, "pbkdf2-sha256:{}:{}", iterations, key_len),
+            Self::Pbkdf2Sha512 { iterations, key_len } =>
+                write!(f, "pbkdf2-sha512:{}:{}", iterations, key_len),
+            Self::Scrypt { log_n, r, p, key_len } =>
+                write!(f, "scrypt:{}:{}:{}:{}", log_n, r, p, key_len),
+            Self::Argon2id { memory_kib, iterations, parallelism, key_len } =>
+                write!(f, "argon2id:{}:{}:{}:{}", memory_kib, iterations, parallelism, key_len),
+        }
+    }
+}
+
+impl FromStr for KdfParams {
+    type Err = Error;
+
+    /// Parses parameters in the format produced by [`Display`](fmt::Display)
+    /// implementation. Name of the algorithm is case-insensitive. Parameters
+    /// beyond the limits described by [`KdfParams`] are rejected.
+    fn from_str(text: &str) -> Result<Self, Error> {
+        let mut parts = text.trim().split(':');
+        let name = parts.next().unwrap_or_default().to_lowercase();
+        let args: Vec<&str> = parts.collect();
+
+        let expected_args = match name.as_str() {
+            "pbkdf2-sha256" | "pbkdf2-sha512" => 2,
+            "scrypt" | "argon2id"             => 4,
+            _ => return Err(Error::Unsupported { operation: format!("KDF `{}`", name) })
+        };
+        if args.len() != expected_args {
+            return Err(Error::invalid_parameter(
+                "kdf",
+                format!("`{}` takes {} parameters, {} given", name, expected_args, args.len())
+            ))
+        }
+
+        let params = match name.as_str() {
+            "pbkdf2-sha256" => Self::Pbkdf2Sha256 {
+                iterations: parse_number(args[0], "iterations")?,
+                key_len: parse_number(args[1], "key_len")?,
+            },
+            "pbkdf2-sha512" => Self::Pbkdf2Sha512 {
+                iterations: parse_number(args[0], "iterations")?,
+                key_len: parse_number(args[1], "key_len")?,
+            },
+            "scrypt" => Self::Scrypt {
+                log_n: parse_number(args[0], "log_n")?,
+                r: parse_number(args[1], "r")?,
+                p: parse_number(args[2], "p")?,
+                key_len: parse_number(args[3], "key_len")?,
+            },
+            _ => Self::Argon2id {
+                memory_kib: parse_number(args[0], "memory_kib")?,
+                iterations: parse_number(args[1], "iterations")?,
+                parallelism: parse_number(args[2], "parallelism")?,
+                key_len: parse_number(args[3], "key_len")?,
+            },
+        };
+        params.check_limits()?;
+        Ok(params)
+    }
+}
+
+impl Key {
+
+    /// Derives a key from a password, so that a passphrase can be used instead
+    /// of a randomly generated key.
+    ///
+    /// # Parameters
+    ///
+    /// - `password`: password to derive the key from
+    /// - `salt`: random bytes unique for every derived key; they are not secret
+    ///   and must be stored together with the encrypted data
+    /// - `params`: algorithm deriving the key and its parameters
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with the derived key of length given by `params`
+    /// - [`Err`] with [`Error::InvalidParameters`] if `params` or length of the
+    ///   salt are not accepted by the algorithm, or if `params` are beyond the
+    ///   limits described by [`KdfParams`]
+    pub fn derive_from_password(
+        password: &[u8],
+        salt: &[u8],
+        params: &KdfParams,
+    ) -> Result<Key, Error> {
+        params.check_limits()?;
+        let key_len = params.key_len();
+        let mut derived = vec![0; key_len];
+
+        match *params {
+            KdfParams::Pbkdf2Sha256 { iterations, .. } =>
+                pbkdf2::<sha2::Sha256>(password, salt, iterations, &mut derived)?,
+            KdfParams::Pbkdf2Sha512 { iterations, .. } =>
+                pbkdf2::<sha2::Sha512>(password, salt, iterations, &mut derived)?,
+            KdfParams::Scrypt { log_n, r, p, .. } => {
+                let scrypt_params = scrypt::Params::new(log_n, r, p, key_len)
+                    .map_err(|error| Error::invalid_parameter("scrypt", error.to_string()))?;
+                scrypt::scrypt(password, salt, &scrypt_params, &mut derived)
+                    .map_err(|error| Error::invalid_parameter("key_len", error.to_string()))?;
+            },
+            KdfParams::Argon2id { memory_kib, iterations, parallelism, .. } => {
+                let argon2_params = argon2::Params::new(
+                    memory_kib, iterations, parallelism, Some(key_len)
+                )
+                    .map_err(|error| Error::invalid_parameter("argon2id", error.to_string()))?;
+                argon2::Argon2::new(
+                    argon2::Algorithm::Argon2id,
+                    argon2::Version::V0x13,
+                    argon2_params
+                )
+                    .hash_password_into(password, salt, &mut derived)
+                    .map_err(|error| Error::invalid_parameter("argon2id", error.to_string()))?;
+            },
+        }
+
+        Ok(Key::new(derived))
+    }
+}
+
+/// Fills `derived` with key derived by PBKDF2 with HMAC using hash function `T`.
+fn pbkdf2<T>(
+    password: &[u8],
+    salt: &[u8],
+    iterations: u32,
+    derived: &mut [u8],
+) -> Result<(), Error>
+where T: Default + Clone + sha2::Digest + BlockSizeUser,
+{
+    if iterations == 0 {
+        return Err(Error::invalid_parameter("iterations", "must be non-zero"))
+    }
+    // HMAC pads the key with zeros to the block size, so a single zero byte is
+    // the same HMAC key as an empty password:
+    let hmac = match password.is_empty() {
+        true  => Hmac::<T>::new(&Key::new(vec![0])),
+        false => Hmac::<T>::new(&Key::new(password.to_vec())),
+    };
+
+    let block_len = <T as sha2::Digest>::output_size();
+    for (block_idx, block) in derived.chunks_mut(block_len).enumerate() {
+        // Blocks are numbered from one by a 32-bit big-endian integer:
+        let block_number = u32::try_from(block_idx + 1)
+            .map_err(|_| Error::invalid_parameter("key_len", "derived key is too long"))?;
+
+        let mut first = hmac.authenticator();
+        first.update(salt);
+        first.update(&block_number.to_be_bytes());
+        // Intermediate blocks are wiped when replaced or dropped:
+        let mut last: Zeroizing<Vec<u8>> = Zeroizing::new(first.finalize().into());
+        let mut sum = last.clone();
+
+        for _ in 1..iterations {
+            let mut next = hmac.authenticator();
+            next.update(&last);
+            last = Zeroizing::new(next.finalize().into());
+            sum.iter_mut().zip(last.iter()).for_each(|(a, b)| *a ^= b);
+        }
+        block.copy_from_slice(&sum[..block.len()]);
+    }
+    Ok(())
+}
+
+/// HMAC-based extract-and-expand key derivation function (HKDF) as specified by
+/// RFC 5869, built on a hash function of the SHA-2 family. Unlike
+/// [`Key::derive_from_password`], it is fast and must be used only with input
+/// key material that already has enough entropy, e.g. a randomly generated
+/// master [`Key`].
+///
+/// The extract step is done by [`new()`], which concentrates the entropy of the
+/// input key material into a pseudorandom key. The expand step is done by
+/// [`expand()`], which derives an independent key for every `info` label, so
+/// that one master key can give separate keys for encryption, authentication
+/// and other purposes.
+///
+/// Use type aliases such as [`Hkdf256`] rather than this struct directly.
+///
+/// ```
+/// use mdcrypt::Hkdf256;
+/// use mdcrypt::Key;
+///
+/// let master = Key::new(vec![0x0b; 32]);
+/// let hkdf = Hkdf256::new(b"salt", &master);
+/// let keys = hkdf.expand_all([&b"encryption"[..], b"mac"], 32).unwrap();
+/// assert_ne!(keys[0], keys[1]);
+/// ```
+///
+/// [`new()`]: Hkdf::new
+/// [`expand()`]: Hkdf::expand
+#[derive(Clone)]
+pub struct Hkdf<T>
+where T: Default + Clone + sha2::Digest + BlockSizeUser,
+{
+    /// HMAC keyed by the pseudorandom key.
+    hmac: Hmac<T>,
+    /// The pseudorandom key produced by the extract step.
+    prk: Key,
+}
+
+impl<T> Hkdf<T>
+where T: Default + Clone + sha2::Digest + BlockSizeUser,
+{
+    /// Performs the extract step.
+    ///
+    /// # Parameters
+    ///
+    /// - `salt`: optional non-secret random value; empty salt is the same as a
+    ///   salt of zeros with length of the hash output, as RFC 5869 specifies
+    /// - `ikm`: input key material
+    pub fn new(salt: &[u8], ikm: &Key) -> Self {
+        // HMAC pads the key with zeros to the block size, so a single zero byte
+        // is the same HMAC key as an empty salt or a salt of zeros:
+        let salt = match salt.is_empty() {
+            true  => Key::new(vec![0]),
+            false => Key::new(salt.to_vec()),
+        };
+        let prk: Key = Hmac::<T>::new(&salt).encrypt(ikm.iter().copied());
+        Self::from_prk(prk)
+    }
+
+    /// Skips the extract step and uses given key as the pseudorandom key. The key
+    /// should be at least as long as the hash output.
+    ///
+    /// # Parameters
+    ///
+    /// - `prk`: pseudorandom key, e.g. obtained earlier by [`prk()`](Hkdf::prk)
+    pub fn from_prk(prk: Key) -> Self {
+        Self { hmac: Hmac::new(&prk), prk }
+    }
+
+    /// Returns the pseudorandom key produced by the extract step.
+    pub fn prk(&self) -> &Key {
+        &self.prk
+    }
+
+    /// Returns the maximal length of a key derived by [`expand()`](Hkdf::expand)
+    /// in bytes, which is 255 times the length of the hash output.
+    pub fn max_key_len() -> usize {
+        255 * <T as sha2::Digest>::output_size()
+    }
+
+    /// Performs the expand step, deriving a key for given label.
+    ///
+    /// # Parameters
+    ///
+    /// - `info`: context and application specific label of the derived key;
+    ///   different labels give independent keys
+    /// - `key_len`: length of the derived key in bytes
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with the derived key
+    /// - [`Err`] with [`Error::InvalidParameters`] if `key_len` is zero or greater
+    ///   than [`max_key_len()`](Hkdf::max_key_len)
+    pub fn expand(&self, info: &[u8], key_len: usize) -> Result<Key, Error> {
+        if key_len == 0 || key_len > Self::max_key_len() {
+            return Err(Error::invalid_parameter(
+                "key_len",
+                format!("must be from 1 to {}", Self::max_key_len())
+            ))
+        }
+
+        // T(i) = HMAC(PRK, T(i - 1) | info | i), where T(0) is empty:
+        let mut derived = Vec::with_capacity(key_len);
+        // T(i - 1) is wiped when replaced or dropped:
+        let mut previous: Zeroizing<Vec<u8>> = Zeroizing::new(Vec::new());
+        for counter in 1..=u8::MAX {
+            let mut hmac = self.hmac.authenticator();
+            hmac.update(&previous);
+            hmac.update(info);
+            hmac.update(&[counter]);
+            previous = Zeroizing::new(hmac.finalize().into());
+
+            let missing = key_len - derived.len();
+            derived.extend(previous.iter().take(missing));
+            if derived.len() == key_len {
+                break
+            }
+        }
+        Ok(Key::new(derived))
+    }
+
+    /// Performs the expand step for every given label, see
+    /// [`expand()`](Hkdf::expand).
+    ///
+    /// # Parameters
+    ///
+    /// - `infos`: labels of the derived keys
+    /// - `key_len`: length of every derived key in bytes
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with the derived keys in the order of the labels
+    /// - [`Err`] with [`Error::InvalidParameters`] if `key_len` is invalid
+    pub fn expand_all<'a, I>(&self, infos: I, key_len: usize) -> Result<Vec<Key>, Error>
+    where I: IntoIterator<Item = &'a [u8]>,
+    {
+        infos.into_iter().map(|info| self.expand(info, key_len)).collect()
+    }
+}
+
+/// HKDF with HMAC-SHA-256.
+pub type Hkdf256 = Hkdf<sha2::Sha256>;
+
+/// HKDF with HMAC-SHA-512.
+pub type Hkdf512 = Hkdf<sha2::Sha512>;
+
+fn parse_number<N: FromStr>(text: &str, name: &str) -> Result<N, Error> {
+    text.trim().parse().map_err(|_| Error::invalid_parameter(
+        name,
+        format!("`{}` is not a valid number", text)
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::hex;
+
+    #[test]
+    fn pbkdf2_known_answers() {
+        // RFC 7914, section 11:
+        let params = KdfParams::Pbkdf2Sha256 { iterations: 1, key_len: 64 };
+        let key = Key::derive_from_password(b"passwd", b"salt", &params).unwrap();
+        assert_eq!(key.iter().copied().collect::<Vec<u8>>(), hex(concat!(
+            "55ac046e56e3089fec1691c22544b605f94185216dde0465e68b9d57c20dacbc",
+            "49ca9cccf179b645991664b39d77ef317c71b845b1e30bd509112041d3a19783"
+        )));
+
+        let params = KdfParams::Pbkdf2Sha512 { iterations: 1000, key_len: 64 };
+        let key = Key::derive_from_password(b"password", b"NaCl", &params).unwrap();
+        assert_eq!(key.iter().copied().collect::<Vec<u8>>(), hex(concat!(
+            "6d3d85ab0b2085ce9b31b67375179001", "70be315ba6ed538e6f924b3588c66e97",
+            "63210c9873672c557f4bbf5b967fdca3", "d63f275e7bf95caf123dba674de3bf4b"
+        )));
+    }
+
+    #[test]
+    fn scrypt_known_answer() {
+        // RFC 7914, section 12:
+        let params = KdfParams::Scrypt { log_n: 4, r: 1, p: 1, key_len: 64 };
+        let key = Key::derive_from_password(b"", b"", &params).unwrap();
+        assert_eq!(key.iter().copied().collect::<Vec<u8>>(), hex(concat!(
+            "77d6576238657b203b19ca42c18a0497f16b4844e3074ae8dfdffa3fede21442",
+            "fcd0069ded0948f8326a753a0fc81f17e8d3e0fb2e0d3628cf35e20c38d18906"
+        )));
+    }
+
+    #[test]
+    fn argon2id_known_answer() {
+        let params = KdfParams::Argon2id {
+            memory_kib: 64, iterations: 3, parallelism: 1, key_len: 32
+        };
+        let key = Key::derive_from_password(b"password", b"somesalt", &params).unwrap();
+        assert_eq!(
+            key.iter().copied().collect::<Vec<u8>>(),
+            hex("b9e3bfda58fb95f1e1e479af4e01bfade5a0dba34d400a254f770462674169e6")
+        );
+    }
+
+    #[test]
+    fn params_to_string_and_back() {
+        let all = [
+            KdfParams::Pbkdf2Sha256 { iterations: 600_000, key_len: 32 },
+            KdfParams::Pbkdf2Sha512 { iterations: 210_000, key_len: 64 },
+            KdfParams::Scrypt { log_n: 17, r: 8, p: 1, key_len: 16 },
+            KdfParams::default(),
+        ];
+        for params in all {
+            assert_eq!(params.to_string().parse::<KdfParams>().unwrap(), params);
+        }
+        assert_eq!(KdfParams::default().to_string(), "argon2id:19456:2:1:32");
+    }
+
+    #[test]
+    fn invalid_params() {
+        assert!(matches!("bcrypt:10".parse::<KdfParams>(), Err(Error::Unsupported { .. })));
+        assert!(matches!(
+            "scrypt:15:8:1".parse::<KdfParams>(),
+            Err(Error::InvalidParameters { .. })
+        ));
+        assert!(matches!(
+            "pbkdf2-sha256:many:32".parse::<KdfParams>(),
+            Err(Error::InvalidParameters { parameter, .. }) if parameter == "iterations"
+        ));
+
+        let derive = |params| Key::derive_from_password(b"password", b"somesalt", &params);
+        assert!(derive(KdfParams::Pbkdf2Sha256 { iterations: 0, key_len: 32 }).is_err());
+        assert!(derive(KdfParams::Pbkdf2Sha256 { iterations: 1, key_len: 0 }).is_err());
+        assert!(derive(KdfParams::Scrypt { log_n: 4, r: 0, p: 1, key_len: 32 }).is_err());
+        // Argon2 requires at least 8 bytes of salt:
+        let params = KdfParams::Argon2id {
+            memory_kib: 64, iterations: 1, parallelism: 1, key_len: 32
+        };
+        assert!(Key::derive_from_password(b"password", b"salt", &params).is_err());
+    }
+
+    #[test]
+    fn params_beyond_limits() {
+        let too_costly = [
+            "pbkdf2-sha256:1:18446744073709551615",
+            "pbkdf2-sha512:1:65",
+            "pbkdf2-sha256:4294967295:32",
+            "scrypt:255:8:1:32",
+            "scrypt:21:8:1:32",
+            "scrypt:10:4294967295:1:32",
+            "scrypt:10:8:4294967295:32",
+            "argon2id:4294967295:2:1:32",
+            "argon2id:19456:4294967295:1:32",
+            "argon2id:19456:2:4294967295:32",
+        ];
+        for text in too_costly {
+            assert!(matches!(text.parse::<KdfParams>(), Err(Error::InvalidParameters { .. })));
+        }
+        assert!("scrypt:20:8:1:64".parse::<KdfParams>().is_ok());
+        assert!("argon2id:1048576:100:64:64".parse::<KdfParams>().is_ok());
+
+        // Parameters constructed directly are checked as well:
+        let params = KdfParams::Pbkdf2Sha256 { iterations: 1, key_len: usize::MAX };
+        assert!(matches!(
+            Key::derive_from_password(b"password", b"salt", &params),
+            Err(Error::InvalidParameters { parameter, .. }) if parameter == "key_len"
+        ));
+    }
+
+    #[test]
+    fn hkdf_known_answers() {
+        // RFC 5869, test case 1:
+        let ikm = Key::new(vec![0x0b; 22]);
+        let hkdf = Hkdf256::new(&hex("000102030405060708090a0b0c"), &ikm);
+        assert_eq!(
+            hkdf.prk().iter().copied().collect::<Vec<u8>>(),
+            hex("077709362c2e32df0ddc3f0dc47bba6390b6c73bb50f9c3122ec844ad7c2b3e5")
+        );
+        let okm = hkdf.expand(&hex("f0f1f2f3f4f5f6f7f8f9"), 42).unwrap();
+        assert_eq!(okm.iter().copied().collect::<Vec<u8>>(), hex(concat!(
+            "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf",
+            "34007208d5b887185865"
+        )));
+
+        // RFC 5869, test case 3 with empty salt and info:
+        let okm = Hkdf256::new(b"", &ikm).expand(b"", 42).unwrap();
+        assert_eq!(okm.iter().copied().collect::<Vec<u8>>(), hex(concat!(
+            "8da4e775a563c18f715f802a063c5a31b8a11f5c5ee1879ec3454e5f3c738d2d",
+            "9d201395faa4b61a96c8"
+        )));
+    }
+
+    #[test]
+    fn hkdf_labels() {
+        let hkdf = Hkdf512::new(b"salt", &Key::new(vec![42; 64]));
+        let keys = hkdf.expand_all([&b"encryption"[..], b"mac", b"ecc"], 32).unwrap();
+        assert_eq!(keys.len(), 3);
+        assert!(keys.iter().all(|key| key.len() == 32));
+        assert_ne!(keys[0], keys[1]);
+        assert_ne!(keys[1], keys[2]);
+        // Shorter key is a prefix of the longer one with the same label:
+        let short = hkdf.expand(b"mac", 16).unwrap();
+        assert!(short.iter().eq(keys[1].iter().take(16)));
+        // Extract step can be skipped with a stored pseudorandom key:
+        let restored = Hkdf512::from_prk(hkdf.prk().clone());
+        assert_eq!(restored.expand(b"ecc", 32).unwrap(), keys[2]);
+
+        assert!(hkdf.expand(b"", 0).is_err());
+        assert!(hkdf.expand(b"", Hkdf512::max_key_len()).is_ok());
+        assert!(hkdf.expand(b"", Hkdf512::max_key_len() + 1).is_err());
+    }
+}
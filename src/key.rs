@@ -9,13 +9,44 @@ use std::ops::BitXor;
 use std::ops::Index;
 use std::ops::Not;
 use std::slice;
+use std::str::FromStr;
+#[cfg(all(feature = "mlock", unix))]
+use std::collections::BTreeMap;
+#[cfg(all(feature = "mlock", unix))]
+use std::sync::Mutex;
+#[cfg(all(feature = "mlock", unix))]
+use std::sync::PoisonError;
 
 use rand::Rng;
-
+use subtle::ConstantTimeEq;
+use zeroize::Zeroize;
+use zeroize::Zeroizing;
+
+use crate::algorithms::Base16;
+use crate::algorithms::Base64;
+use crate::decrypt::TryDecrypt;
+use crate::error::Error;
+use crate::key_file::ArmoredKey;
+
+/// Secret key used by the algorithms of this crate.
+///
+/// The key takes care of the secret it holds:
+///
+///  -  its bytes are overwritten by zeros when the key is dropped
+///  -  comparison by `==` takes the same time no matter how many bytes match
+///  -  [`Debug`](fmt::Debug) and [`Display`](fmt::Display) print only the length
+///     of the key; use [`reveal()`](Key::reveal) or hexadecimal formatting such
+///     as `{:x}` to get the secret itself
+///  -  with `mlock` feature enabled, memory of the key is locked on Unix systems,
+///     so that the secret is never swapped to disk
+///
+/// Every clone of the key is protected in the same way.
 pub struct Key {
     data: Vec<u8>,
 }
 
+// A key is never empty, so it has no `is_empty()`:
+#[allow(clippy::len_without_is_empty)]
 impl Key {
     /// Creates a [`Key`](Key) instance from [`Vec`](Vec) of [`u8`](u8)s.
     ///
@@ -24,10 +55,14 @@ impl Key {
     /// - if length of given vector is zero
     pub fn new(data: Vec<u8>) -> Self {
         // Key length must not be zero
-        assert!(data.len() > 0, "Length of the key must be non-zero");
+        assert!(!data.is_empty(), "Length of the key must be non-zero");
+
+        // Keep the secret out of swap if requested:
+        #[cfg(all(feature = "mlock", unix))]
+        lock(&data);
 
         // Create a new instance
-        Self { data: data }
+        Self { data }
     }
 
     /// Generates [`Key`](Key) instance with specified length, consisting of random
@@ -39,18 +74,14 @@ impl Key {
     /// - `rng`: random number generator
     pub fn random<R: Rng + ?Sized>(key_len: NonZeroUsize, rng: &mut R) -> Self {
 
-        // Create a vector with given capacity and fill the vector with zeros:
-        let mut data: Vec<u8> = Vec::with_capacity(key_len.into());
-        data.fill(0);
+        // Create a key of zeros with given length, so that its memory is locked
+        // before the secret is written there:
+        let mut key = Self::new(vec![0; key_len.into()]);
 
-        // Create a mutable slice
-        let slice = &mut data[..];
+        // Use random number generator (RNG) to fill the key
+        rng.fill_bytes(&mut key.data);
 
-        // Use random number generator (RNG) to fill our vector through the slice
-        rng.fill_bytes(slice);
-
-        // Create an instance
-        Self { data }
+        key
     }
 
     /// Returns length of the key in bytes.
@@ -65,43 +96,135 @@ impl Key {
 
     /// Returns an iterator that does not consume the [`Key`](Key) instance itself.
     pub fn iter(&self) -> slice::Iter<'_, u8> {
-        (&self).into_iter()
+        self.into_iter()
+    }
+
+    /// Parses a key written as hexadecimal digits, e.g. by `{:x}` or `{:X}`.
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with the parsed key
+    /// - [`Err`] with [`Error::InvalidKey`] if the text is empty, has odd length
+    ///   or contains a character other than a hexadecimal digit
+    pub fn from_hex(hex: &str) -> Result<Self, Error> {
+        if hex.is_empty() {
+            return Err(Error::InvalidKey { reason: "key must not be empty".into() })
+        }
+        let data: Vec<u8> = Base16::new().try_decrypt(hex.bytes()).map_err(invalid_key)?;
+        Ok(Self::new(data))
+    }
+
+    /// Parses a key written in the standard Base64 with padding (RFC 4648,
+    /// section 4), e.g. by [`to_base64()`](Key::to_base64).
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with the parsed key
+    /// - [`Err`] with [`Error::InvalidKey`] if the text is empty or is not
+    ///   a valid Base64
+    pub fn from_base64(text: &str) -> Result<Self, Error> {
+        let data: Vec<u8> = Base64::default().try_decrypt(text.bytes()).map_err(invalid_key)?;
+        if data.is_empty() {
+            return Err(Error::InvalidKey { reason: "key must not be empty".into() })
+        }
+        Ok(Self::new(data))
+    }
+
+    /// Returns the key written in the standard Base64 with padding. Like
+    /// [`reveal()`](Key::reveal), this exposes the secret.
+    pub fn to_base64(&self) -> String {
+        Base64::default().encode_to_string(&self.data)
+    }
+
+    /// Returns bytes of the key. This is the explicit way to get the secret, since
+    /// [`Debug`](fmt::Debug) and [`Display`](fmt::Display) do not print it.
+    pub fn reveal(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+impl Drop for Key {
+    fn drop(&mut self) {
+        // Wipe the secret before the memory is returned to the allocator:
+        self.data.as_mut_slice().zeroize();
+
+        #[cfg(all(feature = "mlock", unix))]
+        unlock(&self.data);
     }
 }
 
 impl PartialEq for Key {
+    /// Compares the keys in constant time, so that the time does not reveal how
+    /// many bytes match. Only the lengths of the keys may leak.
     fn eq(&self, other: &Self) -> bool {
-        // Lengths must be the same...
-        self.len() == other.len() &&
-        // ...and all bytes must be equal
-        self.into_iter()
-            // zip with the other key's iterator
-            .zip(other.into_iter())
-            // look for bytes that do not match
-            .filter(|(a, b)| a != b)
-            // we should find no bytes that do not match if the keys are the same
-            .next().is_none()
+        self.data.ct_eq(&other.data).into()
     }
 }
 
 impl Eq for Key {}
 
+impl FromStr for Key {
+    type Err = Error;
+
+    /// Parses the key from text in one of the following formats:
+    ///
+    ///  -  `hex:<digits>` or just `<digits>`: see [`Key::from_hex`]
+    ///  -  `base64:<text>`: see [`Key::from_base64`]
+    ///  -  armored key starting by `-----BEGIN MDCRYPT KEY-----`: see
+    ///     [`ArmoredKey`]; its headers are ignored
+    ///
+    /// Leading and trailing whitespace is ignored.
+    fn from_str(text: &str) -> Result<Self, Error> {
+        let text = text.trim();
+        if text.starts_with("-----BEGIN") {
+            return Ok(text.parse::<ArmoredKey>()?.into_key())
+        }
+        match text.split_once(':') {
+            Some(("hex", hex))       => Self::from_hex(hex),
+            Some(("base64", base64)) => Self::from_base64(base64),
+            Some((prefix, _))        => Err(Error::InvalidKey {
+                reason: format!("unknown key format `{}`", prefix)
+            }),
+            None                     => Self::from_hex(text),
+        }
+    }
+}
+
+impl TryFrom<&str> for Key {
+    type Error = Error;
+
+    /// Parses the key in the same way as [`FromStr`] implementation.
+    fn try_from(text: &str) -> Result<Self, Error> {
+        text.parse()
+    }
+}
+
 impl FromIterator<u8> for Key {
     /// Constructs the key from an iterable object of [`u8`]s. If iterable object
     /// gives no element, this method will panic.
     fn from_iter<T: IntoIterator<Item = u8>>(iter: T) -> Self {
-
-        // Collect everything into a `Vec`:
-        let data: Vec<u8> = iter.into_iter().collect();
+        let iter = iter.into_iter();
+
+        // Collect everything into a `Vec`. It is grown here rather than by `push`,
+        // so that every buffer left behind is wiped:
+        let mut data = Zeroizing::new(Vec::with_capacity(iter.size_hint().0));
+        for byte in iter {
+            if data.len() == data.capacity() {
+                let mut grown = Vec::with_capacity((2 * data.capacity()).max(32));
+                grown.extend_from_slice(&data);
+                drop(Zeroizing::new(std::mem::replace(&mut *data, grown)));
+            }
+            data.push(byte);
+        }
 
         // Create a new instance
-        Self::new(data)
+        Self::new(std::mem::take(&mut *data))
     }
 }
 
 impl IntoIterator for Key {
     type Item = u8;
-    type IntoIter = std::vec::IntoIter<u8>;
+    type IntoIter = KeyIntoIter;
 
     /// Returns an iterator over bytes of the key. This will consume the [`Key`](Key)
     /// instance.
@@ -111,10 +234,35 @@ impl IntoIterator for Key {
     /// An iterator over [`u8`](u8); each [`u8`](u8) represents a single byte from
     /// the key.
     fn into_iter(self) -> Self::IntoIter {
-        self.data.into_iter()
+        KeyIntoIter { key: self, position: 0 }
     }
 }
 
+/// Iterator over bytes of a [`Key`] returned by its
+/// [`into_iter()`](IntoIterator::into_iter). The iterator owns the key, so the
+/// secret is wiped once the iterator is dropped.
+pub struct KeyIntoIter {
+    key: Key,
+    position: usize,
+}
+
+impl Iterator for KeyIntoIter {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        let byte = self.key.data.get(self.position).copied();
+        self.position += byte.is_some() as usize;
+        byte
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.key.len() - self.position;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for KeyIntoIter {}
+
 impl<'a> IntoIterator for &'a Key {
     type Item = &'a u8;
     type IntoIter = slice::Iter<'a, u8>;
@@ -132,17 +280,19 @@ impl<'a> IntoIterator for &'a Key {
 }
 
 impl Clone for Key {
+    /// Copies the secret into a new key, which is wiped on drop (and locked in
+    /// memory) in the same way as the original.
     fn clone(&self) -> Self {
 
         // Clone our data
         let data_clone = self.data.clone();
 
         // Construct a new instance from cloned data
-        Self { data: data_clone }
+        Self::new(data_clone)
     }
 }
 
-impl<'a> Add for &'a Key {
+impl Add for &Key {
     ///
     /// The output of addition (`+`) operator is a new [`Key`](Key) instance.
     ///
@@ -156,7 +306,7 @@ impl<'a> Add for &'a Key {
         self.data
             .iter()
             .chain(other.data.iter())
-            .map(|&v| v) // <== Dereference
+            .copied()
             .collect()
     }
 }
@@ -182,7 +332,7 @@ where
 // Bitwise operator implementation
 //===================================================================================
 
-impl<'a> Not for &'a Key {
+impl Not for &Key {
     ///
     /// The output type of the unary `!` operator is a new [`Key`] instance.
     ///
@@ -202,7 +352,7 @@ impl<'a> Not for &'a Key {
     }
 }
 
-impl<'a> BitAnd for &'a Key {
+impl BitAnd for &Key {
     ///
     /// The output type of the binary `&` operator is a new [`Key`] instance.
     ///
@@ -228,7 +378,7 @@ impl<'a> BitAnd for &'a Key {
     }
 }
 
-impl<'a> BitOr for &'a Key {
+impl BitOr for &Key {
     ///
     /// The output type of the binary `|` operator is a new [`Key`] instance.
     ///
@@ -254,7 +404,7 @@ impl<'a> BitOr for &'a Key {
     }
 }
 
-impl<'a> BitXor for &'a Key {
+impl BitXor for &Key {
     ///
     /// The output type of the binary `^` operator is a new [`Key`] instance.
     ///
@@ -320,16 +470,20 @@ impl fmt::Binary for Key {
 }
 
 impl fmt::Display for Key {
+    /// Prints only the length of the key, so that the secret does not end up in
+    /// logs by accident. Use `{:x}` to print the secret itself.
     fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
-        // Use `LowerHex` trait implementation
-        fmt::LowerHex::fmt(&self, formatter)
+        write!(formatter, "<redacted {}-bit key>", self.len_bits())
     }
 }
 
 impl fmt::Debug for Key {
+    /// Prints only the length of the key, so that the secret does not end up in
+    /// logs or panic messages by accident.
     fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
-        // Use `LowerHex` trait implementation
-        fmt::LowerHex::fmt(&self, formatter)
+        formatter.debug_struct("Key")
+            .field("len", &self.len())
+            .finish_non_exhaustive()
     }
 }
 
@@ -340,3 +494,142 @@ impl fmt::Pointer for Key {
         fmt::Pointer::fmt(&ptr, formatter)
     }
 }
+
+/// Reports a key which cannot be decoded as [`Error::InvalidKey`]. The reason
+/// comes from [`Error::InvalidEncoding`], so it does not quote the key either.
+fn invalid_key(error: Error) -> Error {
+    Error::InvalidKey { reason: error.to_string() }
+}
+
+// Memory locking implementation
+//===================================================================================
+
+/// Number of keys using each locked page, by address of the page. Locks do not
+/// nest, so a page shared by several keys is unlocked only when the last of them
+/// is dropped.
+#[cfg(all(feature = "mlock", unix))]
+static LOCKED_PAGES: Mutex<BTreeMap<usize, usize>> = Mutex::new(BTreeMap::new());
+
+/// Returns size of a memory page and addresses of pages holding given bytes.
+#[cfg(all(feature = "mlock", unix))]
+fn pages(data: &[u8]) -> (usize, impl Iterator<Item = usize>) {
+    // SAFETY: sysconf has no preconditions
+    let page_len = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize;
+    let start = data.as_ptr() as usize / page_len * page_len;
+    let end = data.as_ptr() as usize + data.len();
+    (page_len, (start..end).step_by(page_len))
+}
+
+/// Locks pages holding given bytes in memory. Failure is ignored, since the
+/// limit of locked memory can be low and the key is still usable without it.
+#[cfg(all(feature = "mlock", unix))]
+fn lock(data: &[u8]) {
+    let mut locked_pages = LOCKED_PAGES.lock().unwrap_or_else(PoisonError::into_inner);
+    let (page_len, pages) = pages(data);
+    for page in pages {
+        let users = locked_pages.entry(page).or_insert(0);
+        if *users == 0 {
+            // SAFETY: the page holds a part of a valid allocation
+            unsafe { libc::mlock(page as *const libc::c_void, page_len) };
+        }
+        *users += 1;
+    }
+}
+
+/// Unlocks pages locked by [`lock()`], unless another key still uses them.
+#[cfg(all(feature = "mlock", unix))]
+fn unlock(data: &[u8]) {
+    let mut locked_pages = LOCKED_PAGES.lock().unwrap_or_else(PoisonError::into_inner);
+    let (page_len, pages) = pages(data);
+    for page in pages {
+        let Some(users) = locked_pages.get_mut(&page) else { continue };
+        *users -= 1;
+        if *users == 0 {
+            locked_pages.remove(&page);
+            // SAFETY: the page holds a part of a valid allocation
+            unsafe { libc::munlock(page as *const libc::c_void, page_len) };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn random() {
+        let key = Key::random(NonZeroUsize::new(32).unwrap(), &mut rand::thread_rng());
+        assert_eq!(key.len(), 32);
+        assert_ne!(key, Key::new(vec![0; 32]));
+    }
+
+    #[test]
+    fn equality() {
+        let key = Key::new(vec![1, 2, 3]);
+        assert_eq!(key, key.clone());
+        assert_ne!(key, Key::new(vec![1, 2, 4]));
+        assert_ne!(key, Key::new(vec![1, 2]));
+    }
+
+    #[test]
+    fn redacted() {
+        let key = Key::new(vec![0xab, 0xcd]);
+        assert_eq!(format!("{}", key), "<redacted 16-bit key>");
+        assert_eq!(format!("{:?}", key), "Key { len: 2, .. }");
+        assert_eq!(format!("{:x}", key), "abcd");
+        assert_eq!(key.reveal(), &[0xab, 0xcd]);
+    }
+
+    #[test]
+    fn parse() {
+        let key = Key::new(vec![0x00, 0xab, 0xcd, 0xef]);
+        for text in ["00abcdef", "00ABCDEF", " hex:00abcdef\n", "base64:AKvN7w=="] {
+            assert_eq!(text.parse::<Key>().unwrap(), key, "{}", text);
+        }
+        assert_eq!(Key::try_from(format!("{:X}", key).as_str()).unwrap(), key);
+        assert_eq!(Key::from_base64(&key.to_base64()).unwrap(), key);
+
+        for text in ["", "abc", "0g", "hex:", "base64:", "base64:AKvN7w", "rot13:00"] {
+            assert!(matches!(text.parse::<Key>(), Err(Error::InvalidKey { .. })), "{}", text);
+        }
+        // Signs are not digits, even though `u8::from_str_radix` accepts them:
+        for text in ["+f+f", "+0+1", "hex:+a", "-1", "0+"] {
+            assert!(matches!(text.parse::<Key>(), Err(Error::InvalidKey { .. })), "{}", text);
+        }
+        assert!(matches!(
+            Key::from_hex("00+1"),
+            Err(Error::InvalidKey { reason }) if reason.ends_with("position 2")
+        ));
+    }
+
+    #[test]
+    fn into_iter() {
+        let iter = Key::new(vec![1, 2, 3]).into_iter();
+        assert_eq!(iter.len(), 3);
+        assert_eq!(iter.collect::<Vec<u8>>(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn from_iter() {
+        // The iterator does not know its length, so the buffer has to grow:
+        let key: Key = (0..=255).filter(|byte| byte % 3 == 0).collect();
+        assert_eq!(key.reveal(), (0..=255).step_by(3).collect::<Vec<u8>>());
+        assert_eq!((&key ^ &key).reveal(), vec![0; 86]);
+        assert_eq!((&key + &!&key).len(), 172);
+    }
+
+    #[cfg(all(feature = "mlock", unix))]
+    #[test]
+    fn shared_pages_stay_locked() {
+        let is_locked = |key: &Key| {
+            let locked_pages = LOCKED_PAGES.lock().unwrap();
+            pages(key.reveal()).1.all(|page| locked_pages.contains_key(&page))
+        };
+        // Small keys are likely allocated in the same page:
+        let first = Key::new(vec![1; 16]);
+        let second = Key::new(vec![2; 16]);
+        assert!(is_locked(&first) && is_locked(&second));
+        drop(second);
+        assert!(is_locked(&first));
+    }
+}
@@ -0,0 +1,294 @@
+use std::fmt;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::str::FromStr;
+
+use zeroize::Zeroizing;
+
+use crate::error::Error;
+use crate::key::Key;
+
+/// First line of an armored key.
+const BEGIN_LINE: &str = "-----BEGIN MDCRYPT KEY-----";
+
+/// Last line of an armored key.
+const END_LINE: &str = "-----END MDCRYPT KEY-----";
+
+/// Number of Base64 characters on a line of an armored key.
+const LINE_LEN: usize = 64;
+
+/// [`Key`] together with optional headers describing it, which can be written as
+/// text suitable for files and copy-pasting:
+///
+/// ```text
+/// -----BEGIN MDCRYPT KEY-----
+/// Algorithm: vigener
+/// Purpose: encryption
+///
+/// AKvN7w==
+/// -----END MDCRYPT KEY-----
+/// ```
+///
+/// Headers are lines `Name: value` followed by an empty line, which can be
+/// omitted when there are no headers. The key itself is written in the standard
+/// Base64 with padding, split into lines of 64 characters. Unknown headers are
+/// ignored when parsing.
+///
+/// Use [`Display`](fmt::Display) implementation to write the armored key and
+/// [`FromStr`] implementation to parse it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ArmoredKey {
+    key: Key,
+    algorithm: Option<String>,
+    purpose: Option<String>,
+}
+
+impl ArmoredKey {
+
+    /// Creates an armored key with no headers.
+    pub fn new(key: Key) -> Self {
+        Self { key, algorithm: None, purpose: None }
+    }
+
+    /// Sets `Algorithm` header naming the algorithm the key is meant for, e.g.
+    /// a specification accepted by [`Registry`](crate::Registry).
+    ///
+    /// # Panics
+    ///
+    /// - if the value contains a line break
+    pub fn with_algorithm<S: Into<String>>(mut self, algorithm: S) -> Self {
+        self.algorithm = Some(header_value(algorithm.into()));
+        self
+    }
+
+    /// Sets `Purpose` header describing what the key is used for, e.g.
+    /// `encryption` or `authentication`.
+    ///
+    /// # Panics
+    ///
+    /// - if the value contains a line break
+    pub fn with_purpose<S: Into<String>>(mut self, purpose: S) -> Self {
+        self.purpose = Some(header_value(purpose.into()));
+        self
+    }
+
+    /// Returns the key.
+    pub fn key(&self) -> &Key {
+        &self.key
+    }
+
+    /// Returns the key, dropping the headers.
+    pub fn into_key(self) -> Key {
+        self.key
+    }
+
+    /// Returns value of `Algorithm` header, if present.
+    pub fn algorithm(&self) -> Option<&str> {
+        self.algorithm.as_deref()
+    }
+
+    /// Returns value of `Purpose` header, if present.
+    pub fn purpose(&self) -> Option<&str> {
+        self.purpose.as_deref()
+    }
+
+    /// Reads an armored key from a file.
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with the armored key
+    /// - [`Err`] with [`Error::Io`] if the file cannot be read, or with the error
+    ///   of [`FromStr`] implementation if it does not contain an armored key
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let text = Zeroizing::new(fs::read_to_string(path)?);
+        text.parse()
+    }
+
+    /// Writes the armored key into a file, replacing its content. On Unix, the
+    /// file is made readable and writable by its owner only before the key is
+    /// written, even if it already existed.
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] if the key has been written
+    /// - [`Err`] with [`Error::Io`] otherwise
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options.open(path)?;
+
+        // Mode given above applies only to a new file, so restrict an existing
+        // one as well before its content is replaced:
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            file.set_permissions(fs::Permissions::from_mode(0o600))?;
+        }
+        file.set_len(0)?;
+
+        let text = Zeroizing::new(self.to_string());
+        file.write_all(text.as_bytes())?;
+        file.sync_all()?;
+        Ok(())
+    }
+}
+
+impl fmt::Display for ArmoredKey {
+    /// Writes the armored key, including the secret, ending with a line break.
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(formatter, "{}", BEGIN_LINE)?;
+        if let Some(algorithm) = &self.algorithm {
+            writeln!(formatter, "Algorithm: {}", algorithm)?;
+        }
+        if let Some(purpose) = &self.purpose {
+            writeln!(formatter, "Purpose: {}", purpose)?;
+        }
+        if self.algorithm.is_some() || self.purpose.is_some() {
+            writeln!(formatter)?;
+        }
+
+        let encoded = Zeroizing::new(self.key.to_base64());
+        for line in encoded.as_bytes().chunks(LINE_LEN) {
+            // Base64 consists of ASCII characters only:
+            writeln!(formatter, "{}", std::str::from_utf8(line).unwrap())?;
+        }
+        writeln!(formatter, "{}", END_LINE)
+    }
+}
+
+impl FromStr for ArmoredKey {
+    type Err = Error;
+
+    /// Parses the armored key. Leading and trailing whitespace of the text and of
+    /// every line is ignored.
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with the armored key
+    /// - [`Err`] with [`Error::Malformed`] if the text has wrong structure, or
+    ///   with [`Error::InvalidKey`] if the key itself is not a valid Base64
+    fn from_str(text: &str) -> Result<Self, Error> {
+        let mut lines = text.trim().lines().map(str::trim);
+        if lines.next() != Some(BEGIN_LINE) {
+            return Err(Error::malformed(format!("expected `{}`", BEGIN_LINE)))
+        }
+        let lines: Vec<&str> = lines.collect();
+        let (end_line, lines) = lines.split_last()
+            .ok_or_else(|| Error::malformed(format!("expected `{}`", END_LINE)))?;
+        if *end_line != END_LINE {
+            return Err(Error::malformed(format!("expected `{}`", END_LINE)))
+        }
+
+        // Headers are present only if they are separated by an empty line:
+        let (headers, body) = match lines.iter().position(|line| line.is_empty()) {
+            Some(idx) => (&lines[..idx], &lines[idx + 1..]),
+            None      => (&[][..], lines),
+        };
+
+        let mut armored_key = Self { key: Key::new(vec![0]), algorithm: None, purpose: None };
+        for header in headers {
+            let (name, value) = header.split_once(':')
+                .ok_or_else(|| Error::malformed(format!("invalid header `{}`", header)))?;
+            match name.trim().to_lowercase().as_str() {
+                "algorithm" => armored_key.algorithm = Some(value.trim().to_string()),
+                "purpose"   => armored_key.purpose = Some(value.trim().to_string()),
+                _           => {},
+            }
+        }
+
+        let encoded = Zeroizing::new(body.concat());
+        armored_key.key = Key::from_base64(&encoded)?;
+        Ok(armored_key)
+    }
+}
+
+impl Key {
+
+    /// Reads a key from a file. The file can contain the key in any format
+    /// accepted by [`FromStr`] implementation of [`Key`], including an armored
+    /// key, whose headers are ignored. Use [`ArmoredKey::load`] to read them.
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with the key
+    /// - [`Err`] with [`Error::Io`] if the file cannot be read, or with the error
+    ///   of [`FromStr`] implementation if it does not contain a key
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let text = Zeroizing::new(fs::read_to_string(path)?);
+        text.parse()
+    }
+
+    /// Writes the key into a file as an [`ArmoredKey`] without headers. See
+    /// [`ArmoredKey::save`] for more information.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        ArmoredKey::new(self.clone()).save(path)
+    }
+}
+
+/// Trims value of a header and checks it fits on a single line.
+fn header_value(value: String) -> String {
+    assert!(!value.contains(['\r', '\n']), "Header value must not contain a line break");
+    value.trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_string_and_back() {
+        let key = Key::new((0..100).collect());
+        let armored = ArmoredKey::new(key.clone())
+            .with_algorithm("vigener")
+            .with_purpose("encryption");
+        let text = armored.to_string();
+        assert!(text.starts_with("-----BEGIN MDCRYPT KEY-----\nAlgorithm: vigener\n"));
+        assert!(text.lines().all(|line| line.len() <= LINE_LEN));
+        assert_eq!(text.parse::<ArmoredKey>().unwrap(), armored);
+        assert_eq!(text.parse::<Key>().unwrap(), key);
+
+        let bare = ArmoredKey::new(key);
+        assert_eq!(bare.to_string().parse::<ArmoredKey>().unwrap(), bare);
+    }
+
+    #[test]
+    fn parse_err() {
+        let valid = "-----BEGIN MDCRYPT KEY-----\nPurpose: mac\n\nAKvN7w==\n-----END MDCRYPT KEY-----";
+        assert_eq!(valid.parse::<ArmoredKey>().unwrap().purpose(), Some("mac"));
+
+        for text in [
+            "AKvN7w==",
+            "-----BEGIN MDCRYPT KEY-----\nAKvN7w==",
+            "-----BEGIN MDCRYPT KEY-----\nPurpose mac\n\nAKvN7w==\n-----END MDCRYPT KEY-----",
+        ] {
+            assert!(matches!(text.parse::<ArmoredKey>(), Err(Error::Malformed { .. })), "{}", text);
+        }
+        let text = "-----BEGIN MDCRYPT KEY-----\nAKvN7w=\n-----END MDCRYPT KEY-----";
+        assert!(matches!(text.parse::<ArmoredKey>(), Err(Error::InvalidKey { .. })));
+    }
+
+    #[test]
+    fn save_and_load() {
+        let path = std::env::temp_dir().join(format!("mdcrypt-key-{}", std::process::id()));
+        let key = Key::random(32.try_into().unwrap(), &mut rand::thread_rng());
+        key.save(&path).unwrap();
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        let loaded = Key::load(&path);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.unwrap(), key);
+
+        assert!(matches!(Key::load(&path), Err(Error::Io(_))));
+    }
+}
@@ -4,6 +4,25 @@
 /// correction code and other.
 pub mod algorithms;
 
+/// Module for cryptanalysis of the classical ciphers from [`algorithms`], such as
+/// recovering the key of [`Vigener`](algorithms::Vigener) from encrypted data
+/// only. Useful for teaching and CTF challenges.
+pub mod analysis;
+
+mod chain;          pub use chain::*;
+mod container;      pub use container::*;
 mod decrypt;        pub use decrypt::*;
+mod digest;         pub use digest::*;
+mod dynamic;        pub use dynamic::*;
 mod encrypt;        pub use encrypt::*;
-mod key;            pub use key::*;
\ No newline at end of file
+mod error;          pub use error::*;
+mod gf256;
+mod kdf;            pub use kdf::*;
+mod key;            pub use key::*;
+mod key_file;       pub use key_file::*;
+mod registry;       pub use registry::*;
+mod shamir;         pub use shamir::*;
+mod stream;         pub use stream::*;
+
+#[cfg(test)]
+mod test_util;
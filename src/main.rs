@@ -0,0 +1,489 @@
+use std::fs;
+use std::io;
+use std::io::BufRead;
+use std::io::Read;
+use std::io::Write;
+use std::num::NonZeroUsize;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::Args;
+use clap::Parser;
+use clap::Subcommand;
+use clap::ValueEnum;
+use rand::Rng;
+use zeroize::Zeroizing;
+
+use mdcrypt::algorithms::Blake2b;
+use mdcrypt::algorithms::Blake2s;
+use mdcrypt::algorithms::Blake3;
+use mdcrypt::algorithms::HammingECC;
+use mdcrypt::algorithms::Sha224;
+use mdcrypt::algorithms::Sha256;
+use mdcrypt::algorithms::Sha384;
+use mdcrypt::algorithms::Sha512;
+use mdcrypt::algorithms::Sha512_224;
+use mdcrypt::algorithms::Sha512_256;
+use mdcrypt::algorithms::Sha3_224;
+use mdcrypt::algorithms::Sha3_256;
+use mdcrypt::algorithms::Sha3_384;
+use mdcrypt::algorithms::Sha3_512;
+use mdcrypt::ArmoredKey;
+use mdcrypt::DecryptReader;
+use mdcrypt::Digest;
+use mdcrypt::EncryptWriter;
+use mdcrypt::Error;
+use mdcrypt::KdfParams;
+use mdcrypt::Key;
+use mdcrypt::Registry;
+
+/// Length of the random salt written before data encrypted with a passphrase.
+const SALT_LEN: usize = 16;
+
+/// Name of standard input and output in place of a file name.
+const STDIO: &str = "-";
+
+/// Command-line interface to the mdcrypt library.
+#[derive(Parser)]
+#[command(name = "mdcrypt", version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print or check SHA-2 checksums in the format of sha256sum.
+    Hash(HashArgs),
+    /// Encrypt data with a cipher from the registry.
+    Encrypt(CipherArgs),
+    /// Decrypt data encrypted by `encrypt`.
+    Decrypt(CipherArgs),
+    /// Protect data against errors by Hamming code.
+    #[command(subcommand)]
+    Ecc(EccCommand),
+    /// Generate a random key and print it as an armored key.
+    Keygen(KeygenArgs),
+}
+
+#[derive(Args)]
+struct HashArgs {
+    /// Hash function.
+    #[arg(short, long, value_enum, default_value_t = HashAlgorithm::Sha256)]
+    algorithm: HashAlgorithm,
+    /// Read checksums from the files and check them.
+    #[arg(short, long)]
+    check: bool,
+    /// Files to hash, or files with checksums when checking; `-` is standard
+    /// input.
+    #[arg(default_value = STDIO)]
+    files: Vec<PathBuf>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum HashAlgorithm {
+    Sha224,
+    Sha256,
+    Sha384,
+    Sha512,
+    #[value(name = "sha512-224")]
+    Sha512_224,
+    #[value(name = "sha512-256")]
+    Sha512_256,
+    #[value(name = "sha3-224")]
+    Sha3_224,
+    #[value(name = "sha3-256")]
+    Sha3_256,
+    #[value(name = "sha3-384")]
+    Sha3_384,
+    #[value(name = "sha3-512")]
+    Sha3_512,
+    Blake2b,
+    Blake2s,
+    Blake3,
+}
+
+#[derive(Args)]
+struct CipherArgs {
+    /// Cipher and its arguments as accepted by the registry, without the key,
+    /// e.g. `chacha20-poly1305`, `aes-gcm` or `aes:ecb`. The key needs to have
+    /// the length required by the cipher.
+    #[arg(short, long, default_value = "chacha20-poly1305")]
+    algorithm: String,
+    #[command(flatten)]
+    key: KeySource,
+    /// Parameters of the key derivation when a passphrase is used for
+    /// encryption. Decryption reads them from the input.
+    #[arg(long, default_value_t = KdfParams::default())]
+    kdf: KdfParams,
+    #[command(flatten)]
+    io: IoArgs,
+}
+
+/// Exactly one source of the key. A key derived from a passphrase uses a random
+/// salt, which is written before the encrypted data together with the KDF
+/// parameters, see [`kdf_header()`].
+#[derive(Args)]
+#[group(required = true, multiple = false)]
+struct KeySource {
+    /// File with the key in any format accepted by the library.
+    #[arg(short, long)]
+    key_file: Option<PathBuf>,
+    /// File whose first line is the passphrase.
+    #[arg(long)]
+    passphrase_file: Option<PathBuf>,
+    /// Environment variable holding the passphrase.
+    #[arg(long)]
+    passphrase_env: Option<String>,
+}
+
+#[derive(Subcommand)]
+enum EccCommand {
+    /// Encode data.
+    Encode(EccArgs),
+    /// Decode data, correcting single-bit errors in every block.
+    Decode(EccArgs),
+}
+
+#[derive(Args)]
+struct EccArgs {
+    /// Binary logarithm of the block size in bits, from 3 to 24.
+    #[arg(short, long, default_value_t = 4)]
+    block_log_size: u8,
+    /// Number of bits of the field holding length of the data, from 2 to 64.
+    #[arg(short, long, default_value_t = 16)]
+    size_field_bits: u8,
+    #[command(flatten)]
+    io: IoArgs,
+}
+
+#[derive(Args)]
+struct KeygenArgs {
+    /// Length of the key in bytes.
+    #[arg(short, long, default_value_t = NonZeroUsize::new(32).unwrap())]
+    length: NonZeroUsize,
+    /// Value of `Algorithm` header.
+    #[arg(short, long)]
+    algorithm: Option<String>,
+    /// Value of `Purpose` header.
+    #[arg(short, long)]
+    purpose: Option<String>,
+    /// File to write the key into, readable by its owner only; standard output
+    /// by default.
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct IoArgs {
+    /// Input file; `-` is standard input.
+    #[arg(default_value = STDIO)]
+    input: PathBuf,
+    /// Output file; standard output by default.
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let result = match cli.command {
+        Command::Hash(args)    => hash(args),
+        Command::Encrypt(args) => encrypt(args).map(|_| ExitCode::SUCCESS),
+        Command::Decrypt(args) => decrypt(args).map(|_| ExitCode::SUCCESS),
+        Command::Ecc(command)  => ecc(command).map(|_| ExitCode::SUCCESS),
+        Command::Keygen(args)  => keygen(args).map(|_| ExitCode::SUCCESS),
+    };
+    result.unwrap_or_else(|error| {
+        eprintln!("mdcrypt: {}", error);
+        ExitCode::FAILURE
+    })
+}
+
+fn hash(args: HashArgs) -> Result<ExitCode, Error> {
+    if args.check {
+        return check(args)
+    }
+    let mut stdout = io::stdout().lock();
+    for path in &args.files {
+        let digest = hash_file(args.algorithm, path)?;
+        writeln!(stdout, "{:x}  {}", digest, path.display())?;
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Checks checksums read from the files, printing result for every line in the
+/// same way as sha256sum.
+fn check(args: HashArgs) -> Result<ExitCode, Error> {
+    let mut stdout = io::stdout().lock();
+    let (mut mismatched, mut unreadable, mut malformed) = (0, 0, 0);
+
+    for list in &args.files {
+        for line in io::BufReader::new(open(list)?).lines() {
+            let line = line?;
+            let Some((expected, path)) = parse_checksum_line(&line) else {
+                malformed += usize::from(!line.trim().is_empty());
+                continue
+            };
+            match hash_file(args.algorithm, Path::new(path)) {
+                Ok(digest) if format!("{:x}", digest) == expected.to_lowercase() => {
+                    writeln!(stdout, "{}: OK", path)?
+                },
+                Ok(_) => {
+                    mismatched += 1;
+                    writeln!(stdout, "{}: FAILED", path)?
+                },
+                Err(error) => {
+                    unreadable += 1;
+                    eprintln!("mdcrypt: {}: {}", path, error);
+                    writeln!(stdout, "{}: FAILED open or read", path)?
+                },
+            }
+        }
+    }
+
+    if malformed > 0 {
+        eprintln!("mdcrypt: WARNING: {} line(s) are improperly formatted", malformed);
+    }
+    if unreadable > 0 {
+        eprintln!("mdcrypt: WARNING: {} listed file(s) could not be read", unreadable);
+    }
+    if mismatched > 0 {
+        eprintln!("mdcrypt: WARNING: {} computed checksum(s) did NOT match", mismatched);
+    }
+    // Like sha256sum, improperly formatted lines alone are not a failure:
+    Ok(match mismatched + unreadable {
+        0 => ExitCode::SUCCESS,
+        _ => ExitCode::FAILURE,
+    })
+}
+
+/// Splits a line of sha256sum output into the checksum and the file name. The
+/// name is separated by two spaces, or by a space and `*` in binary mode.
+fn parse_checksum_line(line: &str) -> Option<(&str, &str)> {
+    let (checksum, rest) = line.split_once(' ')?;
+    let path = rest.strip_prefix([' ', '*'])?;
+    let is_hex = !checksum.is_empty() && checksum.bytes().all(|byte| byte.is_ascii_hexdigit());
+    (is_hex && !path.is_empty()).then_some((checksum, path))
+}
+
+fn hash_file(algorithm: HashAlgorithm, path: &Path) -> io::Result<Digest> {
+    let reader = open(path)?;
+    match algorithm {
+        HashAlgorithm::Sha224     => Sha224::hash_reader(reader),
+        HashAlgorithm::Sha256     => Sha256::hash_reader(reader),
+        HashAlgorithm::Sha384     => Sha384::hash_reader(reader),
+        HashAlgorithm::Sha512     => Sha512::hash_reader(reader),
+        HashAlgorithm::Sha512_224 => Sha512_224::hash_reader(reader),
+        HashAlgorithm::Sha512_256 => Sha512_256::hash_reader(reader),
+        HashAlgorithm::Sha3_224   => Sha3_224::hash_reader(reader),
+        HashAlgorithm::Sha3_256   => Sha3_256::hash_reader(reader),
+        HashAlgorithm::Sha3_384   => Sha3_384::hash_reader(reader),
+        HashAlgorithm::Sha3_512   => Sha3_512::hash_reader(reader),
+        HashAlgorithm::Blake2b    => Blake2b::default().hash_reader(reader),
+        HashAlgorithm::Blake2s    => Blake2s::default().hash_reader(reader),
+        HashAlgorithm::Blake3     => Blake3::default().hash_reader(reader),
+    }
+}
+
+fn encrypt(args: CipherArgs) -> Result<(), Error> {
+    let data = read_input(&args.io.input)?;
+    let (key, header) = match read_passphrase(&args.key)? {
+        Some(passphrase) => {
+            let mut salt = [0; SALT_LEN];
+            rand::thread_rng().fill(&mut salt[..]);
+            let key = Key::derive_from_password(&passphrase, &salt, &args.kdf)?;
+            (key, kdf_header(&args.kdf, &salt))
+        },
+        None => (read_key_file(&args.key)?, Vec::new()),
+    };
+    let encrypted = build_cipher(&args.algorithm, &key)?.encrypt_bytes(&data)?;
+    write_output(args.io.output.as_deref(), &[&header, &encrypted])
+}
+
+fn decrypt(args: CipherArgs) -> Result<(), Error> {
+    let data = read_input(&args.io.input)?;
+    let (key, encrypted) = match read_passphrase(&args.key)? {
+        Some(passphrase) => {
+            let (kdf, salt, encrypted) = split_kdf_header(&data)?;
+            (Key::derive_from_password(&passphrase, salt, &kdf)?, encrypted)
+        },
+        None => (read_key_file(&args.key)?, &data[..]),
+    };
+    let decrypted = Zeroizing::new(build_cipher(&args.algorithm, &key)?.decrypt_bytes(encrypted)?);
+    write_output(args.io.output.as_deref(), &[&decrypted])
+}
+
+/// Returns the header written before data encrypted with a passphrase: length
+/// of the KDF parameters in one byte, the parameters as text like `--kdf` and
+/// the salt.
+fn kdf_header(kdf: &KdfParams, salt: &[u8; SALT_LEN]) -> Vec<u8> {
+    let params = kdf.to_string();
+    // The longest parameters have less than 100 characters:
+    let mut header = vec![params.len() as u8];
+    header.extend_from_slice(params.as_bytes());
+    header.extend_from_slice(salt);
+    header
+}
+
+/// Splits data encrypted with a passphrase into the KDF parameters, the salt and
+/// the encrypted data, see [`kdf_header()`].
+fn split_kdf_header(data: &[u8]) -> Result<(KdfParams, &[u8], &[u8]), Error> {
+    let missing = || Error::Malformed { reason: "missing KDF parameters and salt".into() };
+    let (&params_len, rest) = data.split_first().ok_or_else(missing)?;
+    if rest.len() < params_len as usize + SALT_LEN {
+        return Err(missing())
+    }
+    let (params, rest) = rest.split_at(params_len as usize);
+    let kdf = std::str::from_utf8(params)
+        .map_err(|_| Error::Malformed { reason: "KDF parameters are not UTF-8".into() })?
+        .parse()?;
+    let (salt, encrypted) = rest.split_at(SALT_LEN);
+    Ok((kdf, salt, encrypted))
+}
+
+/// Builds the cipher from the registry, passing the key as the last argument.
+fn build_cipher(algorithm: &str, key: &Key) -> Result<mdcrypt::Algorithm, Error> {
+    let spec = Zeroizing::new(format!("{}:hex={:x}", algorithm, key));
+    let cipher = Registry::default().build(&spec)?;
+    match cipher.is_reversible() {
+        true  => Ok(cipher),
+        false => Err(Error::Unsupported { operation: format!("{} as a cipher", algorithm) }),
+    }
+}
+
+/// Returns the passphrase if the key should be derived from it.
+fn read_passphrase(source: &KeySource) -> Result<Option<Zeroizing<Vec<u8>>>, Error> {
+    let passphrase = match (&source.passphrase_file, &source.passphrase_env) {
+        (Some(path), _) => {
+            let text = Zeroizing::new(fs::read_to_string(path)?);
+            text.lines().next().unwrap_or_default().as_bytes().to_vec()
+        },
+        (_, Some(variable)) => std::env::var(variable)
+            .map_err(|_| Error::InvalidKey {
+                reason: format!("environment variable `{}` is not set", variable)
+            })?
+            .into_bytes(),
+        _ => return Ok(None),
+    };
+    Ok(Some(Zeroizing::new(passphrase)))
+}
+
+fn read_key_file(source: &KeySource) -> Result<Key, Error> {
+    // Clap makes sure that exactly one source is given:
+    Key::load(source.key_file.as_ref().expect("key file or passphrase is required"))
+}
+
+fn ecc(command: EccCommand) -> Result<(), Error> {
+    match command {
+        EccCommand::Encode(args) => {
+            let hamming = HammingECC::new(args.block_log_size, args.size_field_bits)?;
+            let mut writer = EncryptWriter::new(&hamming, create(args.io.output.as_deref())?);
+            io::copy(&mut open(&args.io.input)?, &mut writer)?;
+            writer.finish()?;
+        },
+        EccCommand::Decode(args) => {
+            let hamming = HammingECC::new(args.block_log_size, args.size_field_bits)?;
+            let mut reader = DecryptReader::new(&hamming, open(&args.io.input)?);
+            let mut output = create(args.io.output.as_deref())?;
+            io::copy(&mut reader, &mut output)?;
+            output.flush()?;
+        },
+    }
+    Ok(())
+}
+
+fn keygen(args: KeygenArgs) -> Result<(), Error> {
+    let mut armored = ArmoredKey::new(Key::random(args.length, &mut rand::thread_rng()));
+    if let Some(algorithm) = args.algorithm {
+        armored = armored.with_algorithm(algorithm);
+    }
+    if let Some(purpose) = args.purpose {
+        armored = armored.with_purpose(purpose);
+    }
+    match args.output {
+        Some(path) => armored.save(path),
+        None => {
+            let text = Zeroizing::new(armored.to_string());
+            io::stdout().write_all(text.as_bytes()).map_err(Error::from)
+        },
+    }
+}
+
+fn open(path: &Path) -> io::Result<Box<dyn Read>> {
+    match path.as_os_str() == STDIO {
+        true  => Ok(Box::new(io::stdin().lock())),
+        false => Ok(Box::new(fs::File::open(path)?)),
+    }
+}
+
+fn create(path: Option<&Path>) -> io::Result<Box<dyn Write>> {
+    match path {
+        Some(path) => Ok(Box::new(io::BufWriter::new(fs::File::create(path)?))),
+        None       => Ok(Box::new(io::stdout().lock())),
+    }
+}
+
+fn read_input(path: &Path) -> io::Result<Vec<u8>> {
+    let mut data = Vec::new();
+    open(path)?.read_to_end(&mut data)?;
+    Ok(data)
+}
+
+fn write_output(path: Option<&Path>, parts: &[&[u8]]) -> Result<(), Error> {
+    let mut output = create(path)?;
+    for part in parts {
+        output.write_all(part)?;
+    }
+    output.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn cli() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn checksum_lines() {
+        assert_eq!(parse_checksum_line("abc0  file name"), Some(("abc0", "file name")));
+        assert_eq!(parse_checksum_line("ABC0 *file"), Some(("ABC0", "file")));
+        assert_eq!(parse_checksum_line("abc0 file"), None);
+        assert_eq!(parse_checksum_line("xyz  file"), None);
+        assert_eq!(parse_checksum_line("abc0  "), None);
+        assert_eq!(parse_checksum_line(""), None);
+    }
+
+    #[test]
+    fn kdf_headers() {
+        let kdf = KdfParams::Pbkdf2Sha256 { iterations: 1000, key_len: 32 };
+        let mut data = kdf_header(&kdf, &[7; SALT_LEN]);
+        assert_eq!(data[0] as usize, "pbkdf2-sha256:1000:32".len());
+        data.extend_from_slice(b"data");
+        assert_eq!(split_kdf_header(&data).unwrap(), (kdf, &[7; SALT_LEN][..], &b"data"[..]));
+
+        for len in [0, 1, 20, data.len() - 5] {
+            assert!(matches!(split_kdf_header(&data[..len]), Err(Error::Malformed { .. })));
+        }
+        data[1] = b'x';
+        assert!(split_kdf_header(&data).is_err());
+    }
+
+    #[test]
+    fn cipher() {
+        let key = Key::new((0..32).collect());
+        for algorithm in ["chacha20-poly1305", "aes-gcm", "aes:ecb", "vigener"] {
+            let cipher = build_cipher(algorithm, &key).unwrap();
+            let encrypted = cipher.encrypt_bytes(b"data").unwrap();
+            assert_eq!(cipher.decrypt_bytes(&encrypted).unwrap(), b"data", "{}", algorithm);
+        }
+        let short = Key::new(vec![1, 2, 3]);
+        assert!(matches!(build_cipher("aes-gcm", &short), Err(Error::InvalidKey { .. })));
+        assert!(matches!(build_cipher("sha256", &key), Err(Error::InvalidParameters { .. })));
+    }
+}
@@ -0,0 +1,573 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use crate::algorithms::Adler32;
+use crate::algorithms::Aes;
+use crate::algorithms::AesGcm;
+use crate::algorithms::Affine;
+use crate::algorithms::Ascii85;
+use crate::algorithms::Base16;
+use crate::algorithms::Base32;
+use crate::algorithms::Base32Alphabet;
+use crate::algorithms::Base64;
+use crate::algorithms::Base64Alphabet;
+use crate::algorithms::Blake2b;
+use crate::algorithms::Blake2s;
+use crate::algorithms::Blake3;
+use crate::algorithms::Caesar;
+use crate::algorithms::ChaCha20Poly1305;
+use crate::algorithms::ColumnarTransposition;
+use crate::algorithms::Crc;
+use crate::algorithms::CrcParams;
+use crate::algorithms::Fletcher16;
+use crate::algorithms::Fletcher32;
+use crate::algorithms::Framed;
+use crate::algorithms::HammingECC;
+use crate::algorithms::Hill;
+use crate::algorithms::Mode;
+use crate::algorithms::Playfair;
+use crate::algorithms::RailFence;
+use crate::algorithms::ReedSolomon;
+use crate::algorithms::Sha224;
+use crate::algorithms::Sha256;
+use crate::algorithms::Sha384;
+use crate::algorithms::Sha512;
+use crate::algorithms::Sha512_224;
+use crate::algorithms::Sha512_256;
+use crate::algorithms::Sha3_224;
+use crate::algorithms::Sha3_256;
+use crate::algorithms::Sha3_384;
+use crate::algorithms::Sha3_512;
+use crate::algorithms::Shake128;
+use crate::algorithms::Shake256;
+use crate::algorithms::Substitution;
+use crate::algorithms::Vigener;
+use crate::algorithms::Z85;
+use crate::decrypt::TryDecrypt;
+use crate::dynamic::DynCipher;
+use crate::dynamic::DynEncrypt;
+use crate::error::Error;
+use crate::Key;
+
+/// Function constructing an [`Algorithm`] from arguments of its specification,
+/// e.g. from everything after the name, split by `:`.
+pub type Constructor = fn(&[&str]) -> Result<Algorithm, Error>;
+
+/// An algorithm chosen at runtime, e.g. built by [`Registry`].
+pub enum Algorithm {
+
+    /// An algorithm which can both encrypt and decrypt, such as a cipher or an
+    /// error correction code.
+    Reversible(Box<dyn DynCipher>),
+
+    /// An algorithm which can only encrypt, such as a hash function.
+    OneWay(Box<dyn DynEncrypt>),
+}
+
+impl Algorithm {
+
+    /// Returns `true` if the algorithm can decrypt.
+    pub fn is_reversible(&self) -> bool {
+        matches!(self, Self::Reversible(_))
+    }
+
+    /// Encrypts given data. See [`DynEncrypt::encrypt_bytes`] for more
+    /// information.
+    pub fn encrypt_bytes(&self, data_to_encrypt: &[u8]) -> Result<Vec<u8>, Error> {
+        match self {
+            Self::Reversible(algorithm) => algorithm.encrypt_bytes(data_to_encrypt),
+            Self::OneWay(algorithm)     => algorithm.encrypt_bytes(data_to_encrypt),
+        }
+    }
+
+    /// Decrypts given data. See [`DynDecrypt::decrypt_bytes`] for more
+    /// information. One-way algorithms always return [`Error::Unsupported`].
+    ///
+    /// [`DynDecrypt::decrypt_bytes`]: crate::DynDecrypt::decrypt_bytes
+    pub fn decrypt_bytes(&self, encrypted_data: &[u8]) -> Result<Vec<u8>, Error> {
+        match self {
+            Self::Reversible(algorithm) => algorithm.decrypt_bytes(encrypted_data),
+            Self::OneWay(_) => Err(Error::Unsupported { operation: "decrypt".into() }),
+        }
+    }
+}
+
+impl FromStr for Algorithm {
+    type Err = Error;
+
+    /// Builds an algorithm from its specification using [`Registry::default`].
+    fn from_str(spec: &str) -> Result<Self, Error> {
+        Registry::default().build(spec)
+    }
+}
+
+/// Registry of algorithms which can be built from a string specification, so
+/// that algorithms can be chosen at runtime, e.g. in configuration files.
+///
+/// Specification consists of name of the algorithm and its arguments, all
+/// separated by `:`. Names are case-insensitive. [`Registry::default`] contains
+/// following algorithms:
+///
+///  -  `vigener:hex=<key>` or `vigener:text=<key>` (`vigenere` can be used as
+///     well): [`Vigener`] with key given as hexadecimal digits or as UTF-8 text
+///  -  `aes:ecb:hex=<key>`, `aes:cbc:hex=<key>:iv=<iv>`, `aes:ctr:hex=<key>:iv=<nonce>`,
+///     `aes:cfb:hex=<key>:iv=<iv>`: [`Aes`] with 16, 24 or 32-byte key and 16-byte
+///     initialization vector or initial counter block, all given as hexadecimal
+///     digits, for example `aes:cbc:hex=<32 digits>:iv=<32 digits>`
+///  -  `aes-gcm:hex=<key>[:ad=<data>]`, `chacha20-poly1305:hex=<key>[:ad=<data>]`:
+///     [`AesGcm`] and [`ChaCha20Poly1305`] with optional associated data given as
+///     hexadecimal digits
+///  -  `caesar:<shift>`, `rot13`, `affine:<a>:<b>`, `substitution:<substitutes>`,
+///     `playfair:<keyword>`, `hill:<keyword>`, `rail-fence:<rails>`,
+///     `columnar:<keyword>`: classical ciphers over the Latin alphabet, for
+///     example `affine:5:8` or `hill:GYBNQKURP`
+///  -  `sha224`, `sha256`, `sha384`, `sha512`, `sha512-224`, `sha512-256`: the
+///     SHA-2 family
+///  -  `sha3-224`, `sha3-256`, `sha3-384`, `sha3-512`: the SHA-3 family
+///  -  `shake128[:<output_len>]`, `shake256[:<output_len>]`, `blake2b[:<output_len>]`,
+///     `blake2s[:<output_len>]`, `blake3[:<output_len>]`: hash functions with
+///     optional length of the digest in bytes, for example `blake2b:32`
+///  -  `crc:<params>`, `adler32`, `fletcher16`, `fletcher32`: checksums, where
+///     `<params>` is one of `8`, `16-arc`, `16-ibm-3740`, `16-kermit`, `16-xmodem`,
+///     `32`, `32-bzip2`, `32c`, `64-xz` and `64-ecma-182` named after constants
+///     of [`CrcParams`], for example `crc:32c`
+///  -  `framed:<checksum>`: [`Framed`] data followed by one of the checksums above,
+///     for example `framed:crc:32` or `framed:adler32`
+///  -  `hamming:<blk_log_size>:<size_field_bits>`: [`HammingECC`], for example
+///     `hamming:4:16`
+///  -  `reed-solomon:<n>:<k>`: [`ReedSolomon`], for example `reed-solomon:255:223`
+///  -  `base16` (`hex` can be used as well), `ascii85`, `z85`: text encodings
+///  -  `base32[:pad|:nopad]`, `base32-crockford[:pad|:nopad]`, `base64[:pad|:nopad]`,
+///     `base64url[:pad|:nopad]`: text encodings, padded by default except for
+///     `base32-crockford`
+pub struct Registry {
+    constructors: HashMap<String, Constructor>,
+}
+
+impl Registry {
+
+    /// Creates an empty registry. Use [`Registry::default`] to get a registry
+    /// with algorithms from this crate.
+    pub fn new() -> Self {
+        Self { constructors: HashMap::new() }
+    }
+
+    /// Registers an algorithm under given name. If there is an algorithm with the
+    /// same name already, it is replaced.
+    ///
+    /// # Parameters
+    ///
+    /// - `name`: case-insensitive name of the algorithm
+    /// - `constructor`: function building the algorithm from its arguments
+    pub fn register(&mut self, name: &str, constructor: Constructor) {
+        self.constructors.insert(name.to_lowercase(), constructor);
+    }
+
+    /// Builds an algorithm from its specification.
+    ///
+    /// # Parameters
+    ///
+    /// - `spec`: specification such as `hamming:4:16`
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with the algorithm
+    /// - [`Err`] with [`Error::InvalidParameters`] if the algorithm is not
+    ///   registered or its arguments are invalid
+    pub fn build(&self, spec: &str) -> Result<Algorithm, Error> {
+        let mut parts = spec.split(':');
+        // `split` always returns at least one part:
+        let name = parts.next().unwrap().trim().to_lowercase();
+        let args: Vec<&str> = parts.collect();
+        match self.constructors.get(&name) {
+            Some(constructor) => constructor(&args),
+            None => Err(Error::invalid_parameter("name", format!("unknown algorithm `{}`", name)))
+        }
+    }
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        let mut registry = Self::new();
+        registry.register("vigener", build_vigener);
+        registry.register("vigenere", build_vigener);
+        registry.register("aes", build_aes);
+        registry.register("aes-gcm", |args| build_aead(args, AesGcm::new));
+        registry.register("chacha20-poly1305", |args| build_aead(args, ChaCha20Poly1305::new));
+        registry.register("caesar", build_caesar);
+        registry.register("rot13", |args| build_encoding(args, Caesar::rot13()));
+        registry.register("affine", build_affine);
+        registry.register("substitution", |args| build_keyword(args, Substitution::new));
+        registry.register("playfair", |args| build_keyword(args, Playfair::new));
+        registry.register("hill", |args| build_keyword(args, Hill::from_keyword));
+        registry.register("rail-fence", build_rail_fence);
+        registry.register("columnar", |args| build_keyword(args, ColumnarTransposition::new));
+        registry.register("sha224", |args| build_hash(args, Sha224::default()));
+        registry.register("sha256", |args| build_hash(args, Sha256::default()));
+        registry.register("sha384", |args| build_hash(args, Sha384::default()));
+        registry.register("sha512", |args| build_hash(args, Sha512::default()));
+        registry.register("sha512-224", |args| build_hash(args, Sha512_224::default()));
+        registry.register("sha512-256", |args| build_hash(args, Sha512_256::default()));
+        registry.register("sha3-224", |args| build_hash(args, Sha3_224::default()));
+        registry.register("sha3-256", |args| build_hash(args, Sha3_256::default()));
+        registry.register("sha3-384", |args| build_hash(args, Sha3_384::default()));
+        registry.register("sha3-512", |args| build_hash(args, Sha3_512::default()));
+        registry.register("shake128", |args| build_sized_hash(args, Shake128::new));
+        registry.register("shake256", |args| build_sized_hash(args, Shake256::new));
+        registry.register("blake2b", |args| build_sized_hash(args, Blake2b::new));
+        registry.register("blake2s", |args| build_sized_hash(args, Blake2s::new));
+        registry.register("blake3", |args| build_sized_hash(args, Blake3::new));
+        registry.register("crc", |args| Ok(Algorithm::OneWay(Box::new(build_crc(args)?))));
+        registry.register("adler32", |args| build_hash(args, Adler32::new()));
+        registry.register("fletcher16", |args| build_hash(args, Fletcher16::new()));
+        registry.register("fletcher32", |args| build_hash(args, Fletcher32::new()));
+        registry.register("framed", build_framed);
+        registry.register("hamming", build_hamming);
+        registry.register("reed-solomon", build_reed_solomon);
+        registry.register("base16", |args| build_encoding(args, Base16::new()));
+        registry.register("hex", |args| build_encoding(args, Base16::new()));
+        registry.register("base32", |args| {
+            let base32 = Base32::new(Base32Alphabet::Standard);
+            build_encoding(&[], base32.with_padding(parse_padding(args, true)?))
+        });
+        registry.register("base32-crockford", |args| {
+            let base32 = Base32::new(Base32Alphabet::Crockford);
+            build_encoding(&[], base32.with_padding(parse_padding(args, false)?))
+        });
+        registry.register("base64", |args| {
+            let base64 = Base64::new(Base64Alphabet::Standard);
+            build_encoding(&[], base64.with_padding(parse_padding(args, true)?))
+        });
+        registry.register("base64url", |args| {
+            let base64 = Base64::new(Base64Alphabet::UrlSafe);
+            build_encoding(&[], base64.with_padding(parse_padding(args, true)?))
+        });
+        registry.register("ascii85", |args| build_encoding(args, Ascii85::new()));
+        registry.register("z85", |args| build_encoding(args, Z85::new()));
+        registry
+    }
+}
+
+fn build_vigener(args: &[&str]) -> Result<Algorithm, Error> {
+    let key = match args {
+        [key] => parse_key(key)?,
+        _ => return Err(Error::invalid_parameter("key", "expected exactly one argument"))
+    };
+    Ok(Algorithm::Reversible(Box::new(Vigener::new(key))))
+}
+
+fn build_aes(args: &[&str]) -> Result<Algorithm, Error> {
+    let (key, mode) = match args {
+        [mode, key] if mode.eq_ignore_ascii_case("ecb") => (parse_key(key)?, Mode::Ecb),
+        [mode, key, iv] => {
+            let iv = parse_iv(iv)?;
+            let mode = match mode.to_lowercase().as_str() {
+                "cbc" => Mode::Cbc { iv },
+                "ctr" => Mode::Ctr { nonce: iv },
+                "cfb" => Mode::Cfb { iv },
+                _ => return Err(Error::invalid_parameter(
+                    "mode",
+                    format!("expected `cbc`, `ctr` or `cfb` with `iv=` but `{}` was given", mode)
+                ))
+            };
+            (parse_key(key)?, mode)
+        },
+        _ => return Err(Error::invalid_parameter("arguments", "expected `mode`, key and `iv=`"))
+    };
+    Ok(Algorithm::Reversible(Box::new(Aes::new(&key, mode)?)))
+}
+
+fn build_aead<C>(args: &[&str], constructor: fn(&Key, &[u8]) -> Result<C, Error>)
+        -> Result<Algorithm, Error>
+where C: DynCipher + 'static {
+    let (key, associated_data) = match args {
+        [key] => (parse_key(key)?, Vec::new()),
+        [key, associated_data] => match associated_data.split_once('=') {
+            Some(("ad", hex)) => (parse_key(key)?, Base16::new().try_decrypt(hex.bytes())?),
+            _ => return Err(Error::invalid_parameter("associated_data", "expected `ad=`"))
+        },
+        _ => return Err(Error::invalid_parameter("arguments", "expected key and optional `ad=`"))
+    };
+    Ok(Algorithm::Reversible(Box::new(constructor(&key, &associated_data)?)))
+}
+
+fn build_caesar(args: &[&str]) -> Result<Algorithm, Error> {
+    let shift = match args {
+        [shift] => parse_number(shift, "shift")?,
+        _ => return Err(Error::invalid_parameter("arguments", "expected `shift`"))
+    };
+    Ok(Algorithm::Reversible(Box::new(Caesar::new(shift as usize))))
+}
+
+fn build_affine(args: &[&str]) -> Result<Algorithm, Error> {
+    let (a, b) = match args {
+        [a, b] => (parse_number(a, "a")?, parse_number(b, "b")?),
+        _ => return Err(Error::invalid_parameter("arguments", "expected `a` and `b`"))
+    };
+    Ok(Algorithm::Reversible(Box::new(Affine::new(a as usize, b as usize)?)))
+}
+
+fn build_rail_fence(args: &[&str]) -> Result<Algorithm, Error> {
+    let rails = match args {
+        [rails] => parse_number(rails, "rails")?,
+        _ => return Err(Error::invalid_parameter("arguments", "expected `rails`"))
+    };
+    Ok(Algorithm::Reversible(Box::new(RailFence::new(rails as usize)?)))
+}
+
+fn build_keyword<C>(args: &[&str], constructor: fn(&str) -> Result<C, Error>)
+        -> Result<Algorithm, Error>
+where C: DynCipher + 'static {
+    match args {
+        [keyword] => Ok(Algorithm::Reversible(Box::new(constructor(keyword)?))),
+        _ => Err(Error::invalid_parameter("keyword", "expected exactly one argument"))
+    }
+}
+
+fn build_hash<H>(args: &[&str], hash: H) -> Result<Algorithm, Error>
+where H: DynEncrypt + 'static {
+    match args {
+        [] => Ok(Algorithm::OneWay(Box::new(hash))),
+        _  => Err(Error::invalid_parameter("arguments", "hash functions take no arguments"))
+    }
+}
+
+fn build_sized_hash<H>(args: &[&str], constructor: fn(usize) -> Result<H, Error>)
+        -> Result<Algorithm, Error>
+where H: DynEncrypt + Default + 'static {
+    let hash = match args {
+        [] => H::default(),
+        [output_len] => constructor(parse_len(output_len, "output_len")?)?,
+        _ => return Err(Error::invalid_parameter("arguments", "expected at most `output_len`"))
+    };
+    Ok(Algorithm::OneWay(Box::new(hash)))
+}
+
+fn build_crc(args: &[&str]) -> Result<Crc, Error> {
+    let params = match args {
+        [params] => match params.to_lowercase().as_str() {
+            "8"           => CrcParams::CRC_8,
+            "16-arc"      => CrcParams::CRC_16_ARC,
+            "16-ibm-3740" => CrcParams::CRC_16_IBM_3740,
+            "16-kermit"   => CrcParams::CRC_16_KERMIT,
+            "16-xmodem"   => CrcParams::CRC_16_XMODEM,
+            "32"          => CrcParams::CRC_32,
+            "32-bzip2"    => CrcParams::CRC_32_BZIP2,
+            "32c"         => CrcParams::CRC_32C,
+            "64-xz"       => CrcParams::CRC_64_XZ,
+            "64-ecma-182" => CrcParams::CRC_64_ECMA_182,
+            _ => return Err(Error::invalid_parameter(
+                "params",
+                format!("unknown CRC `{}`", params)
+            ))
+        },
+        _ => return Err(Error::invalid_parameter("arguments", "expected exactly one argument"))
+    };
+    Crc::new(params)
+}
+
+fn build_framed(args: &[&str]) -> Result<Algorithm, Error> {
+    let framed: Box<dyn DynCipher> = match args {
+        [checksum, rest @ ..] => match (checksum.to_lowercase().as_str(), rest) {
+            ("crc", params)    => Box::new(Framed::new(build_crc(params)?)),
+            ("adler32", [])    => Box::new(Framed::new(Adler32::new())),
+            ("fletcher16", []) => Box::new(Framed::new(Fletcher16::new())),
+            ("fletcher32", []) => Box::new(Framed::new(Fletcher32::new())),
+            _ => return Err(Error::invalid_parameter(
+                "checksum",
+                format!("unknown checksum `{}`", args.join(":"))
+            ))
+        },
+        _ => return Err(Error::invalid_parameter("checksum", "expected a checksum"))
+    };
+    Ok(Algorithm::Reversible(framed))
+}
+
+fn build_hamming(args: &[&str]) -> Result<Algorithm, Error> {
+    let (blk_log_size, size_field_bits) = match args {
+        [blk_log_size, size_field_bits] => (
+            parse_number(blk_log_size, "blk_log_size")?,
+            parse_number(size_field_bits, "size_field_bits")?
+        ),
+        _ => return Err(Error::invalid_parameter(
+            "arguments",
+            "expected `blk_log_size` and `size_field_bits`"
+        ))
+    };
+    let hamming = HammingECC::new(blk_log_size, size_field_bits)?;
+    Ok(Algorithm::Reversible(Box::new(hamming)))
+}
+
+fn build_reed_solomon(args: &[&str]) -> Result<Algorithm, Error> {
+    let (n, k) = match args {
+        [n, k] => (parse_number(n, "n")?, parse_number(k, "k")?),
+        _ => return Err(Error::invalid_parameter("arguments", "expected `n` and `k`"))
+    };
+    let reed_solomon = ReedSolomon::new(n as usize, k as usize)?;
+    Ok(Algorithm::Reversible(Box::new(reed_solomon)))
+}
+
+fn build_encoding<C>(args: &[&str], encoding: C) -> Result<Algorithm, Error>
+where C: DynCipher + 'static {
+    match args {
+        [] => Ok(Algorithm::Reversible(Box::new(encoding))),
+        _  => Err(Error::invalid_parameter("arguments", "unexpected arguments of the encoding"))
+    }
+}
+
+fn parse_padding(args: &[&str], default: bool) -> Result<bool, Error> {
+    match args {
+        []        => Ok(default),
+        ["pad"]   => Ok(true),
+        ["nopad"] => Ok(false),
+        _ => Err(Error::invalid_parameter("padding", "expected `pad` or `nopad`"))
+    }
+}
+
+fn parse_key(arg: &str) -> Result<Key, Error> {
+    match arg.split_once('=') {
+        Some(("hex", hex))   => Key::from_hex(hex),
+        Some(("text", ""))   => Err(Error::InvalidKey { reason: "key must not be empty".into() }),
+        Some(("text", text)) => Ok(Key::new(text.as_bytes().to_vec())),
+        _ => Err(Error::invalid_parameter("key", "expected `hex=` or `text=`"))
+    }
+}
+
+fn parse_iv(arg: &str) -> Result<[u8; 16], Error> {
+    let iv: Vec<u8> = match arg.split_once('=') {
+        Some(("iv", hex)) => Base16::new().try_decrypt(hex.bytes())?,
+        _ => return Err(Error::invalid_parameter("iv", "expected `iv=`"))
+    };
+    iv.try_into().map_err(|iv: Vec<u8>| Error::invalid_parameter(
+        "iv",
+        format!("expected 16 bytes but {} were given", iv.len())
+    ))
+}
+
+fn parse_len(text: &str, name: &str) -> Result<usize, Error> {
+    text.trim().parse().map_err(|_| Error::invalid_parameter(
+        name,
+        format!("`{}` is not a length in bytes", text)
+    ))
+}
+
+fn parse_number(text: &str, name: &str) -> Result<u8, Error> {
+    text.trim().parse().map_err(|_| Error::invalid_parameter(
+        name,
+        format!("`{}` is not a number from 0 to 255", text)
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Encrypt;
+    use crate::test_util::to_hex;
+
+    #[test]
+    fn build_ok() {
+        let data = b"registry".to_vec();
+        for spec in ["vigenere:hex=00ff10", "Vigener:text=key", "hamming:4:16", "hamming:3:5",
+                "reed-solomon:255:223", "Reed-Solomon:7:3", "base16", "HEX", "base32",
+                "base32:nopad", "base32-crockford", "base64", "base64url:nopad", "ascii85",
+                "z85", "framed:crc:32", "Framed:CRC:64-xz", "framed:adler32", "framed:fletcher16",
+                "framed:fletcher32"] {
+            let algorithm: Algorithm = spec.parse().unwrap();
+            let encrypted = algorithm.encrypt_bytes(&data).unwrap();
+            assert_eq!(algorithm.decrypt_bytes(&encrypted).unwrap(), data);
+        }
+
+        // Playfair gives upper case letters:
+        let text = b"REGISTRY".to_vec();
+        for spec in ["caesar:3", "ROT13", "affine:5:8", "substitution:QWERTYUIOPASDFGHJKLZXCVBNM",
+                "playfair:monarchy", "hill:hill", "rail-fence:3", "columnar:zebras"] {
+            let algorithm: Algorithm = spec.parse().unwrap();
+            let encrypted = algorithm.encrypt_bytes(&text).unwrap();
+            assert_ne!(encrypted, text, "{}", spec);
+            assert_eq!(algorithm.decrypt_bytes(&encrypted).unwrap(), text, "{}", spec);
+        }
+
+        let iv = "iv=00112233445566778899aabbccddeeff";
+        for spec in ["aes:ecb:hex=000102030405060708090a0b0c0d0e0f".to_string(),
+                format!("AES:CBC:hex={}:{}", "0f".repeat(24), iv),
+                format!("aes:ctr:text={}:{}", "k".repeat(32), iv),
+                format!("aes:cfb:text=0123456789abcdef:{}", iv)] {
+            let algorithm: Algorithm = spec.parse().unwrap();
+            let encrypted = algorithm.encrypt_bytes(&data).unwrap();
+            assert_ne!(encrypted, data, "{}", spec);
+            assert_eq!(algorithm.decrypt_bytes(&encrypted).unwrap(), data, "{}", spec);
+        }
+
+        let key = "hex=".to_string() + &"5a".repeat(32);
+        for spec in [format!("aes-gcm:{}", key), format!("AES-GCM:text={}", "k".repeat(16)),
+                format!("chacha20-poly1305:{}", key),
+                format!("chacha20-poly1305:{}:ad=0102", key)] {
+            let algorithm: Algorithm = spec.parse().unwrap();
+            let encrypted = algorithm.encrypt_bytes(&data).unwrap();
+            assert_eq!(algorithm.decrypt_bytes(&encrypted).unwrap(), data, "{}", spec);
+        }
+        // Associated data must match:
+        let sealed = format!("aes-gcm:{}:ad=01", key).parse::<Algorithm>().unwrap();
+        let opened = format!("aes-gcm:{}:ad=02", key).parse::<Algorithm>().unwrap();
+        let encrypted = sealed.encrypt_bytes(&data).unwrap();
+        assert!(matches!(opened.decrypt_bytes(&encrypted), Err(Error::AuthenticationFailed)));
+
+        let sha256: Algorithm = "sha256".parse().unwrap();
+        let expected: Vec<u8> = Sha256::default().encrypt(data.clone());
+        assert_eq!(sha256.encrypt_bytes(&data).unwrap(), expected);
+        assert!(!sha256.is_reversible());
+        assert!(sha256.decrypt_bytes(&expected).is_err());
+
+        for (spec, output_len) in [("sha3-256", 32), ("SHA3-512", 64), ("shake128", 32),
+                ("shake256:100", 100), ("blake2b", 64), ("blake2b:20", 20), ("blake2s", 32),
+                ("blake3", 32), ("blake3:255", 255), ("blake3:1000", 1000), ("shake128:300", 300),
+                ("blake2b:64", 64)] {
+            let hash: Algorithm = spec.parse().unwrap();
+            assert_eq!(hash.encrypt_bytes(&data).unwrap().len(), output_len, "{}", spec);
+        }
+
+        // Check values of the catalogue of CRC algorithms:
+        for (spec, check) in [("crc:8", "f4"), ("crc:16-arc", "bb3d"), ("crc:16-ibm-3740", "29b1"),
+                ("crc:16-kermit", "2189"), ("crc:16-xmodem", "31c3"), ("crc:32", "cbf43926"),
+                ("crc:32-bzip2", "fc891918"), ("CRC:32C", "e3069283"),
+                ("crc:64-xz", "995dc9bbdf1939fa"), ("crc:64-ecma-182", "6c40df5f0b497347"),
+                ("adler32", "091e01de"), ("fletcher16", "1ede"), ("fletcher32", "df09d509")] {
+            let checksum: Algorithm = spec.parse().unwrap();
+            let value = checksum.encrypt_bytes(b"123456789").unwrap();
+            assert_eq!(to_hex(&value), check, "{}", spec);
+        }
+        let framed: Algorithm = "framed:crc:32".parse().unwrap();
+        let mut frame = framed.encrypt_bytes(&data).unwrap();
+        frame[0] ^= 1;
+        assert!(framed.decrypt_bytes(&frame).is_err());
+    }
+
+    #[test]
+    fn build_err() {
+        for spec in ["", "rot47", "vigenere", "vigenere:hex=0g", "vigenere:hex=",
+                "vigenere:key=00", "sha256:1", "hamming:4", "hamming:2:16", "hamming:x:1",
+                "reed-solomon:255", "reed-solomon:256:223", "reed-solomon:10:10", "sha3-256:32",
+                "blake2b:65", "blake2s:0", "blake3:1:2", "blake3:-1", "shake256:2000000",
+                "shake128:x", "base16:pad", "base64:none", "base64:pad:nopad", "z85:1", "aes",
+                "aes:ecb:hex=0011",
+                "aes:cbc:text=0123456789abcdef", "aes:cbc:text=0123456789abcdef:iv=0011",
+                "aes:cbc:text=0123456789abcdef:iv=0g",
+                "aes:ofb:text=0123456789abcdef:iv=00", "aes:ecb:text=0123456789abcdef:nonce=00",
+                "aes-gcm", "aes-gcm:hex=00", "aes-gcm:text=0123456789abcdef:aad=00",
+                "chacha20-poly1305:text=0123456789abcdef", "chacha20-poly1305:hex=:ad=00",
+                "caesar", "caesar:x", "rot13:1", "affine:2:1", "affine:5", "substitution:abc",
+                "playfair", "hill:abc", "rail-fence:1", "columnar:", "columnar:a:b", "crc",
+                "crc:31", "crc:32:32c", "adler32:1", "framed", "framed:crc", "framed:adler32:1",
+                "framed:sha256"] {
+            assert!(spec.parse::<Algorithm>().is_err(), "{}", spec);
+        }
+    }
+
+    #[test]
+    fn register() {
+        let mut registry = Registry::new();
+        assert!(registry.build("sha256").is_err());
+        registry.register("SHA256", |_| Ok(Algorithm::OneWay(Box::new(Sha256::default()))));
+        assert!(registry.build("sha256").is_ok());
+    }
+}
@@ -0,0 +1,280 @@
+use std::fmt;
+use std::str::FromStr;
+
+use rand::Rng;
+use zeroize::Zeroizing;
+
+use crate::algorithms::Base64;
+use crate::decrypt::TryDecrypt;
+use crate::error::Error;
+use crate::gf256;
+use crate::key::Key;
+
+/// Prefix of a [`KeyShare`] encoded as text.
+const TEXT_PREFIX: &str = "mdcrypt-share:";
+
+/// One share of a [`Key`] split by [`Key::split`] using Shamir's secret sharing.
+/// Any `threshold` shares of the same key can be joined back by
+/// [`Key::combine`], while fewer shares reveal nothing about the key.
+///
+/// Every byte of the key is shared independently: it is the constant term of
+/// a random polynomial of degree `threshold - 1` over GF(256) and the share holds
+/// values of the polynomials at the point given by the share's index.
+///
+/// A share can be stored as bytes by [`to_bytes()`] or as text by [`encode()`].
+/// Both hold the threshold, the index and the value, so the share can be
+/// combined without any other information. Like [`Key`], the share is wiped on
+/// drop and its [`Debug`](fmt::Debug) does not print the value.
+///
+/// [`to_bytes()`]: KeyShare::to_bytes
+/// [`encode()`]: KeyShare::encode
+#[derive(Clone, PartialEq, Eq)]
+pub struct KeyShare {
+    threshold: u8,
+    index: u8,
+    value: Key,
+}
+
+impl KeyShare {
+
+    /// Returns number of shares needed to recover the key.
+    pub fn threshold(&self) -> u8 {
+        self.threshold
+    }
+
+    /// Returns index of the share, counted from one.
+    pub fn index(&self) -> u8 {
+        self.index
+    }
+
+    /// Returns the share as bytes: the threshold, the index and the value, which
+    /// is as long as the shared key.
+    pub fn to_bytes(&self) -> Zeroizing<Vec<u8>> {
+        let mut bytes = Zeroizing::new(Vec::with_capacity(self.value.len() + 2));
+        bytes.push(self.threshold);
+        bytes.push(self.index);
+        bytes.extend(self.value.iter());
+        bytes
+    }
+
+    /// Parses the share from bytes produced by [`to_bytes()`](KeyShare::to_bytes).
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with the share
+    /// - [`Err`] with [`Error::Malformed`] if the bytes are too short or the
+    ///   threshold or the index are zero
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        match bytes {
+            [0, _, _, ..] => Err(Error::malformed("threshold of the share is zero")),
+            [_, 0, _, ..] => Err(Error::malformed("index of the share is zero")),
+            [threshold, index, value @ ..] if !value.is_empty() => Ok(Self {
+                threshold: *threshold,
+                index: *index,
+                value: Key::new(value.to_vec()),
+            }),
+            _ => Err(Error::malformed("share is too short")),
+        }
+    }
+
+    /// Returns the share as text: `mdcrypt-share:` followed by its bytes in the
+    /// standard Base64. Use [`FromStr`] implementation to parse it.
+    pub fn encode(&self) -> String {
+        format!("{}{}", TEXT_PREFIX, Base64::default().encode_to_string(&self.to_bytes()))
+    }
+}
+
+impl FromStr for KeyShare {
+    type Err = Error;
+
+    /// Parses the share from text produced by [`encode()`](KeyShare::encode).
+    /// Leading and trailing whitespace is ignored.
+    fn from_str(text: &str) -> Result<Self, Error> {
+        let encoded = text.trim().strip_prefix(TEXT_PREFIX)
+            .ok_or_else(|| Error::malformed(format!("share must start with `{}`", TEXT_PREFIX)))?;
+        let bytes: Vec<u8> = Base64::default().try_decrypt(encoded.bytes())?;
+        Self::from_bytes(&Zeroizing::new(bytes))
+    }
+}
+
+impl fmt::Debug for KeyShare {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.debug_struct("KeyShare")
+            .field("threshold", &self.threshold)
+            .field("index", &self.index)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Key {
+
+    /// Splits the key into shares using Shamir's secret sharing, so that any
+    /// `threshold` shares recover the key and fewer shares reveal nothing about
+    /// it. See [`KeyShare`] for more information.
+    ///
+    /// # Parameters
+    ///
+    /// - `threshold`: number of shares needed to recover the key, at least 1
+    /// - `shares`: number of shares to create, at least `threshold`
+    /// - `rng`: cryptographically secure random number generator
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with shares whose indices are 1 to `shares`
+    /// - [`Err`] with [`Error::InvalidParameters`] if `threshold` is zero or
+    ///   greater than `shares`
+    pub fn split<R: Rng + ?Sized>(
+        &self,
+        threshold: u8,
+        shares: u8,
+        rng: &mut R,
+    ) -> Result<Vec<KeyShare>, Error> {
+        if threshold == 0 {
+            return Err(Error::invalid_parameter("threshold", "must be at least 1"))
+        }
+        if shares < threshold {
+            return Err(Error::invalid_parameter(
+                "shares",
+                format!("must be at least the threshold {}", threshold)
+            ))
+        }
+
+        // Coefficients of the polynomials, the constant terms being the key
+        // bytes. Coefficients of each power are stored together:
+        let mut coefficients = Zeroizing::new(vec![0; self.len() * threshold as usize]);
+        coefficients[..self.len()].copy_from_slice(self.reveal());
+        rng.fill_bytes(&mut coefficients[self.len()..]);
+
+        let shares = (1..=shares)
+            .map(|index| {
+                // Horner's method from the highest power down to the key byte:
+                let mut value = Zeroizing::new(vec![0; self.len()]);
+                for power in coefficients.chunks(self.len()).rev() {
+                    value.iter_mut()
+                        .zip(power)
+                        .for_each(|(value, coefficient)| {
+                            *value = gf256::mul_ct(*value, index) ^ coefficient
+                        });
+                }
+                KeyShare { threshold, index, value: Key::new(value.to_vec()) }
+            })
+            .collect();
+        Ok(shares)
+    }
+
+    /// Recovers a key split by [`split()`](Key::split). If more shares than the
+    /// threshold are given, only the first `threshold` ones are used.
+    ///
+    /// Shares of different keys with the same threshold and length cannot be
+    /// detected, they give a wrong key.
+    ///
+    /// # Returns
+    ///
+    /// - [`Ok`] with the recovered key
+    /// - [`Err`] with [`Error::InvalidParameters`] if there are fewer shares than
+    ///   the threshold, or the shares differ in threshold or length, or two shares
+    ///   have the same index
+    pub fn combine(shares: &[KeyShare]) -> Result<Key, Error> {
+        let first = shares.first()
+            .ok_or_else(|| Error::invalid_parameter("shares", "no share given"))?;
+        if shares.iter().any(|share| share.threshold != first.threshold) {
+            return Err(Error::invalid_parameter("shares", "shares have different thresholds"))
+        }
+        if shares.iter().any(|share| share.value.len() != first.value.len()) {
+            return Err(Error::invalid_parameter("shares", "shares have different lengths"))
+        }
+        if shares.len() < first.threshold as usize {
+            return Err(Error::invalid_parameter(
+                "shares",
+                format!("{} shares needed but {} given", first.threshold, shares.len())
+            ))
+        }
+        let shares = &shares[..first.threshold as usize];
+        for (idx, share) in shares.iter().enumerate() {
+            if shares[..idx].iter().any(|other| other.index == share.index) {
+                return Err(Error::invalid_parameter(
+                    "shares",
+                    format!("index {} is given twice", share.index)
+                ))
+            }
+        }
+
+        // Lagrange interpolation at zero; subtraction is XOR in GF(256). Shares
+        // are secret, so only the constant-time arithmetic is used:
+        let mut key = Zeroizing::new(vec![0; first.value.len()]);
+        for share in shares {
+            let basis = shares.iter()
+                .filter(|other| other.index != share.index)
+                .fold(1, |basis, other| {
+                    let factor = gf256::inv_ct(other.index ^ share.index);
+                    gf256::mul_ct(basis, gf256::mul_ct(other.index, factor))
+                });
+            key.iter_mut()
+                .zip(share.value.iter())
+                .for_each(|(byte, value)| *byte ^= gf256::mul_ct(basis, *value));
+        }
+        Ok(Key::new(key.to_vec()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_and_combine() {
+        let mut rng = rand::thread_rng();
+        let key = Key::new((0..=255).collect());
+        let shares = key.split(3, 5, &mut rng).unwrap();
+        assert_eq!(shares.len(), 5);
+        assert!(shares.iter().zip(1..).all(|(share, index)| share.index() == index));
+
+        // Any 3 shares in any order recover the key:
+        for combination in [[0, 1, 2], [4, 2, 0], [1, 3, 4], [3, 4, 2]] {
+            let chosen: Vec<KeyShare> = combination.iter()
+                .map(|&idx| shares[idx].clone())
+                .collect();
+            assert_eq!(Key::combine(&chosen).unwrap(), key);
+        }
+        assert_eq!(Key::combine(&shares).unwrap(), key);
+
+        // A single share is a copy of the key when threshold is one:
+        let shares = key.split(1, 2, &mut rng).unwrap();
+        assert_eq!(Key::combine(&shares[1..]).unwrap(), key);
+    }
+
+    #[test]
+    fn encoding() {
+        let key = Key::new(vec![1, 2, 3, 4]);
+        let shares = key.split(2, 3, &mut rand::thread_rng()).unwrap();
+        let decoded: Vec<KeyShare> = shares.iter()
+            .map(|share| share.encode().parse().unwrap())
+            .collect();
+        assert_eq!(decoded, shares);
+        let from_bytes = KeyShare::from_bytes(&shares[2].to_bytes()).unwrap();
+        assert_eq!(from_bytes, shares[2]);
+        assert_eq!(format!("{:?}", shares[0]), "KeyShare { threshold: 2, index: 1, .. }");
+
+        for bytes in [&[2, 1][..], &[0, 1, 5], &[2, 0, 5]] {
+            assert!(matches!(KeyShare::from_bytes(bytes), Err(Error::Malformed { .. })));
+        }
+        assert!("share:AgEF".parse::<KeyShare>().is_err());
+        assert!("mdcrypt-share:AgEF".parse::<KeyShare>().is_ok());
+    }
+
+    #[test]
+    fn invalid() {
+        let mut rng = rand::thread_rng();
+        let key = Key::new(vec![42; 16]);
+        assert!(key.split(0, 3, &mut rng).is_err());
+        assert!(key.split(4, 3, &mut rng).is_err());
+
+        let shares = key.split(3, 4, &mut rng).unwrap();
+        assert!(Key::combine(&[]).is_err());
+        assert!(Key::combine(&shares[..2]).is_err());
+        let duplicate = [shares[0].clone(), shares[1].clone(), shares[0].clone()];
+        assert!(Key::combine(&duplicate).is_err());
+        let other = Key::new(vec![42; 16]).split(2, 3, &mut rng).unwrap();
+        assert!(Key::combine(&[shares[0].clone(), shares[1].clone(), other[2].clone()]).is_err());
+    }
+}
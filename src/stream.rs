@@ -0,0 +1,325 @@
+use std::io;
+use std::io::Read;
+use std::io::Write;
+
+/// Size of chunks read from the underlying reader by [`DecryptReader`].
+const READ_CHUNK_LEN: usize = 8192;
+
+/// Represents a state of an encryption which is fed by chunks of data. The state
+/// carries everything needed across chunk boundaries, so that the output is the
+/// same no matter how the data are split into chunks.
+pub trait Encryptor {
+
+    /// Encrypts next chunk of data. Encrypted data are appended to `output`. The
+    /// implementor may keep some data in its state and output them later.
+    ///
+    /// # Parameters
+    ///
+    /// - `input`: next chunk of data to encrypt
+    /// - `output`: vector to append encrypted data to
+    fn update(&mut self, input: &[u8], output: &mut Vec<u8>) -> io::Result<()>;
+
+    /// Finishes encryption and appends the rest of encrypted data to `output`.
+    ///
+    /// # Parameters
+    ///
+    /// - `output`: vector to append encrypted data to
+    fn finish(self, output: &mut Vec<u8>) -> io::Result<()>;
+}
+
+/// Represents a state of a decryption which is fed by chunks of encrypted data.
+/// The state carries everything needed across chunk boundaries, so that the
+/// output is the same no matter how the data are split into chunks.
+pub trait Decryptor {
+
+    /// Decrypts next chunk of encrypted data. Decrypted data are appended to
+    /// `output`. The implementor may keep some data in its state and output them
+    /// later.
+    ///
+    /// # Parameters
+    ///
+    /// - `input`: next chunk of data to decrypt
+    /// - `output`: vector to append decrypted data to
+    fn update(&mut self, input: &[u8], output: &mut Vec<u8>) -> io::Result<()>;
+
+    /// Finishes decryption and appends the rest of decrypted data to `output`.
+    /// This is the place to report that the encrypted data are truncated.
+    ///
+    /// # Parameters
+    ///
+    /// - `output`: vector to append decrypted data to
+    fn finish(self, output: &mut Vec<u8>) -> io::Result<()>;
+}
+
+/// Represents an algorithm that can encrypt data in chunks using constant memory.
+/// See [`EncryptWriter`] for encrypting into a [`Write`] implementor.
+pub trait StreamEncrypt {
+
+    /// The state of encryption.
+    type Encryptor<'a>: Encryptor where Self: 'a;
+
+    /// Creates a new encryption state with nothing encrypted yet.
+    fn encryptor(&self) -> Self::Encryptor<'_>;
+}
+
+/// Represents an algorithm that can decrypt data in chunks using constant memory.
+/// See [`DecryptReader`] for decrypting from a [`Read`] implementor.
+pub trait StreamDecrypt {
+
+    /// The state of decryption.
+    type Decryptor<'a>: Decryptor where Self: 'a;
+
+    /// Creates a new decryption state with nothing decrypted yet.
+    fn decryptor(&self) -> Self::Decryptor<'_>;
+}
+
+/// A [`Write`] adapter which encrypts everything written into it and writes
+/// encrypted data into the underlying writer. Some algorithms output data only
+/// when they are finished, so [`finish`] must be called after the last write.
+/// Dropping the writer without calling [`finish`] will lose the end of the
+/// encrypted data.
+///
+/// [`finish`]: EncryptWriter::finish
+pub struct EncryptWriter<E, W>
+where
+    E: Encryptor,
+    W: Write,
+{
+    encryptor: E,
+    inner: W,
+    buffer: Vec<u8>,
+}
+
+impl<E, W> EncryptWriter<E, W>
+where
+    E: Encryptor,
+    W: Write,
+{
+    /// Creates a new [`EncryptWriter`] instance.
+    ///
+    /// # Parameters
+    ///
+    /// - `algorithm`: algorithm to encrypt with
+    /// - `inner`: writer to write encrypted data into
+    pub fn new<'a, A>(algorithm: &'a A, inner: W) -> Self
+    where A: StreamEncrypt<Encryptor<'a> = E> {
+        Self { encryptor: algorithm.encryptor(), inner, buffer: Vec::new() }
+    }
+
+    /// Returns a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Finishes encryption, writes the rest of encrypted data and flushes the
+    /// underlying writer.
+    ///
+    /// # Returns
+    ///
+    /// The underlying writer.
+    pub fn finish(self) -> io::Result<W> {
+        let Self { encryptor, mut inner, mut buffer } = self;
+        buffer.clear();
+        encryptor.finish(&mut buffer)?;
+        inner.write_all(&buffer)?;
+        inner.flush()?;
+        Ok(inner)
+    }
+}
+
+impl<E, W> Write for EncryptWriter<E, W>
+where
+    E: Encryptor,
+    W: Write,
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // Encrypt into our buffer and pass everything to the underlying writer:
+        self.buffer.clear();
+        self.encryptor.update(buf, &mut self.buffer)?;
+        self.inner.write_all(&self.buffer)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// A [`Read`] adapter which reads encrypted data from the underlying reader and
+/// returns them decrypted.
+pub struct DecryptReader<D, R>
+where
+    D: Decryptor,
+    R: Read,
+{
+    decryptor: Option<D>,
+    inner: R,
+    chunk: Vec<u8>,
+    buffer: Vec<u8>,
+    position: usize,
+}
+
+impl<D, R> DecryptReader<D, R>
+where
+    D: Decryptor,
+    R: Read,
+{
+    /// Creates a new [`DecryptReader`] instance.
+    ///
+    /// # Parameters
+    ///
+    /// - `algorithm`: algorithm to decrypt with
+    /// - `inner`: reader to read encrypted data from
+    pub fn new<'a, A>(algorithm: &'a A, inner: R) -> Self
+    where A: StreamDecrypt<Decryptor<'a> = D> {
+        Self {
+            decryptor: Some(algorithm.decryptor()),
+            inner,
+            chunk: vec![0; READ_CHUNK_LEN],
+            buffer: Vec::new(),
+            position: 0,
+        }
+    }
+
+    /// Returns a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns the underlying reader. Data which were already read from it, but
+    /// not returned yet, are lost.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<D, R> Read for DecryptReader<D, R>
+where
+    D: Decryptor,
+    R: Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            // If we have something decrypted, return it:
+            if self.position < self.buffer.len() {
+                let len = buf.len().min(self.buffer.len() - self.position);
+                buf[..len].copy_from_slice(&self.buffer[self.position..self.position + len]);
+                self.position += len;
+                return Ok(len)
+            }
+            // Otherwise decrypt next chunk. If decryption is finished, there is
+            // nothing more to return:
+            self.buffer.clear();
+            self.position = 0;
+            let read_len = match self.decryptor {
+                Some(_) => self.inner.read(&mut self.chunk)?,
+                None    => return Ok(0)
+            };
+            match read_len {
+                0   => self.decryptor.take().unwrap().finish(&mut self.buffer)?,
+                len => self.decryptor.as_mut().unwrap().update(&self.chunk[..len], &mut self.buffer)?
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::HammingECC;
+    use crate::algorithms::Sha256;
+    use crate::algorithms::Vigener;
+    use crate::Encrypt;
+    use crate::Key;
+    use crate::TryEncrypt;
+
+    /// Writes `data` into an [`EncryptWriter`] in chunks of given length.
+    fn encrypt_chunked<A>(algorithm: &A, data: &[u8], chunk_len: usize) -> Vec<u8>
+    where A: StreamEncrypt {
+        let mut writer = EncryptWriter::new(algorithm, Vec::new());
+        for chunk in data.chunks(chunk_len) {
+            writer.write_all(chunk).unwrap();
+        }
+        writer.finish().unwrap()
+    }
+
+    /// Reads everything from a [`DecryptReader`] in chunks of given length.
+    fn decrypt_chunked<A>(algorithm: &A, data: &[u8], chunk_len: usize) -> io::Result<Vec<u8>>
+    where A: StreamDecrypt {
+        let mut reader = DecryptReader::new(algorithm, data);
+        let mut result = Vec::new();
+        let mut chunk = vec![0; chunk_len];
+        loop {
+            match reader.read(&mut chunk)? {
+                0   => return Ok(result),
+                len => result.extend_from_slice(&chunk[..len])
+            }
+        }
+    }
+
+    #[test]
+    fn vigener() {
+        let vigener = Vigener::new(Key::new(vec![3, 141, 59, 26, 5]));
+        let data: Vec<u8> = (0..=255).cycle().take(20000).collect();
+        let expected: Vec<u8> = vigener.encrypt(data.clone());
+        for chunk_len in [1, 3, 7, 4096, 100000] {
+            let encrypted = encrypt_chunked(&vigener, &data, chunk_len);
+            assert_eq!(encrypted, expected);
+            assert_eq!(decrypt_chunked(&vigener, &encrypted, chunk_len).unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn sha2() {
+        let data: Vec<u8> = (0..=255).cycle().take(20000).collect();
+        let expected: Vec<u8> = Sha256::default().encrypt(data.clone());
+        for chunk_len in [1, 64, 1000] {
+            assert_eq!(encrypt_chunked(&Sha256::default(), &data, chunk_len), expected);
+        }
+    }
+
+    #[test]
+    fn hamming_single_frame() {
+        // Data shorter than a frame are encoded the same way as by `try_encrypt`:
+        let hamming = HammingECC::new(5, 12).unwrap();
+        for len in [0, 1, 1000, 4094] {
+            let data: Vec<u8> = (0..=255).cycle().take(len).collect();
+            let expected: Vec<u8> = hamming.try_encrypt(data.clone()).unwrap();
+            let encrypted = encrypt_chunked(&hamming, &data, 100);
+            assert_eq!(encrypted, expected);
+            assert_eq!(decrypt_chunked(&hamming, &encrypted, 33).unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn hamming_multiple_frames() {
+        let hamming = HammingECC::new(4, 6).unwrap();
+        for len in [63, 126, 127, 1000] {
+            let data: Vec<u8> = (0..=255).cycle().take(len).collect();
+            let encrypted = encrypt_chunked(&hamming, &data, 10);
+            assert_eq!(decrypt_chunked(&hamming, &encrypted, 1).unwrap(), data);
+            assert_eq!(decrypt_chunked(&hamming, &encrypted, 1000).unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn hamming_truncated() {
+        let hamming = HammingECC::new(4, 6).unwrap();
+        let data: Vec<u8> = (0..=255).cycle().take(200).collect();
+        let encrypted = encrypt_chunked(&hamming, &data, 10);
+        assert!(decrypt_chunked(&hamming, &encrypted[..encrypted.len() - 5], 10).is_err());
+        assert!(decrypt_chunked(&hamming, &[], 10).is_err());
+
+        // Data of two whole frames end with an empty frame, which must not be
+        // missing:
+        let data: Vec<u8> = (0..=255).cycle().take(126).collect();
+        let encrypted = encrypt_chunked(&hamming, &data, 10);
+        let encoded_len = |len| hamming.try_encrypt::<_, Vec<u8>>(vec![0; len]).unwrap().len();
+        let frame_len = encoded_len(63);
+        assert_eq!(encrypted.len(), 2 * frame_len + encoded_len(0));
+        for cut in [frame_len, 2 * frame_len] {
+            let result = decrypt_chunked(&hamming, &encrypted[..cut], 10);
+            assert_eq!(result.unwrap_err().kind(), io::ErrorKind::UnexpectedEof);
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use crate::Decrypt;
+use crate::EncodingErrorKind;
+use crate::Encrypt;
+use crate::Error;
+use crate::TryDecrypt;
+
+/// Parses bytes written in hexadecimal, as test vectors usually are.
+///
+/// # Parameters
+///
+/// - `text`: even number of hexadecimal digits
+pub(crate) fn hex(text: &str) -> Vec<u8> {
+    (0..text.len()).step_by(2)
+        .map(|idx| u8::from_str_radix(&text[idx..idx + 2], 16).unwrap())
+        .collect()
+}
+
+/// Writes bytes in lowercase hexadecimal, as digests are usually written.
+///
+/// # Parameters
+///
+/// - `bytes`: bytes to write
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Hashes the data and writes the digest in lowercase hexadecimal.
+///
+/// # Parameters
+///
+/// - `hasher`: hash function to test
+/// - `data`: data to hash
+pub(crate) fn digest_hex<H, D>(hasher: &H, data: D) -> String
+where
+    H:           Encrypt,
+    D:           IntoIterator<Item = u8>,
+    D::IntoIter: ExactSizeIterator,
+{
+    to_hex(&hasher.encrypt::<D, Vec<u8>>(data))
+}
+
+/// Encrypts the data, checks that they are decrypted back and returns the
+/// encrypted data.
+///
+/// # Parameters
+///
+/// - `cipher`: cipher to test
+/// - `data`: data to encrypt
+pub(crate) fn round_trip<C: Encrypt + Decrypt>(cipher: &C, data: &[u8]) -> Vec<u8> {
+    let encrypted: Vec<u8> = cipher.encrypt(data.to_vec());
+    let decrypted: Vec<u8> = cipher.decrypt(encrypted.clone());
+    assert_eq!(decrypted, data);
+    encrypted
+}
+
+/// Same as [`round_trip()`] for ciphers of text, which keep the text valid
+/// UTF-8.
+pub(crate) fn round_trip_text<C: Encrypt + Decrypt>(cipher: &C, text: &str) -> String {
+    String::from_utf8(round_trip(cipher, text.as_bytes())).unwrap()
+}
+
+/// Checks that decoding of every text fails with [`Error::InvalidEncoding`] of
+/// given kind at given position.
+///
+/// # Parameters
+///
+/// - `codec`: encoding to test
+/// - `cases`: invalid texts with the expected positions and kinds
+pub(crate) fn assert_invalid_encoding<C>(codec: &C, cases: &[(&str, usize, EncodingErrorKind)])
+where C: TryDecrypt<ErrorType = Error> {
+    for &(text, position, kind) in cases {
+        match codec.try_decrypt::<_, Vec<u8>>(text.bytes()) {
+            Err(Error::InvalidEncoding { position: actual_position, kind: actual_kind, .. }) => {
+                assert_eq!((actual_position, actual_kind), (position, kind), "{}", text)
+            },
+            result => panic!("{}: {:?}", text, result),
+        }
+    }
+}